@@ -0,0 +1,958 @@
+//! A language server that exposes Typst's IDE capabilities to editors.
+//!
+//! Speaks the Language Server Protocol through JSON-RPC messages on standard
+//! input and output.
+
+use std::collections::{HashMap, HashSet};
+use std::fs;
+use std::io::{self, BufRead, Read, StdinLock, Stdout, Write};
+use std::ops::Range;
+use std::path::{Path, PathBuf};
+
+use serde_json::{json, Value as Json};
+use typst::diag::{Severity, SourceDiagnostic, StrResult};
+use typst::doc::{Document, Frame};
+use typst::eval::Tracer;
+use typst::ide::{self, CompletionKind, Tag, Tooltip};
+use typst::syntax::ast::{self, AstNode};
+use typst::syntax::{LinkedNode, Source, SourceId, Span, SyntaxKind};
+use typst::util::PathExt;
+use typst::World;
+
+use crate::{LspCommand, SystemWorld, MAX_CACHE_AGE};
+
+/// The semantic token types, indexed by `token_type`.
+const TOKEN_TYPES: &[&str] = &[
+    "comment",
+    "punctuation",
+    "escape",
+    "strong",
+    "emph",
+    "link",
+    "raw",
+    "label",
+    "ref",
+    "heading",
+    "marker",
+    "term",
+    "delim",
+    "operator",
+    "keyword",
+    "number",
+    "string",
+    "function",
+    "interpolated",
+];
+
+/// The JSON-RPC error code for unknown methods.
+const METHOD_NOT_FOUND: i32 = -32601;
+
+/// The JSON-RPC error code for malformed parameters.
+const INVALID_PARAMS: i32 = -32602;
+
+/// The LSP error code for requests before initialization.
+const SERVER_NOT_INITIALIZED: i32 = -32002;
+
+/// The result of a request: either a result value or an error code and
+/// message.
+type Response = Result<Json, (i32, String)>;
+
+/// Serve requests until the client exits.
+pub fn serve(command: LspCommand) -> StrResult<()> {
+    let mut conn = Connection::new();
+
+    // Requests are only allowed after the client introduced itself.
+    let (id, params) = loop {
+        let Some(message) = conn.receive()? else { return Ok(()) };
+        match (message.method(), message.0.get("id")) {
+            ("initialize", Some(id)) => break (id.clone(), message.params().clone()),
+            ("exit", _) => return Ok(()),
+            (_, Some(id)) => conn.respond(
+                id.clone(),
+                Err((SERVER_NOT_INITIALIZED, "server is not initialized".into())),
+            )?,
+            (_, None) => {}
+        }
+    };
+
+    let root = command
+        .root
+        .or_else(|| params["rootUri"].as_str().and_then(uri_to_path))
+        .or_else(|| params["rootPath"].as_str().map(PathBuf::from))
+        .or_else(|| std::env::current_dir().ok())
+        .unwrap_or_default();
+
+    let mut server = Server {
+        world: SystemWorld::new(root, &command.font_paths, None),
+        document: None,
+        published: HashSet::new(),
+    };
+
+    conn.respond(id, Ok(capabilities()))?;
+
+    let mut shutdown = false;
+    while let Some(message) = conn.receive()? {
+        let params = message.params();
+        match (message.method(), message.0.get("id")) {
+            ("shutdown", Some(id)) => {
+                shutdown = true;
+                conn.respond(id.clone(), Ok(Json::Null))?;
+            }
+            ("exit", _) => break,
+            (method, Some(id)) => {
+                let response = server.request(method, params);
+                conn.respond(id.clone(), response)?;
+            }
+            (method, None) => {
+                for (uri, diagnostics) in server.notify(method, params) {
+                    conn.notify(
+                        "textDocument/publishDiagnostics",
+                        json!({ "uri": uri, "diagnostics": diagnostics }),
+                    )?;
+                }
+            }
+        }
+    }
+
+    // The client is supposed to shut the server down before exiting.
+    if !shutdown {
+        std::process::exit(1);
+    }
+
+    Ok(())
+}
+
+/// What the server can do.
+fn capabilities() -> Json {
+    json!({
+        "capabilities": {
+            // Incremental text synchronization.
+            "textDocumentSync": 2,
+            "completionProvider": {
+                "triggerCharacters": ["#", ".", "@", "(", ",", ":", " "],
+            },
+            "hoverProvider": true,
+            "definitionProvider": true,
+            "referencesProvider": true,
+            "documentSymbolProvider": true,
+            "semanticTokensProvider": {
+                "legend": { "tokenTypes": TOKEN_TYPES, "tokenModifiers": [] },
+                "full": true,
+            },
+        },
+        "serverInfo": { "name": "typst", "version": env!("TYPST_VERSION") },
+    })
+}
+
+/// The state of the language server.
+struct Server {
+    /// Holds the documents that are open in the editor and everything else
+    /// that compilation needs.
+    world: SystemWorld,
+    /// The last successfully compiled document, which completions and
+    /// tooltips use for introspection.
+    document: Option<Document>,
+    /// The files for which diagnostics were published last.
+    published: HashSet<PathBuf>,
+}
+
+impl Server {
+    /// Handle a request.
+    fn request(&mut self, method: &str, params: &Json) -> Response {
+        let invalid = || (INVALID_PARAMS, "invalid parameters".to_string());
+        let result = match method {
+            "textDocument/completion" => self.complete(params).ok_or_else(invalid)?,
+            "textDocument/hover" => self.hover(params).ok_or_else(invalid)?,
+            "textDocument/definition" => self.definition(params).ok_or_else(invalid)?,
+            "textDocument/references" => self.references(params).ok_or_else(invalid)?,
+            "textDocument/documentSymbol" => self.symbols(params).ok_or_else(invalid)?,
+            "textDocument/semanticTokens/full" => {
+                self.tokens(params).ok_or_else(invalid)?
+            }
+            _ => return Err((METHOD_NOT_FOUND, format!("unknown method {method}"))),
+        };
+        Ok(result)
+    }
+
+    /// Handle a notification and return diagnostics to publish.
+    fn notify(&mut self, method: &str, params: &Json) -> Vec<(String, Json)> {
+        let document = &params["textDocument"];
+        let Some(path) = document["uri"].as_str().and_then(uri_to_path) else {
+            return vec![];
+        };
+
+        let id = match method {
+            "textDocument/didOpen" => {
+                let text = document["text"].as_str().unwrap_or_default();
+                self.world.pin(&path, text.into())
+            }
+            "textDocument/didChange" => {
+                let Some(id) = self.world.pinned_id(&path) else { return vec![] };
+                let changes = params["contentChanges"].as_array();
+                for change in changes.into_iter().flatten() {
+                    self.change(id, change);
+                }
+                id
+            }
+            "textDocument/didClose" => {
+                // The file on disk is authoritative again.
+                if let Some(id) = self.world.pinned_id(&path) {
+                    if let Ok(text) = fs::read_to_string(&path) {
+                        self.world.source_mut(id).replace(text);
+                    }
+                }
+                return vec![];
+            }
+            _ => return vec![],
+        };
+
+        self.compile(id)
+    }
+
+    /// Apply one change to an open document.
+    fn change(&mut self, id: SourceId, change: &Json) {
+        let text = change["text"].as_str().unwrap_or_default();
+        let source = self.world.source_mut(id);
+        if change["range"].is_null() {
+            source.replace(text.into());
+        } else if let Some(range) = to_offsets(source, &change["range"]) {
+            source.edit(range, text);
+        }
+    }
+
+    /// Compile the document with the given main file and return its
+    /// diagnostics, grouped by file.
+    fn compile(&mut self, main: SourceId) -> Vec<(String, Json)> {
+        self.world.reset();
+        self.world.main = main;
+
+        let mut tracer = Tracer::default();
+        let result = typst::compile(&self.world, &mut tracer);
+        let mut diagnostics = tracer.warnings().to_vec();
+        match result {
+            Ok(document) => self.document = Some(document),
+            Err(errors) => diagnostics.extend(*errors),
+        }
+
+        // Like in watch mode, drop the memoized results that this compilation
+        // didn't reuse.
+        comemo::evict(MAX_CACHE_AGE);
+
+        let mut grouped: HashMap<PathBuf, Vec<Json>> = HashMap::new();
+        for diagnostic in diagnostics {
+            if diagnostic.span.is_detached() {
+                continue;
+            }
+
+            let source = self.world.source(diagnostic.span.source());
+            let json = to_diagnostic(&self.world, source, &diagnostic);
+            grouped.entry(source.path().into()).or_default().push(json);
+        }
+
+        // Clear the diagnostics of files that no longer have any.
+        for path in self.published.drain() {
+            grouped.entry(path).or_default();
+        }
+
+        self.published = grouped
+            .iter()
+            .filter(|(_, diagnostics)| !diagnostics.is_empty())
+            .map(|(path, _)| path.clone())
+            .collect();
+
+        grouped
+            .into_iter()
+            .map(|(path, diagnostics)| (path_to_uri(&path), Json::Array(diagnostics)))
+            .collect()
+    }
+
+    /// Find the open document and the cursor position of a request.
+    fn locate(&self, params: &Json) -> Option<(&Source, usize)> {
+        let path = uri_to_path(params["textDocument"]["uri"].as_str()?)?;
+        let source = self.world.source(self.world.pinned_id(&path)?);
+        let cursor = to_offset(source, &params["position"])?;
+        Some((source, cursor))
+    }
+
+    /// The pages of the last compiled document.
+    fn frames(&self) -> &[Frame] {
+        self.document.as_ref().map_or(&[], |document| &document.pages)
+    }
+
+    /// Complete the code at the cursor.
+    fn complete(&self, params: &Json) -> Option<Json> {
+        let (source, cursor) = self.locate(params)?;
+
+        // Trigger kind 1 means that the user explicitly asked for completions.
+        let explicit = params["context"]["triggerKind"].as_u64() == Some(1);
+        let Some((from, completions)) =
+            ide::autocomplete(&self.world, self.frames(), source, cursor, explicit)
+        else {
+            return Some(Json::Null);
+        };
+
+        let range = to_range(source, from..cursor);
+        let items: Vec<_> = completions
+            .into_iter()
+            .map(|completion| {
+                let kind = match completion.kind {
+                    CompletionKind::Syntax => 15,
+                    CompletionKind::Func => 3,
+                    CompletionKind::Param => 6,
+                    CompletionKind::Constant => 21,
+                    CompletionKind::Symbol(_) => 1,
+                };
+                let apply = completion.apply.as_deref().unwrap_or(&completion.label);
+                json!({
+                    "label": completion.label.as_str(),
+                    "kind": kind,
+                    "detail": completion.detail.as_deref(),
+                    // Format 2 means snippet syntax.
+                    "insertTextFormat": 2,
+                    "textEdit": { "range": range, "newText": to_snippet(apply) },
+                })
+            })
+            .collect();
+
+        Some(json!({ "isIncomplete": false, "items": items }))
+    }
+
+    /// Describe the item under the cursor.
+    fn hover(&self, params: &Json) -> Option<Json> {
+        let (source, cursor) = self.locate(params)?;
+        let value = match ide::tooltip(&self.world, self.frames(), source, cursor) {
+            Some(Tooltip::Text(text)) => text.to_string(),
+            Some(Tooltip::Code(code)) => format!("```typst\n{code}\n```"),
+            None => return Some(Json::Null),
+        };
+        Some(json!({ "contents": { "kind": "markdown", "value": value } }))
+    }
+
+    /// Find the definition of the identifier, label or import under the
+    /// cursor.
+    fn definition(&self, params: &Json) -> Option<Json> {
+        let (source, cursor) = self.locate(params)?;
+        let Some(leaf) = LinkedNode::new(source.root()).leaf_at(cursor) else {
+            return Some(Json::Null);
+        };
+
+        let location = match leaf.kind() {
+            SyntaxKind::Str
+                if matches!(
+                    leaf.parent_kind(),
+                    Some(SyntaxKind::ModuleImport | SyntaxKind::ModuleInclude)
+                ) =>
+            {
+                leaf.cast::<ast::Str>()
+                    .and_then(|path| self.find_file(source, &path.get()))
+            }
+            _ => ide::definition(&self.world, source, cursor)
+                .and_then(|span| self.to_location(span)),
+        };
+
+        Some(location.unwrap_or(Json::Null))
+    }
+
+    /// Find all uses of the binding or label under the cursor.
+    fn references(&self, params: &Json) -> Option<Json> {
+        let (source, cursor) = self.locate(params)?;
+        let declaration =
+            params["context"]["includeDeclaration"].as_bool() != Some(false);
+        let definition = ide::definition(&self.world, source, cursor);
+        let locations: Vec<_> = ide::references(&self.world, source, cursor)
+            .into_iter()
+            .filter(|&span| declaration || Some(span) != definition)
+            .filter_map(|span| self.to_location(span))
+            .collect();
+        Some(Json::Array(locations))
+    }
+
+    /// Convert a span into an LSP location.
+    fn to_location(&self, span: Span) -> Option<Json> {
+        if span.is_detached() {
+            return None;
+        }
+
+        let source = self.world.source(span.source());
+        Some(json!({
+            "uri": path_to_uri(source.path()),
+            "range": to_range(source, source.range(span)),
+        }))
+    }
+
+    /// Resolve an imported or included file.
+    ///
+    /// Package imports are not resolved.
+    fn find_file(&self, source: &Source, path: &str) -> Option<Json> {
+        if path.starts_with('@') || source.package().is_some() {
+            return None;
+        }
+
+        let full = match path.strip_prefix('/') {
+            Some(path) => self.world.root().join(path),
+            None => source.path().parent()?.join(path),
+        };
+
+        let full = full.normalize();
+        full.is_file().then(|| {
+            let start = json!({ "line": 0, "character": 0 });
+            json!({
+                "uri": path_to_uri(&full),
+                "range": { "start": start, "end": start },
+            })
+        })
+    }
+
+    /// List the headings of a document as nested symbols.
+    fn symbols(&self, params: &Json) -> Option<Json> {
+        let path = uri_to_path(params["textDocument"]["uri"].as_str()?)?;
+        let source = self.world.source(self.world.pinned_id(&path)?);
+
+        let mut headings = vec![];
+        collect_headings(&LinkedNode::new(source.root()), &mut headings);
+        Some(Json::Array(nest_headings(source, &headings, source.text().len())))
+    }
+
+    /// Classify the tokens of a document for semantic highlighting.
+    fn tokens(&self, params: &Json) -> Option<Json> {
+        let path = uri_to_path(params["textDocument"]["uri"].as_str()?)?;
+        let source = self.world.source(self.world.pinned_id(&path)?);
+
+        let mut tokens = vec![];
+        collect_tokens(&LinkedNode::new(source.root()), None, &mut tokens);
+
+        // Each token is encoded as five numbers, with its position relative to
+        // the previous token.
+        let mut data = vec![];
+        let (mut prev_line, mut prev_column) = (0, 0);
+        for (range, tag) in tokens {
+            let mut start = range.start;
+            while start < range.end {
+                // Tokens may not span multiple lines.
+                let line = source.byte_to_line(start)?;
+                let end = source
+                    .line_to_byte(line + 1)
+                    .map_or(range.end, |next| next.min(range.end));
+                let text = source.get(start..end)?.trim_end_matches(['\n', '\r']);
+                if !text.is_empty() {
+                    let column = utf16_column(source, start)?;
+                    let delta =
+                        if line == prev_line { column - prev_column } else { column };
+                    data.extend([
+                        line - prev_line,
+                        delta,
+                        text.encode_utf16().count(),
+                        token_type(tag),
+                        0,
+                    ]);
+                    prev_line = line;
+                    prev_column = column;
+                }
+                start = end;
+            }
+        }
+
+        Some(json!({ "data": data }))
+    }
+}
+
+/// Reads and writes JSON-RPC messages.
+struct Connection {
+    input: StdinLock<'static>,
+    output: Stdout,
+}
+
+/// A message from the client.
+struct Message(Json);
+
+impl Message {
+    /// The method of a request or notification.
+    fn method(&self) -> &str {
+        self.0["method"].as_str().unwrap_or_default()
+    }
+
+    /// The parameters of a request or notification.
+    fn params(&self) -> &Json {
+        &self.0["params"]
+    }
+}
+
+impl Connection {
+    fn new() -> Self {
+        Self { input: io::stdin().lock(), output: io::stdout() }
+    }
+
+    /// Receive the next message or `None` if the client disconnected.
+    fn receive(&mut self) -> StrResult<Option<Message>> {
+        let err = |_| "failed to read message";
+        let mut length = None;
+        loop {
+            let mut header = String::new();
+            if self.input.read_line(&mut header).map_err(err)? == 0 {
+                return Ok(None);
+            }
+
+            let header = header.trim_end();
+            if header.is_empty() {
+                break;
+            }
+
+            if let Some(value) = header.strip_prefix("Content-Length:") {
+                length =
+                    Some(value.trim().parse().map_err(|_| "invalid content length")?);
+            }
+        }
+
+        let mut body = vec![0; length.ok_or("missing content length")?];
+        self.input.read_exact(&mut body).map_err(err)?;
+        let json = serde_json::from_slice(&body).map_err(|_| "invalid message")?;
+        Ok(Some(Message(json)))
+    }
+
+    /// Respond to a request.
+    fn respond(&mut self, id: Json, response: Response) -> StrResult<()> {
+        self.send(match response {
+            Ok(result) => json!({ "jsonrpc": "2.0", "id": id, "result": result }),
+            Err((code, message)) => json!({
+                "jsonrpc": "2.0",
+                "id": id,
+                "error": { "code": code, "message": message },
+            }),
+        })
+    }
+
+    /// Send a notification.
+    fn notify(&mut self, method: &str, params: Json) -> StrResult<()> {
+        self.send(json!({ "jsonrpc": "2.0", "method": method, "params": params }))
+    }
+
+    /// Write a message.
+    fn send(&mut self, message: Json) -> StrResult<()> {
+        let body = message.to_string();
+        let mut w = self.output.lock();
+        write!(w, "Content-Length: {}\r\n\r\n{body}", body.len())
+            .and_then(|_| w.flush())
+            .map_err(|_| "failed to write message".into())
+    }
+}
+
+/// Convert a diagnostic into its LSP representation.
+fn to_diagnostic(
+    world: &SystemWorld,
+    source: &Source,
+    diagnostic: &SourceDiagnostic,
+) -> Json {
+    let mut message = diagnostic.message.to_string();
+    for hint in &diagnostic.hints {
+        message.push_str("\nhint: ");
+        message.push_str(hint);
+    }
+
+    let severity = match diagnostic.severity {
+        Severity::Error => 1,
+        Severity::Warning => 2,
+    };
+
+    json!({
+        "range": to_range(source, diagnostic.range(world)),
+        "severity": severity,
+        "source": "typst",
+        "message": message,
+    })
+}
+
+/// Convert an LSP position into a byte offset.
+fn to_offset(source: &Source, position: &Json) -> Option<usize> {
+    let line = position["line"].as_u64()? as usize;
+    let character = position["character"].as_u64()? as usize;
+    let start = source.byte_to_utf16(source.line_to_byte(line)?)?;
+    source.utf16_to_byte(start + character)
+}
+
+/// Convert an LSP range into a byte range.
+fn to_offsets(source: &Source, range: &Json) -> Option<Range<usize>> {
+    Some(to_offset(source, &range["start"])?..to_offset(source, &range["end"])?)
+}
+
+/// Convert a byte offset into an LSP position.
+fn to_position(source: &Source, offset: usize) -> Json {
+    let line = source.byte_to_line(offset).unwrap_or_default();
+    let character = utf16_column(source, offset).unwrap_or_default();
+    json!({ "line": line, "character": character })
+}
+
+/// Convert a byte range into an LSP range.
+fn to_range(source: &Source, range: Range<usize>) -> Json {
+    json!({
+        "start": to_position(source, range.start),
+        "end": to_position(source, range.end),
+    })
+}
+
+/// The UTF-16 column of a byte offset, which is how LSP counts columns.
+fn utf16_column(source: &Source, offset: usize) -> Option<usize> {
+    let line = source.byte_to_line(offset)?;
+    let start = source.line_to_byte(line)?;
+    Some(source.byte_to_utf16(offset)? - source.byte_to_utf16(start)?)
+}
+
+/// Convert a `file` URI into a path.
+fn uri_to_path(uri: &str) -> Option<PathBuf> {
+    let path = uri.strip_prefix("file://")?;
+    let mut bytes = vec![];
+    let mut rest = path.as_bytes();
+    while let Some((&b, tail)) = rest.split_first() {
+        if b == b'%' && tail.len() >= 2 {
+            let hex = std::str::from_utf8(&tail[..2]).ok()?;
+            bytes.push(u8::from_str_radix(hex, 16).ok()?);
+            rest = &tail[2..];
+        } else {
+            bytes.push(b);
+            rest = tail;
+        }
+    }
+
+    let decoded = String::from_utf8(bytes).ok()?;
+
+    // Windows paths start with a drive letter: `file:///C:/...`.
+    let decoded = match decoded.strip_prefix('/') {
+        Some(rest) if cfg!(windows) => rest.to_string(),
+        _ => decoded,
+    };
+
+    Some(PathBuf::from(decoded))
+}
+
+/// Convert a path into a `file` URI.
+fn path_to_uri(path: &Path) -> String {
+    let mut uri = String::from("file://");
+    let path = path.to_string_lossy().replace('\\', "/");
+    if !path.starts_with('/') {
+        uri.push('/');
+    }
+
+    for b in path.bytes() {
+        match b {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'.' | b'_' | b'~' => {
+                uri.push(b as char)
+            }
+            b'/' | b':' => uri.push(b as char),
+            _ => uri.push_str(&format!("%{b:02X}")),
+        }
+    }
+
+    uri
+}
+
+/// Convert Typst's snippet syntax into LSP's snippet syntax, which numbers its
+/// placeholders.
+///
+/// Typst's `${lhs} + ${rhs}` becomes `${1:lhs} + ${2:rhs}`.
+fn to_snippet(apply: &str) -> String {
+    let mut snippet = String::new();
+    let mut rest = apply;
+    let mut i = 1;
+    while let Some(start) = rest.find("${") {
+        let Some(len) = rest[start..].find('}') else { break };
+        let name = &rest[start + 2..start + len];
+        snippet.push_str(&rest[..start]);
+        if name.is_empty() {
+            snippet.push_str(&format!("${i}"));
+        } else {
+            snippet.push_str(&format!("${{{i}:{name}}}"));
+        }
+        rest = &rest[start + len + 1..];
+        i += 1;
+    }
+    snippet.push_str(rest);
+    snippet
+}
+
+/// Collect the level, title and range of each heading in a tree.
+fn collect_headings(
+    node: &LinkedNode,
+    headings: &mut Vec<(usize, String, Range<usize>)>,
+) {
+    if let Some(heading) = node.cast::<ast::Heading>() {
+        let title = heading.body().as_untyped().clone().into_text();
+        headings.push((heading.level().get(), title.trim().into(), node.range()));
+        return;
+    }
+
+    for child in node.children() {
+        collect_headings(&child, headings);
+    }
+}
+
+/// Nest headings into document symbols. A heading's section extends up to
+/// the next heading of the same or a higher level.
+fn nest_headings(
+    source: &Source,
+    headings: &[(usize, String, Range<usize>)],
+    end: usize,
+) -> Vec<Json> {
+    let mut symbols = vec![];
+    let mut i = 0;
+    while i < headings.len() {
+        let (level, title, range) = &headings[i];
+        let mut j = i + 1;
+        while j < headings.len() && headings[j].0 > *level {
+            j += 1;
+        }
+
+        let section_end = headings.get(j).map_or(end, |next| next.2.start);
+        symbols.push(json!({
+            "name": if title.is_empty() { "(empty)" } else { title.as_str() },
+            // The kind for strings, as markdown headings are shown.
+            "kind": 15,
+            "range": to_range(source, range.start..section_end),
+            "selectionRange": to_range(source, range.clone()),
+            "children": nest_headings(source, &headings[i + 1..j], section_end),
+        }));
+        i = j;
+    }
+    symbols
+}
+
+/// Collect the highlighted leaves of a tree. Leaves inherit the highlighting of
+/// their closest highlighted ancestor, like the text of a heading.
+fn collect_tokens(
+    node: &LinkedNode,
+    inherited: Option<Tag>,
+    tokens: &mut Vec<(Range<usize>, Tag)>,
+) {
+    let tag = ide::highlight(node).or(inherited);
+    if node.get().children().len() == 0 {
+        if let Some(tag) = tag.filter(|&tag| tag != Tag::Error) {
+            tokens.push((node.range(), tag));
+        }
+        return;
+    }
+
+    for child in node.children() {
+        collect_tokens(&child, tag, tokens);
+    }
+}
+
+/// The index of a highlighting tag in `TOKEN_TYPES`.
+fn token_type(tag: Tag) -> usize {
+    match tag {
+        Tag::Comment => 0,
+        Tag::Punctuation => 1,
+        Tag::Escape => 2,
+        Tag::Strong => 3,
+        Tag::Emph => 4,
+        Tag::Link => 5,
+        Tag::Raw => 6,
+        Tag::Label => 7,
+        Tag::Ref => 8,
+        Tag::Heading => 9,
+        Tag::ListMarker => 10,
+        Tag::ListTerm => 11,
+        Tag::MathDelimiter => 12,
+        Tag::MathOperator | Tag::Operator => 13,
+        Tag::Keyword => 14,
+        Tag::Number => 15,
+        Tag::String => 16,
+        Tag::Function => 17,
+        Tag::Interpolated => 18,
+        Tag::Error => unreachable!("errors are not highlighted"),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// A server whose root is a fresh directory holding the given files.
+    fn server(name: &str, files: &[(&str, &str)]) -> Server {
+        let root =
+            std::env::temp_dir().join(format!("typst-lsp-{name}-{}", std::process::id()));
+        let _ = fs::remove_dir_all(&root);
+        fs::create_dir_all(&root).unwrap();
+        for (path, text) in files {
+            fs::write(root.join(path), text).unwrap();
+        }
+
+        Server {
+            world: SystemWorld::new(root, &[], None),
+            document: None,
+            published: HashSet::new(),
+        }
+    }
+
+    /// The URI of a file in the server's root.
+    fn uri(server: &Server, path: &str) -> String {
+        path_to_uri(&server.world.root.join(path))
+    }
+
+    /// Open a document in the server.
+    fn open(server: &mut Server, path: &str, text: &str) -> Vec<(String, Json)> {
+        let uri = uri(server, path);
+        server.notify(
+            "textDocument/didOpen",
+            &json!({ "textDocument": { "uri": uri, "text": text } }),
+        )
+    }
+
+    /// Send a request about a position in a document.
+    fn request(
+        server: &mut Server,
+        method: &str,
+        path: &str,
+        (line, character): (usize, usize),
+    ) -> Response {
+        let uri = uri(server, path);
+        server.request(
+            method,
+            &json!({
+                "textDocument": { "uri": uri },
+                "position": { "line": line, "character": character },
+            }),
+        )
+    }
+
+    /// The messages of the published diagnostics for a file.
+    fn messages(published: &[(String, Json)], uri: &str) -> Vec<String> {
+        published
+            .iter()
+            .filter(|(target, _)| target == uri)
+            .flat_map(|(_, diagnostics)| diagnostics.as_array().unwrap())
+            .map(|diagnostic| diagnostic["message"].as_str().unwrap().to_string())
+            .collect()
+    }
+
+    #[test]
+    fn test_lsp_dispatch() {
+        let mut server = server("dispatch", &[("main.typ", "")]);
+        open(&mut server, "main.typ", "Hello");
+
+        let response = server.request("textDocument/unknown", &json!({}));
+        assert_eq!(response.unwrap_err().0, METHOD_NOT_FOUND);
+
+        // Requests about documents that aren't open are invalid.
+        let response = request(&mut server, "textDocument/hover", "other.typ", (0, 0));
+        assert_eq!(response.unwrap_err().0, INVALID_PARAMS);
+    }
+
+    #[test]
+    fn test_lsp_completion() {
+        let mut server = server("completion", &[("main.typ", "")]);
+        open(&mut server, "main.typ", "#let value = 1\n#val");
+
+        let response =
+            request(&mut server, "textDocument/completion", "main.typ", (1, 4)).unwrap();
+        let items = response["items"].as_array().unwrap();
+        let item = items.iter().find(|item| item["label"] == "value").unwrap();
+        assert_eq!(item["kind"], 21);
+        assert_eq!(
+            item["textEdit"]["range"],
+            json!({
+                "start": { "line": 1, "character": 1 },
+                "end": { "line": 1, "character": 4 },
+            })
+        );
+    }
+
+    #[test]
+    fn test_lsp_hover() {
+        let mut server = server("hover", &[("main.typ", "")]);
+        open(&mut server, "main.typ", "#let value = 1 + 2\n#value");
+
+        let response =
+            request(&mut server, "textDocument/hover", "main.typ", (1, 3)).unwrap();
+        assert_eq!(response["contents"]["kind"], "markdown");
+        assert_eq!(response["contents"]["value"], "```typst\n3\n```");
+
+        let response =
+            request(&mut server, "textDocument/hover", "main.typ", (0, 0)).unwrap();
+        assert_eq!(response, Json::Null);
+    }
+
+    #[test]
+    fn test_lsp_incremental_edits() {
+        let mut server = server("edits", &[("main.typ", "")]);
+        open(&mut server, "main.typ", "= Heading\nSome text.");
+
+        let uri = uri(&server, "main.typ");
+        let range = |start: (usize, usize), end: (usize, usize)| {
+            json!({
+                "start": { "line": start.0, "character": start.1 },
+                "end": { "line": end.0, "character": end.1 },
+            })
+        };
+
+        server.notify(
+            "textDocument/didChange",
+            &json!({
+                "textDocument": { "uri": uri },
+                "contentChanges": [
+                    { "range": range((1, 5), (1, 9)), "text": "more text" },
+                    { "range": range((0, 2), (0, 2)), "text": "A " },
+                    { "range": range((1, 0), (1, 0)), "text": "Ünïcödé " },
+                    { "range": range((1, 8), (1, 13)), "text": "" },
+                ],
+            }),
+        );
+
+        let id = server.world.pinned_id(&server.world.root.join("main.typ")).unwrap();
+        assert_eq!(server.world.source(id).text(), "= A Heading\nÜnïcödé more text.");
+
+        // A change without a range replaces the whole text.
+        server.notify(
+            "textDocument/didChange",
+            &json!({
+                "textDocument": { "uri": uri },
+                "contentChanges": [{ "text": "New" }],
+            }),
+        );
+        assert_eq!(server.world.source(id).text(), "New");
+    }
+
+    #[test]
+    fn test_lsp_diagnostics() {
+        let mut server = server("diagnostics", &[("main.typ", "")]);
+        let uri = uri(&server, "main.typ");
+
+        let published = open(&mut server, "main.typ", "Hi\n#foo");
+        assert_eq!(messages(&published, &uri), ["unknown variable"]);
+        let (_, diagnostics) = &published[0];
+        assert_eq!(diagnostics[0]["severity"], 1);
+        assert_eq!(
+            diagnostics[0]["range"],
+            json!({
+                "start": { "line": 1, "character": 1 },
+                "end": { "line": 1, "character": 4 },
+            })
+        );
+
+        // Fixing the error clears the diagnostics.
+        let published = server.notify(
+            "textDocument/didChange",
+            &json!({
+                "textDocument": { "uri": uri },
+                "contentChanges": [{ "text": "Hi" }],
+            }),
+        );
+        assert_eq!(published, [(uri.clone(), json!([]))]);
+
+        // Nothing is published when there was nothing to clear.
+        let published = server.notify(
+            "textDocument/didChange",
+            &json!({
+                "textDocument": { "uri": uri },
+                "contentChanges": [{ "text": "Hello" }],
+            }),
+        );
+        assert!(published.is_empty());
+    }
+
+    #[test]
+    fn test_lsp_diagnostics_across_files() {
+        let mut server =
+            server("imports", &[("main.typ", ""), ("lib.typ", "#let x = 1\n#y")]);
+        let published = open(&mut server, "main.typ", "#import \"lib.typ\": x");
+        let lib = uri(&server, "lib.typ");
+        assert_eq!(messages(&published, &lib), ["unknown variable"]);
+    }
+}
@@ -1,14 +1,18 @@
 use std::cell::{RefCell, RefMut};
 use std::collections::HashMap;
+use std::ffi::OsString;
 use std::fs::{self, File};
 use std::hash::Hash;
 use std::io::{self, Write};
 use std::path::{Path, PathBuf};
 use std::process;
+use std::str::FromStr;
+use std::time::{Duration, Instant};
 
+use chrono::{DateTime, Datelike, FixedOffset, Local, TimeZone, Utc};
 use codespan_reporting::diagnostic::{Diagnostic, Label};
 use codespan_reporting::term::{self, termcolor};
-use comemo::Prehashed;
+use comemo::{Prehashed, Track};
 use elsa::FrozenVec;
 use memmap2::Mmap;
 use notify::{RecommendedWatcher, RecursiveMode, Watcher};
@@ -17,52 +21,209 @@ use pico_args::Arguments;
 use same_file::{is_same_file, Handle};
 use siphasher::sip128::{Hasher128, SipHasher};
 use termcolor::{ColorChoice, StandardStream, WriteColor};
-use typst::diag::{FileError, FileResult, SourceError, StrResult};
-use typst::eval::Library;
+use typst::diag::{
+    bail, At, FileError, FileResult, Severity, SourceDiagnostic, SourceResult, StrResult,
+};
+use typst::doc::Document;
+use typst::eval::{eval_string, Datetime, Library, Tracer, Value};
+use typst::export::{PdfOptions, PdfStandard};
 use typst::font::{Font, FontBook, FontInfo, FontVariant};
-use typst::syntax::{Source, SourceId};
-use typst::util::{Buffer, PathExt};
+use typst::geom::Color;
+use typst::model::{Introspector, Selector};
+use typst::package::PackageSpec;
+use typst::syntax::{Source, SourceId, Span};
+use typst::util::{hash128, Buffer, PathExt};
 use typst::World;
 use walkdir::WalkDir;
 
+mod lsp;
+
 type CodespanResult<T> = Result<T, CodespanError>;
 type CodespanError = codespan_reporting::files::Error;
 
 /// What to do.
 enum Command {
     Compile(CompileCommand),
+    Query(QueryCommand),
+    Lsp(LspCommand),
     Fonts(FontsCommand),
 }
 
-/// Compile a .typ file into a PDF file.
+/// Compile a .typ file into a PDF, PNG or SVG file.
 struct CompileCommand {
     input: PathBuf,
     output: PathBuf,
+    format: OutputFormat,
+    ppi: f32,
     root: Option<PathBuf>,
     watch: bool,
     font_paths: Vec<PathBuf>,
+    creation_timestamp: Option<DateTime<Utc>>,
+    pdf_standard: Option<PdfStandard>,
+}
+
+/// The format of the compilation output.
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+enum OutputFormat {
+    Pdf,
+    Png,
+    Svg,
+}
+
+impl OutputFormat {
+    /// The file extension for the format.
+    fn extension(self) -> &'static str {
+        match self {
+            Self::Pdf => "pdf",
+            Self::Png => "png",
+            Self::Svg => "svg",
+        }
+    }
+
+    /// Whether the format produces one file per page.
+    fn is_paged(self) -> bool {
+        matches!(self, Self::Png | Self::Svg)
+    }
+}
+
+impl FromStr for OutputFormat {
+    type Err = &'static str;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Ok(match s.to_ascii_lowercase().as_str() {
+            "pdf" => Self::Pdf,
+            "png" => Self::Png,
+            "svg" => Self::Svg,
+            _ => return Err("unknown output format"),
+        })
+    }
 }
 
+/// The placeholder in output paths that is replaced by the page number.
+const PAGE_NUMBER: &str = "{n}";
+
+/// The placeholder in output paths that is replaced by the zero-padded page
+/// number.
+const PAGE_NUMBER_PADDED: &str = "{0n}";
+
+/// The default pixel density for PNG export.
+const DEFAULT_PPI: f32 = 144.0;
+
+/// How many compilations in watch mode or the language server a memoized
+/// result may go unused before it is evicted. With one, everything the last compilation didn't use is
+/// dropped.
+const MAX_CACHE_AGE: usize = 1;
+
 const HELP: &'static str = "\
-typst creates PDF files from .typ files
+typst creates PDF, PNG and SVG files from .typ files
 
 USAGE:
-  typst [OPTIONS] <input.typ> [output.pdf]
+  typst [compile] [OPTIONS] <input.typ> [output.pdf]
   typst [SUBCOMMAND] ...
 
 ARGS:
   <input.typ>    Path to input Typst file
-  [output.pdf]   Path to output PDF file
+  [output.pdf]   Path to output file. For PNG and SVG, one file is written
+                 per page and the path may contain `{n}` (page number) or
+                 `{0n}` (zero-padded page number). A template is required
+                 if the document has multiple pages
 
 OPTIONS:
   -h, --help        Print this help
   -V, --version     Print the CLI's version
   -w, --watch       Watch the inputs and recompile on changes
+  -f, --format <f>  Output format: pdf, png or svg (default: determined by
+                    the output file's extension, otherwise pdf)
+  --ppi <ppi>       Pixels per inch for PNG export (default: 144)
+  --pdf-standard <standard>
+                    Make the PDF conform to a standard: a-2b (PDF/A-2b)
   --font-path <dir> Add additional directories to search for fonts
   --root <dir>      Configure the root for absolute paths
+  --creation-timestamp <secs>
+                    UNIX timestamp to use as the current date in UTC, for
+                    reproducible builds (default: $SOURCE_DATE_EPOCH if set,
+                    otherwise the system clock)
 
 SUBCOMMANDS:
+  query             Process a document and print introspection results
+  lsp               Run a language server on standard input and output
   --fonts           List all discovered fonts in system and custom font paths
+
+PACKAGES:
+  Packages imported through `@namespace/name:version` are looked up in
+  `{data-dir}/typst/packages/namespace/name/version` and then in
+  `{cache-dir}/typst/packages/namespace/name/version`
+";
+
+/// Query a compiled document for elements and print their fields.
+struct QueryCommand {
+    input: PathBuf,
+    selector: String,
+    field: Option<String>,
+    one: bool,
+    format: SerializationFormat,
+    root: Option<PathBuf>,
+    font_paths: Vec<PathBuf>,
+}
+
+/// The format in which query results are printed.
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+enum SerializationFormat {
+    Json,
+    Yaml,
+}
+
+impl FromStr for SerializationFormat {
+    type Err = &'static str;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Ok(match s.to_ascii_lowercase().as_str() {
+            "json" => Self::Json,
+            "yaml" => Self::Yaml,
+            _ => return Err("unknown serialization format"),
+        })
+    }
+}
+
+const HELP_QUERY: &'static str = "\
+typst query compiles a document and prints the elements matching a selector
+
+USAGE:
+  typst query [OPTIONS] <input.typ> <selector>
+
+ARGS:
+  <input.typ>       Path to input Typst file
+  <selector>        Typst code that evaluates to a selector, for example
+                    `<version>`, `figure` or `heading.where(level: 1)`
+
+OPTIONS:
+  -h, --help        Print this help
+  --field <field>   Print only this field of each matching element
+  --one             Expect exactly one matching element and print it without
+                    wrapping it in an array
+  --format <f>      Serialization format: json or yaml (default: json)
+  --font-path <dir> Add additional directories to search for fonts
+  --root <dir>      Configure the root for absolute paths
+";
+
+/// Run a language server.
+struct LspCommand {
+    root: Option<PathBuf>,
+    font_paths: Vec<PathBuf>,
+}
+
+const HELP_LSP: &'static str = "\
+typst lsp runs a language server that editors talk to through the Language
+Server Protocol on standard input and output
+
+USAGE:
+  typst lsp [OPTIONS]
+
+OPTIONS:
+  -h, --help        Print this help
+  --font-path <dir> Add additional directories to search for fonts
+  --root <dir>      Configure the root for absolute paths (default: the
+                    workspace folder reported by the editor)
 ";
 
 /// List discovered system fonts.
@@ -98,7 +259,19 @@ fn main() {
 
 /// Parse command line arguments.
 fn parse_args() -> StrResult<Command> {
-    let mut args = Arguments::from_env();
+    // The compile subcommand is the default, but it may also be spelled out.
+    let mut raw: Vec<OsString> = std::env::args_os().skip(1).collect();
+    if raw.first().map_or(false, |arg| arg == "compile") {
+        raw.remove(0);
+    }
+
+    let query = raw.first().map_or(false, |arg| arg == "query");
+    let lsp = raw.first().map_or(false, |arg| arg == "lsp");
+    if query || lsp {
+        raw.remove(0);
+    }
+
+    let mut args = Arguments::from_vec(raw);
     if args.contains(["-V", "--version"]) {
         print_version();
     }
@@ -112,6 +285,37 @@ fn parse_args() -> StrResult<Command> {
         }
 
         Command::Fonts(FontsCommand { font_paths, variants: args.contains("--variants") })
+    } else if query {
+        if help {
+            print_help(HELP_QUERY);
+        }
+
+        let root = args.opt_value_from_str("--root").map_err(|_| "missing root path")?;
+        let field = args.opt_value_from_str("--field").map_err(|_| "missing field")?;
+        let one = args.contains("--one");
+        let format = args
+            .opt_value_from_str("--format")
+            .map_err(|_| "unknown serialization format")?
+            .unwrap_or(SerializationFormat::Json);
+
+        let input = args.free_from_str().map_err(|_| "missing input file")?;
+        let selector = args.free_from_str().map_err(|_| "missing selector")?;
+        Command::Query(QueryCommand {
+            input,
+            selector,
+            field,
+            one,
+            format,
+            root,
+            font_paths,
+        })
+    } else if lsp {
+        if help {
+            print_help(HELP_LSP);
+        }
+
+        let root = args.opt_value_from_str("--root").map_err(|_| "missing root path")?;
+        Command::Lsp(LspCommand { root, font_paths })
     } else {
         if help {
             print_help(HELP);
@@ -119,8 +323,41 @@ fn parse_args() -> StrResult<Command> {
 
         let root = args.opt_value_from_str("--root").map_err(|_| "missing root path")?;
         let watch = args.contains(["-w", "--watch"]);
-        let (input, output) = parse_input_output(&mut args, "pdf")?;
-        Command::Compile(CompileCommand { input, output, watch, root, font_paths })
+        let format = args
+            .opt_value_from_str(["-f", "--format"])
+            .map_err(|_| "unknown output format")?;
+        let ppi = args
+            .opt_value_from_str("--ppi")
+            .map_err(|_| "invalid pixel density")?
+            .unwrap_or(DEFAULT_PPI);
+        if !(ppi > 0.0 && ppi.is_finite()) {
+            Err("pixel density must be positive")?;
+        }
+
+        let creation_timestamp = args
+            .opt_value_from_str::<_, i64>("--creation-timestamp")
+            .map_err(|_| "invalid creation timestamp")?
+            .or_else(|| std::env::var("SOURCE_DATE_EPOCH").ok()?.parse().ok())
+            .map(|secs| Utc.timestamp_opt(secs, 0).single())
+            .map(|stamp| stamp.ok_or("creation timestamp is out of range"))
+            .transpose()?;
+
+        let pdf_standard = args
+            .opt_value_from_fn("--pdf-standard", parse_pdf_standard)
+            .map_err(|_| "unknown PDF standard")?;
+
+        let (input, output, format) = parse_input_output(&mut args, format)?;
+        Command::Compile(CompileCommand {
+            input,
+            output,
+            format,
+            ppi,
+            watch,
+            root,
+            font_paths,
+            creation_timestamp,
+            pdf_standard,
+        })
     };
 
     // Don't allow excess arguments.
@@ -133,15 +370,35 @@ fn parse_args() -> StrResult<Command> {
 }
 
 /// Parse two freestanding path arguments, with the output path being optional.
-/// If it is omitted, it is determined from the input path's file stem plus the
-/// given extension.
-fn parse_input_output(args: &mut Arguments, ext: &str) -> StrResult<(PathBuf, PathBuf)> {
+///
+/// The output format is taken from `format` or otherwise from the output
+/// path's extension, defaulting to PDF. If the output path is omitted, it is
+/// determined from the input path's file stem plus the format's extension.
+fn parse_input_output(
+    args: &mut Arguments,
+    format: Option<OutputFormat>,
+) -> StrResult<(PathBuf, PathBuf, OutputFormat)> {
     let input: PathBuf = args.free_from_str().map_err(|_| "missing input file")?;
-    let output = match args.opt_free_from_str().ok().flatten() {
+    let output: Option<PathBuf> = args.opt_free_from_str().ok().flatten();
+
+    let format = format
+        .or_else(|| {
+            let ext = output.as_ref()?.extension()?.to_str()?;
+            ext.parse().ok()
+        })
+        .unwrap_or(OutputFormat::Pdf);
+
+    let output = match output {
         Some(output) => output,
         None => {
             let name = input.file_name().ok_or("source path does not point to a file")?;
-            Path::new(name).with_extension(ext)
+            let stem = Path::new(name).file_stem().unwrap_or_default().to_string_lossy();
+            let name = if format.is_paged() {
+                format!("{stem}-{PAGE_NUMBER}.{}", format.extension())
+            } else {
+                format!("{stem}.{}", format.extension())
+            };
+            PathBuf::from(name)
         }
     };
 
@@ -150,7 +407,15 @@ fn parse_input_output(args: &mut Arguments, ext: &str) -> StrResult<(PathBuf, Pa
         Err("source and destination files are the same")?;
     }
 
-    Ok((input, output))
+    Ok((input, output, format))
+}
+
+/// Parse the name of a PDF standard.
+fn parse_pdf_standard(name: &str) -> StrResult<PdfStandard> {
+    match name.to_ascii_lowercase().as_str() {
+        "a-2b" => Ok(PdfStandard::A2b),
+        _ => Err("unknown PDF standard".into()),
+    }
 }
 
 /// Print a help string and quit.
@@ -181,31 +446,38 @@ fn print_error(msg: &str) -> io::Result<()> {
 fn dispatch(command: Command) -> StrResult<()> {
     match command {
         Command::Compile(command) => compile(command),
+        Command::Query(command) => query(command),
+        Command::Lsp(command) => lsp::serve(command),
         Command::Fonts(command) => fonts(command),
     }
 }
 
-/// Execute a compilation command.
-fn compile(command: CompileCommand) -> StrResult<()> {
-    let root = if let Some(root) = &command.root {
-        root.clone()
-    } else if let Some(dir) = command
-        .input
-        .canonicalize()
-        .ok()
-        .as_ref()
-        .and_then(|path| path.parent())
+/// Determine the root directory for absolute paths.
+///
+/// Defaults to the directory of the input file.
+fn root_dir(input: &Path, root: Option<&Path>) -> PathBuf {
+    if let Some(root) = root {
+        root.into()
+    } else if let Some(dir) =
+        input.canonicalize().ok().as_ref().and_then(|path| path.parent())
     {
         dir.into()
     } else {
         PathBuf::new()
-    };
+    }
+}
+
+/// Execute a compilation command.
+fn compile(command: CompileCommand) -> StrResult<()> {
+    let root = root_dir(&command.input, command.root.as_deref());
 
     // Create the world that serves sources, fonts and files.
-    let mut world = SystemWorld::new(root, &command.font_paths);
+    let mut world =
+        SystemWorld::new(root, &command.font_paths, command.creation_timestamp);
 
     // Perform initial compilation.
-    let failed = compile_once(&mut world, &command)?;
+    let mut cache = ExportCache::default();
+    let failed = compile_once(&mut world, &command, &mut cache)?;
     if !command.watch {
         // Return with non-zero exit code in case of error.
         if failed {
@@ -247,38 +519,186 @@ fn compile(command: CompileCommand) -> StrResult<()> {
         }
 
         if recompile {
-            compile_once(&mut world, &command)?;
-            comemo::evict(30);
+            compile_once(&mut world, &command, &mut cache)?;
+
+            // Keep the memoized results of the last compilation around so that
+            // the next one can reuse whatever is unchanged, but evict the
+            // stale entries that it didn't touch.
+            comemo::evict(MAX_CACHE_AGE);
         }
     }
 }
 
 /// Compile a single time.
-fn compile_once(world: &mut SystemWorld, command: &CompileCommand) -> StrResult<bool> {
+fn compile_once(
+    world: &mut SystemWorld,
+    command: &CompileCommand,
+    cache: &mut ExportCache,
+) -> StrResult<bool> {
+    let start = Instant::now();
     status(command, Status::Compiling).unwrap();
 
     world.reset();
     world.main = world.resolve(&command.input).map_err(|err| err.to_string())?;
 
-    match typst::compile(world) {
-        // Export the PDF.
-        Ok(document) => {
-            let buffer = typst::export::pdf(&document);
-            fs::write(&command.output, buffer).map_err(|_| "failed to write PDF file")?;
-            status(command, Status::Success).unwrap();
+    let mut tracer = Tracer::default();
+    let result = typst::compile(world, &mut tracer).and_then(|document| {
+        let changed = export(&document, command, cache)?;
+        Ok(Summary {
+            duration: start.elapsed(),
+            changed,
+            total: document.pages.len(),
+        })
+    });
+    let warnings = tracer.warnings().to_vec();
+
+    match result {
+        // The PDF, PNG or SVG was exported.
+        Ok(summary) => {
+            if warnings.is_empty() {
+                status(command, Status::Success(summary)).unwrap();
+            } else {
+                status(command, Status::PartialSuccess(summary)).unwrap();
+                print_diagnostics(world, vec![], warnings)
+                    .map_err(|_| "failed to print diagnostics")?;
+            }
             Ok(false)
         }
 
         // Print diagnostics.
         Err(errors) => {
             status(command, Status::Error).unwrap();
-            print_diagnostics(&world, *errors)
+            print_diagnostics(world, *errors, warnings)
                 .map_err(|_| "failed to print diagnostics")?;
             Ok(true)
         }
     }
 }
 
+/// Export into the target format.
+///
+/// Only writes the pages that changed since the last export and returns how
+/// many of them there were. A PDF is rewritten as a whole if anything changed.
+fn export(
+    document: &Document,
+    command: &CompileCommand,
+    cache: &mut ExportCache,
+) -> SourceResult<usize> {
+    // Padded page numbers in file names change when the page count gains or
+    // loses a digit, so all images need to be written again then.
+    let width = document.pages.len().to_string().len();
+    if command.format.is_paged() && cache.pages.len().to_string().len() != width {
+        cache.pages.clear();
+    }
+
+    let hashes: Vec<u128> = document.pages.iter().map(hash128).collect();
+    let changed = cache.changed(&hashes);
+    match command.format {
+        OutputFormat::Pdf => {
+            let hash = hash128(document);
+            if cache.document != Some(hash) {
+                let options = PdfOptions { standard: command.pdf_standard };
+                let buffer = typst::export::pdf(document, &options)?;
+                fs::write(&command.output, buffer)
+                    .map_err(|_| "failed to write PDF file")
+                    .at(Span::detached())?;
+                cache.document = Some(hash);
+            }
+            cache.pages = hashes;
+        }
+        OutputFormat::Png | OutputFormat::Svg => {
+            let template = command.output.to_string_lossy();
+            let templated =
+                template.contains(PAGE_NUMBER) || template.contains(PAGE_NUMBER_PADDED);
+            if !templated && document.pages.len() > 1 {
+                bail!(
+                    Span::detached(),
+                    "cannot export multiple pages without `{n}` in the output path"
+                );
+            }
+
+            let paths: Vec<String> = (1..=document.pages.len())
+                .map(|number| page_path(&template, number, width))
+                .collect();
+
+            cache.pages.resize(document.pages.len(), 0);
+            for &i in &changed {
+                let frame = &document.pages[i];
+                let path = &paths[i];
+
+                if command.format == OutputFormat::Png {
+                    let pixmap =
+                        typst::export::render(frame, command.ppi / 72.0, Color::WHITE);
+                    pixmap
+                        .save_png(path)
+                        .map_err(|_| "failed to write PNG file")
+                        .at(Span::detached())?;
+                } else {
+                    let svg = typst::export::svg(frame);
+                    fs::write(path, svg)
+                        .map_err(|_| "failed to write SVG file")
+                        .at(Span::detached())?;
+                }
+
+                cache.pages[i] = hashes[i];
+            }
+
+            // Remove the images of pages that don't exist anymore. Files that
+            // are already gone are fine.
+            for path in cache.stale(&paths) {
+                fs::remove_file(path).ok();
+            }
+            cache.outputs = paths;
+        }
+    }
+    Ok(changed.len())
+}
+
+/// Remembers what was exported last so that watch mode only rewrites the
+/// output that changed.
+#[derive(Default)]
+struct ExportCache {
+    /// The hash of the last exported document.
+    document: Option<u128>,
+    /// The hashes of the last exported pages.
+    pages: Vec<u128>,
+    /// The paths of the last exported images.
+    outputs: Vec<String>,
+}
+
+impl ExportCache {
+    /// The indices of the pages whose hashes differ from the last export.
+    fn changed(&self, hashes: &[u128]) -> Vec<usize> {
+        (0..hashes.len())
+            .filter(|&i| self.pages.get(i) != Some(&hashes[i]))
+            .collect()
+    }
+
+    /// The previously exported images that aren't part of the new output.
+    fn stale<'a>(&'a self, paths: &'a [String]) -> impl Iterator<Item = &'a String> {
+        self.outputs.iter().filter(|path| !paths.contains(path))
+    }
+}
+
+/// Statistics about a successful compilation.
+struct Summary {
+    /// How long compilation and export took.
+    duration: Duration,
+    /// The number of pages that changed since the last compilation.
+    changed: usize,
+    /// The total number of pages.
+    total: usize,
+}
+
+/// Fill in the page number placeholders of an output path template.
+///
+/// The padded placeholder is filled with zeros up to `width` digits.
+fn page_path(template: &str, number: usize, width: usize) -> String {
+    template
+        .replace(PAGE_NUMBER_PADDED, &format!("{number:0width$}"))
+        .replace(PAGE_NUMBER, &number.to_string())
+}
+
 /// Clear the terminal and render the status message.
 fn status(command: &CompileCommand, status: Status) -> io::Result<()> {
     if !command.watch {
@@ -288,7 +708,7 @@ fn status(command: &CompileCommand, status: Status) -> io::Result<()> {
     let esc = 27 as char;
     let input = command.input.display();
     let output = command.output.display();
-    let time = chrono::offset::Local::now();
+    let time = Local::now();
     let timestamp = time.format("%H:%M:%S");
     let message = status.message();
     let color = status.color();
@@ -307,7 +727,12 @@ fn status(command: &CompileCommand, status: Status) -> io::Result<()> {
     writeln!(w, " {output}")?;
 
     writeln!(w)?;
-    writeln!(w, "[{timestamp}] {message}")?;
+    write!(w, "[{timestamp}] {message}")?;
+    if let Status::Success(summary) | Status::PartialSuccess(summary) = &status {
+        let Summary { duration, changed, total } = summary;
+        write!(w, " in {duration:.2?} ({changed} of {total} pages changed)")?;
+    }
+    writeln!(w)?;
     writeln!(w)?;
 
     w.flush()
@@ -316,7 +741,8 @@ fn status(command: &CompileCommand, status: Status) -> io::Result<()> {
 /// The status in which the watcher can be.
 enum Status {
     Compiling,
-    Success,
+    Success(Summary),
+    PartialSuccess(Summary),
     Error,
 }
 
@@ -324,7 +750,8 @@ impl Status {
     fn message(&self) -> &str {
         match self {
             Self::Compiling => "compiling ...",
-            Self::Success => "compiled successfully",
+            Self::Success(_) => "compiled successfully",
+            Self::PartialSuccess(_) => "compiled with warnings",
             Self::Error => "compiled with errors",
         }
     }
@@ -333,6 +760,7 @@ impl Status {
         let styles = term::Styles::default();
         match self {
             Self::Error => styles.header_error,
+            Self::PartialSuccess(_) => styles.header_warning,
             _ => styles.header_note,
         }
     }
@@ -341,22 +769,33 @@ impl Status {
 /// Print diagnostic messages to the terminal.
 fn print_diagnostics(
     world: &SystemWorld,
-    errors: Vec<SourceError>,
+    errors: Vec<SourceDiagnostic>,
+    warnings: Vec<SourceDiagnostic>,
 ) -> Result<(), codespan_reporting::files::Error> {
     let mut w = StandardStream::stderr(ColorChoice::Auto);
     let config = term::Config { tab_width: 2, ..Default::default() };
 
-    for error in errors {
+    for diagnostic in warnings.into_iter().chain(errors) {
         // The main diagnostic.
-        let range = error.range(world);
-        let diag = Diagnostic::error()
-            .with_message(error.message)
-            .with_labels(vec![Label::primary(error.span.source(), range)]);
+        let labels = if diagnostic.span.is_detached() {
+            vec![]
+        } else {
+            let range = diagnostic.range(world);
+            vec![Label::primary(diagnostic.span.source(), range)]
+        };
+
+        let diag = match diagnostic.severity {
+            Severity::Error => Diagnostic::error(),
+            Severity::Warning => Diagnostic::warning(),
+        }
+        .with_message(diagnostic.message)
+        .with_notes(diagnostic.hints.iter().map(|hint| format!("hint: {hint}")).collect())
+        .with_labels(labels);
 
         term::emit(&mut w, &config, world, &diag)?;
 
         // Stacktrace-like helper diagnostics.
-        for point in error.trace {
+        for point in diagnostic.trace {
             let message = point.v.to_string();
             let help = Diagnostic::help().with_message(message).with_labels(vec![
                 Label::primary(
@@ -372,6 +811,87 @@ fn print_diagnostics(
     Ok(())
 }
 
+/// Execute a query command.
+fn query(command: QueryCommand) -> StrResult<()> {
+    let root = root_dir(&command.input, command.root.as_deref());
+    let mut world = SystemWorld::new(root, &command.font_paths, None);
+    world.main = world.resolve(&command.input).map_err(|err| err.to_string())?;
+
+    let mut tracer = Tracer::default();
+    let result = typst::compile(&world, &mut tracer);
+    let warnings = tracer.warnings().to_vec();
+
+    match result {
+        // Print the matching elements.
+        Ok(document) => {
+            let data = retrieve(&world, &command, &document)?;
+            let serialized = serialize(data, &command)?;
+            println!("{serialized}");
+            print_diagnostics(&world, vec![], warnings)
+                .map_err(|_| "failed to print diagnostics")?;
+        }
+
+        // Print diagnostics.
+        Err(errors) => {
+            print_diagnostics(&world, *errors, warnings)
+                .map_err(|_| "failed to print diagnostics")?;
+            process::exit(1);
+        }
+    }
+
+    Ok(())
+}
+
+/// Retrieve the matches for the selector.
+fn retrieve(
+    world: &(dyn World + 'static),
+    command: &QueryCommand,
+    document: &Document,
+) -> StrResult<Vec<Value>> {
+    let selector = eval_string(world.track(), &command.selector, Span::detached())
+        .map_err(|errors| {
+            let mut message = String::from("failed to evaluate selector");
+            for (i, error) in errors.iter().enumerate() {
+                message.push_str(if i == 0 { ": " } else { ", " });
+                message.push_str(&error.message);
+            }
+            message
+        })?
+        .cast::<Selector>()?;
+
+    // Apply the same restrictions as the `query` function in documents.
+    typst_library::meta::queryable(&selector)?;
+
+    let introspector = Introspector::new(&document.pages);
+    let elements = introspector.query(selector);
+
+    Ok(match &command.field {
+        Some(field) => {
+            elements.into_iter().filter_map(|elem| elem.field(field)).collect()
+        }
+        None => elements.into_iter().map(Value::Content).collect(),
+    })
+}
+
+/// Serialize the query results in the requested format.
+fn serialize(data: Vec<Value>, command: &QueryCommand) -> StrResult<String> {
+    let value = if command.one {
+        if data.len() != 1 {
+            Err(format!("expected exactly one element, found {}", data.len()))?;
+        }
+        data.into_iter().next().unwrap()
+    } else {
+        Value::Array(data.into_iter().collect())
+    };
+
+    let serialized = match command.format {
+        SerializationFormat::Json => serde_json::to_string_pretty(&value).ok(),
+        SerializationFormat::Yaml => serde_yaml::to_string(&value).ok(),
+    };
+
+    serialized.ok_or_else(|| "failed to serialize query results".into())
+}
+
 /// Execute a font listing command.
 fn fonts(command: FontsCommand) -> StrResult<()> {
     let mut searcher = FontSearcher::new();
@@ -401,7 +921,13 @@ struct SystemWorld {
     hashes: RefCell<HashMap<PathBuf, FileResult<PathHash>>>,
     paths: RefCell<HashMap<PathHash, PathSlot>>,
     sources: FrozenVec<Box<Source>>,
+    /// The number of leading sources that are open in an editor. They take
+    /// precedence over the files on disk and survive resets.
+    pinned: usize,
+    packages: RefCell<HashMap<PackageSpec, PathBuf>>,
     main: SourceId,
+    fixed_now: Option<DateTime<Utc>>,
+    now: OnceCell<DateTime<Utc>>,
 }
 
 /// Holds details about the location of a font and lazily the font itself.
@@ -419,7 +945,11 @@ struct PathSlot {
 }
 
 impl SystemWorld {
-    fn new(root: PathBuf, font_paths: &[PathBuf]) -> Self {
+    fn new(
+        root: PathBuf,
+        font_paths: &[PathBuf],
+        fixed_now: Option<DateTime<Utc>>,
+    ) -> Self {
         let mut searcher = FontSearcher::new();
         searcher.search_system();
 
@@ -438,7 +968,11 @@ impl SystemWorld {
             hashes: RefCell::default(),
             paths: RefCell::default(),
             sources: FrozenVec::new(),
+            pinned: 0,
+            packages: RefCell::default(),
             main: SourceId::detached(),
+            fixed_now,
+            now: OnceCell::new(),
         }
     }
 }
@@ -491,6 +1025,48 @@ impl World for SystemWorld {
             .get_or_init(|| read(path).map(Buffer::from))
             .clone()
     }
+
+    fn package(&self, spec: &PackageSpec) -> FileResult<PathBuf> {
+        if let Some(root) = self.packages.borrow().get(spec) {
+            return Ok(root.clone());
+        }
+
+        // Packages are searched for in the data directory first and then in
+        // the cache directory.
+        let subdir = Path::new("typst/packages")
+            .join(spec.namespace.as_str())
+            .join(spec.name.as_str())
+            .join(spec.version.to_string());
+        let root = [dirs::data_dir(), dirs::cache_dir()]
+            .into_iter()
+            .flatten()
+            .map(|dir| dir.join(&subdir).normalize())
+            .find(|dir| dir.is_dir())
+            .ok_or_else(|| FileError::PackageNotFound(spec.clone()))?;
+
+        self.packages.borrow_mut().insert(spec.clone(), root.clone());
+        Ok(root)
+    }
+
+    fn today(&self, offset: Option<i64>) -> Option<Datetime> {
+        // The time is fixed for the duration of a compilation so that all
+        // calls observe the same date.
+        let now = *self.now.get_or_init(|| self.fixed_now.unwrap_or_else(Utc::now));
+        let naive = match offset {
+            Some(hours) => {
+                let seconds = i32::try_from(hours.checked_mul(3600)?).ok()?;
+                now.with_timezone(&FixedOffset::east_opt(seconds)?).naive_local()
+            }
+            None if self.fixed_now.is_some() => now.naive_utc(),
+            None => now.with_timezone(&Local).naive_local(),
+        };
+
+        Datetime::from_ymd(
+            naive.year(),
+            naive.month().try_into().ok()?,
+            naive.day().try_into().ok()?,
+        )
+    }
 }
 
 impl SystemWorld {
@@ -515,7 +1091,16 @@ impl SystemWorld {
 
     fn insert(&self, path: &Path, text: String) -> SourceId {
         let id = SourceId::from_u16(self.sources.len() as u16);
-        let source = Source::new(id, path, text);
+        let mut source = Source::new(id, path, text);
+        let package = self
+            .packages
+            .borrow()
+            .iter()
+            .find(|(_, root)| path.starts_with(root))
+            .map(|(spec, _)| spec.clone());
+        if let Some(spec) = package {
+            source = source.with_package(spec);
+        }
         self.sources.push(Box::new(source));
         id
     }
@@ -546,9 +1131,49 @@ impl SystemWorld {
     }
 
     fn reset(&mut self) {
-        self.sources.as_mut().clear();
+        self.sources.as_mut().truncate(self.pinned);
         self.hashes.borrow_mut().clear();
         self.paths.borrow_mut().clear();
+        self.now.take();
+
+        // Let the pinned sources shadow the files on disk again.
+        for i in 0..self.pinned {
+            let source = &self.sources[i];
+            if let Ok(slot) = self.slot(source.path()) {
+                let _ = slot.source.set(Ok(source.id()));
+            }
+        }
+    }
+
+    /// Pin a source file with the given text, which then shadows the file's
+    /// contents on disk.
+    fn pin(&mut self, path: &Path, text: String) -> SourceId {
+        if let Some(id) = self.pinned_id(path) {
+            self.source_mut(id).replace(text);
+            return id;
+        }
+
+        self.reset();
+        let id = self.insert(path, text);
+        self.pinned += 1;
+        if let Ok(slot) = self.slot(path) {
+            let _ = slot.source.set(Ok(id));
+        }
+        id
+    }
+
+    /// The id of the pinned source file at the given path, if any.
+    fn pinned_id(&self, path: &Path) -> Option<SourceId> {
+        let path = path.normalize();
+        (0..self.pinned)
+            .map(|i| &self.sources[i])
+            .find(|source| source.path() == path)
+            .map(|source| source.id())
+    }
+
+    /// Mutably access a source file.
+    fn source_mut(&mut self, id: SourceId) -> &mut Source {
+        &mut self.sources.as_mut()[id.into_u16() as usize]
     }
 }
 
@@ -743,3 +1368,328 @@ impl FontSearcher {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use typst::doc::Frame;
+    use typst::geom::{Abs, Size};
+
+    /// Parse the freestanding paths of a compile invocation.
+    fn paths(
+        args: &[&str],
+        format: Option<OutputFormat>,
+    ) -> StrResult<(PathBuf, PathBuf, OutputFormat)> {
+        let mut args = Arguments::from_vec(args.iter().map(Into::into).collect());
+        parse_input_output(&mut args, format)
+    }
+
+    /// A compile command that writes to the given output in a fresh directory.
+    fn command(
+        name: &str,
+        output: &str,
+        format: OutputFormat,
+        ppi: f32,
+    ) -> CompileCommand {
+        let dir =
+            std::env::temp_dir().join(format!("typst-cli-{name}-{}", std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(&dir).unwrap();
+        CompileCommand {
+            input: dir.join("main.typ"),
+            output: dir.join(output),
+            format,
+            ppi,
+            root: None,
+            watch: false,
+            font_paths: vec![],
+            creation_timestamp: None,
+            pdf_standard: None,
+        }
+    }
+
+    /// A document with the given number of 72pt x 36pt pages.
+    fn document(pages: usize) -> Document {
+        let size = Size::new(Abs::pt(72.0), Abs::pt(36.0));
+        Document {
+            pages: vec![Frame::new(size); pages],
+            ..Default::default()
+        }
+    }
+
+    /// The width and height of a PNG file.
+    fn png_size(path: &Path) -> (u32, u32) {
+        let data = fs::read(path).unwrap();
+        let int = |at: usize| u32::from_be_bytes(data[at..at + 4].try_into().unwrap());
+        (int(16), int(20))
+    }
+
+    /// Compile a document for queries and return a command matching the
+    /// selector in it.
+    fn queried(selector: &str) -> (SystemWorld, Document, QueryCommand) {
+        let compile = command("query", "out.pdf", OutputFormat::Pdf, DEFAULT_PPI);
+        fs::write(
+            &compile.input,
+            "#metadata((a: 1)) <one>\n\
+             #metadata(\"x\") <two>\n\
+             #metadata(\"y\") <two>\n\
+             = Heading",
+        )
+        .unwrap();
+
+        let root = compile.input.parent().unwrap().to_path_buf();
+        let mut world = SystemWorld::new(root, &[], None);
+        world.main = world.resolve(&compile.input).unwrap();
+        let document = typst::compile(&world, &mut Tracer::default()).unwrap();
+
+        let command = QueryCommand {
+            input: compile.input,
+            selector: selector.into(),
+            field: None,
+            one: false,
+            format: SerializationFormat::Json,
+            root: None,
+            font_paths: vec![],
+        };
+
+        (world, document, command)
+    }
+
+    /// Retrieve and serialize the matches of a query command.
+    fn run_query(
+        world: &SystemWorld,
+        document: &Document,
+        command: &QueryCommand,
+    ) -> StrResult<String> {
+        serialize(retrieve(world, command, document)?, command)
+    }
+
+    #[test]
+    fn test_warn_about_divergent_layout() {
+        let command = command("diverge", "out.pdf", OutputFormat::Pdf, DEFAULT_PPI);
+        fs::write(
+            &command.input,
+            "#let c = counter(\"c\")\n\
+             #locate(loc => c.update(c.final(loc).first() + 1))",
+        )
+        .unwrap();
+
+        let root = command.input.parent().unwrap().to_path_buf();
+        let mut world = SystemWorld::new(root, &[], None);
+        world.main = world.resolve(&command.input).unwrap();
+
+        let mut tracer = Tracer::default();
+        typst::compile(&world, &mut tracer).unwrap();
+        let [warning] = tracer.warnings() else { panic!("expected one warning") };
+        assert_eq!(warning.message, "layout did not converge within 5 attempts");
+        assert_eq!(
+            warning.hints,
+            ["check if any states or queries are updating themselves"]
+        );
+    }
+
+    #[test]
+    fn test_export_separates_identical_elements() {
+        let command = command("tagged", "out.pdf", OutputFormat::Pdf, DEFAULT_PPI);
+        fs::write(
+            &command.input,
+            "A\n\nA\n\nA\n\n#table(columns: 2, table.header[H][H], [B], [B])",
+        )
+        .unwrap();
+
+        let root = command.input.parent().unwrap().to_path_buf();
+        let mut world = SystemWorld::new(root, &[], None);
+        world.main = world.resolve(&command.input).unwrap();
+
+        let document = typst::compile(&world, &mut Tracer::default()).unwrap();
+        export(&document, &command, &mut ExportCache::default()).unwrap();
+
+        // Each paragraph and each cell is a structure element of its own,
+        // while the cells of the header share one header element.
+        let pdf = fs::read(&command.output).unwrap();
+        let pdf = String::from_utf8_lossy(&pdf);
+        let count = |role: &str| pdf.matches(&format!("/S /{role}\n")).count();
+        assert_eq!(count("P"), 7);
+        assert_eq!(count("TH"), 2);
+        assert_eq!(count("TD"), 2);
+        assert_eq!(count("TR"), 2);
+        assert_eq!(count("THead"), 1);
+        assert_eq!(count("Table"), 1);
+    }
+
+    #[test]
+    fn test_output_format_selection() {
+        let (_, output, format) = paths(&["in.typ", "out-{n}.png"], None).unwrap();
+        assert_eq!((output, format), ("out-{n}.png".into(), OutputFormat::Png));
+
+        let (_, _, format) = paths(&["in.typ", "OUT.SVG"], None).unwrap();
+        assert_eq!(format, OutputFormat::Svg);
+
+        let (_, _, format) = paths(&["in.typ", "out.txt"], None).unwrap();
+        assert_eq!(format, OutputFormat::Pdf);
+
+        let (_, _, format) =
+            paths(&["in.typ", "out.png"], Some(OutputFormat::Svg)).unwrap();
+        assert_eq!(format, OutputFormat::Svg);
+
+        let (_, output, format) = paths(&["dir/in.typ"], None).unwrap();
+        assert_eq!((output, format), ("in.pdf".into(), OutputFormat::Pdf));
+
+        let (_, output, _) = paths(&["dir/in.typ"], Some(OutputFormat::Png)).unwrap();
+        assert_eq!(output, PathBuf::from("in-{n}.png"));
+
+        assert_eq!(paths(&[], None).unwrap_err(), "missing input file");
+        assert_eq!("jpg".parse::<OutputFormat>(), Err("unknown output format"));
+    }
+
+    #[test]
+    fn test_page_path() {
+        assert_eq!(page_path("out-{n}.png", 7, 2), "out-7.png");
+        assert_eq!(page_path("out-{0n}.png", 7, 3), "out-007.png");
+        assert_eq!(page_path("out-{0n}.png", 12, 2), "out-12.png");
+        assert_eq!(page_path("{n}/{0n}.svg", 3, 2), "3/03.svg");
+        assert_eq!(page_path("out.png", 1, 1), "out.png");
+    }
+
+    #[test]
+    fn test_export_pages() {
+        let command = command("pages", "out-{0n}.png", OutputFormat::Png, 144.0);
+        let dir = command.output.parent().unwrap();
+        let mut cache = ExportCache::default();
+        assert_eq!(export(&document(10), &command, &mut cache).unwrap(), 10);
+        assert_eq!(png_size(&dir.join("out-01.png")), (144, 72));
+        assert!(dir.join("out-10.png").exists());
+
+        // Shrinking the document below ten pages renames all images.
+        assert_eq!(export(&document(2), &command, &mut cache).unwrap(), 2);
+        assert!(dir.join("out-1.png").exists());
+        assert!(dir.join("out-2.png").exists());
+        assert!(!dir.join("out-01.png").exists());
+        assert!(!dir.join("out-10.png").exists());
+    }
+
+    #[test]
+    fn test_export_ppi() {
+        let command = command("ppi", "out.png", OutputFormat::Png, 72.0);
+        export(&document(1), &command, &mut ExportCache::default()).unwrap();
+        assert_eq!(png_size(&command.output), (72, 36));
+    }
+
+    #[test]
+    fn test_export_svg() {
+        let command = command("svg", "out-{n}.svg", OutputFormat::Svg, DEFAULT_PPI);
+        let dir = command.output.parent().unwrap();
+        export(&document(2), &command, &mut ExportCache::default()).unwrap();
+        let svg = fs::read_to_string(dir.join("out-2.svg")).unwrap();
+        assert!(svg.starts_with("<svg"));
+    }
+
+    #[test]
+    fn test_export_requires_template() {
+        let command = command("template", "out.png", OutputFormat::Png, DEFAULT_PPI);
+        let errors =
+            export(&document(2), &command, &mut ExportCache::default()).unwrap_err();
+        assert_eq!(
+            errors[0].message,
+            "cannot export multiple pages without `{n}` in the output path"
+        );
+    }
+
+    #[test]
+    fn test_query_field() {
+        let (world, document, mut command) = queried("<two>");
+        command.field = Some("value".into());
+        let output = run_query(&world, &document, &command).unwrap();
+        assert_eq!(output, "[\n  \"x\",\n  \"y\"\n]");
+
+        // Elements without the field are skipped.
+        command.selector = "heading".into();
+        let output = run_query(&world, &document, &command).unwrap();
+        assert_eq!(output, "[]");
+    }
+
+    #[test]
+    fn test_query_one() {
+        let (world, document, mut command) = queried("<one>");
+        command.field = Some("value".into());
+        command.one = true;
+        let output = run_query(&world, &document, &command).unwrap();
+        assert_eq!(output, "{\n  \"a\": 1\n}");
+
+        command.selector = "<two>".into();
+        let error = run_query(&world, &document, &command).unwrap_err();
+        assert_eq!(error, "expected exactly one element, found 2");
+
+        command.selector = "<three>".into();
+        let error = run_query(&world, &document, &command).unwrap_err();
+        assert_eq!(error, "expected exactly one element, found 0");
+    }
+
+    #[test]
+    fn test_query_yaml() {
+        let (world, document, mut command) = queried("<two>");
+        command.field = Some("value".into());
+        command.format = SerializationFormat::Yaml;
+        let output = run_query(&world, &document, &command).unwrap();
+        assert_eq!(output, "---\n- x\n- y\n");
+
+        command.selector = "<one>".into();
+        command.one = true;
+        let output = run_query(&world, &document, &command).unwrap();
+        assert_eq!(output, "---\na: 1\n");
+    }
+
+    #[test]
+    fn test_query_invalid_selector() {
+        let (world, document, mut command) = queried("heading.where(");
+        let error = run_query(&world, &document, &command).unwrap_err();
+        assert!(error.starts_with("failed to evaluate selector: "), "{error}");
+
+        command.selector = "1 + 2".into();
+        let error = run_query(&world, &document, &command).unwrap_err();
+        assert_eq!(
+            error,
+            "expected function, location, label, string, regular expression, \
+             or selector, found integer"
+        );
+
+        command.selector = "regex(\"x\")".into();
+        let error = run_query(&world, &document, &command).unwrap_err();
+        assert_eq!(error, "cannot query for text");
+
+        command.selector = "text".into();
+        let error = run_query(&world, &document, &command).unwrap_err();
+        assert_eq!(error, "cannot query for texts");
+    }
+
+    #[test]
+    fn test_export_cache_changed() {
+        let mut cache = ExportCache::default();
+        assert_eq!(cache.changed(&[1, 2, 3]), [0, 1, 2]);
+
+        cache.pages = vec![1, 2, 3];
+        assert!(cache.changed(&[1, 2, 3]).is_empty());
+        assert_eq!(cache.changed(&[1, 5, 3]), [1]);
+        assert_eq!(cache.changed(&[1, 2, 3, 4]), [3]);
+        assert!(cache.changed(&[1, 2]).is_empty());
+        assert!(cache.changed(&[]).is_empty());
+    }
+
+    #[test]
+    fn test_export_cache_stale() {
+        let paths = |names: &[&str]| -> Vec<String> {
+            names.iter().map(|name| name.to_string()).collect()
+        };
+
+        let mut cache = ExportCache::default();
+        assert_eq!(cache.stale(&paths(&["1.png"])).count(), 0);
+
+        cache.outputs = paths(&["1.png", "2.png", "3.png"]);
+        let current = paths(&["1.png", "2.png"]);
+        assert_eq!(cache.stale(&current).collect::<Vec<_>>(), ["3.png"]);
+
+        cache.outputs = paths(&["08.png", "09.png", "10.png"]);
+        let current = paths(&["1.png", "2.png", "8.png", "9.png"]);
+        assert_eq!(cache.stale(&current).count(), 3);
+    }
+}
@@ -743,7 +743,12 @@ const TYPE_ORDER: &[&str] = &[
     "relative length",
     "fraction",
     "color",
+    "gradient",
+    "pattern",
+    "datetime",
+    "duration",
     "string",
+    "bytes",
     "regex",
     "label",
     "content",
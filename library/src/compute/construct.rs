@@ -1,7 +1,7 @@
 use std::num::NonZeroI64;
 use std::str::FromStr;
 
-use typst::eval::Regex;
+use typst::eval::{Bytes, Datetime, Duration, Regex};
 
 use crate::prelude::*;
 
@@ -290,6 +290,7 @@ cast_from_value! {
 /// - Integers are formatted in base 10.
 /// - Floats are formatted in base 10 and never in exponential notation.
 /// - From labels the name is extracted.
+/// - Bytes are decoded as UTF-8.
 ///
 /// ## Example
 /// ```example
@@ -319,6 +320,86 @@ cast_from_value! {
     v: f64 => Self(format_str!("{}", v)),
     v: Label => Self(v.0.into()),
     v: Str => Self(v),
+    v: Bytes => Self(
+        std::str::from_utf8(&v)
+            .map_err(|_| "bytes are not valid utf-8")?
+            .into()
+    ),
+}
+
+/// Convert a value to bytes.
+///
+/// - Strings are encoded in UTF-8.
+/// - Arrays of integers between `{0}` and `{255}` are converted directly. The
+///   dedicated byte representation is much more efficient than the array
+///   representation and thus typically used for large byte buffers (e.g. image
+///   data).
+///
+/// ## Example
+/// ```example
+/// #bytes("Hello 😃") \
+/// #bytes((123, 160, 22, 0))
+/// ```
+///
+/// Display: Bytes
+/// Category: construct
+/// Returns: bytes
+#[func]
+pub fn bytes(
+    /// The value that should be converted to bytes.
+    value: ToBytes,
+) -> Value {
+    Value::Bytes(value.0)
+}
+
+/// A value that can be cast to bytes.
+struct ToBytes(Bytes);
+
+cast_from_value! {
+    ToBytes,
+    v: Str => Self(v.as_bytes().into()),
+    v: Array => Self(
+        v.into_iter()
+            .map(|item| match item {
+                Value::Int(byte @ 0..=255) => Ok(byte as u8),
+                Value::Int(_) => Err("number must be between 0 and 255"),
+                _ => Err("expected integer"),
+            })
+            .collect::<Result<Vec<u8>, _>>()?
+            .into()
+    ),
+    v: Bytes => Self(v),
+}
+
+/// Convert a value to an array.
+///
+/// Bytes are converted into an array of integers between `{0}` and `{255}`.
+/// Arrays are returned unchanged.
+///
+/// ## Example
+/// ```example
+/// #let hi = "👋"
+/// #array(bytes(hi))
+/// ```
+///
+/// Display: Array
+/// Category: construct
+/// Returns: array
+#[func]
+pub fn array(
+    /// The value that should be converted to an array.
+    value: ToArray,
+) -> Value {
+    Value::Array(value.0)
+}
+
+/// A value that can be cast to an array.
+struct ToArray(Array);
+
+cast_from_value! {
+    ToArray,
+    v: Bytes => Self(v.to_array()),
+    v: Array => Self(v),
 }
 
 /// Create a label from a string.
@@ -351,6 +432,229 @@ pub fn label(
     Value::Label(Label(name))
 }
 
+/// Create a new datetime.
+///
+/// You can specify the [datetime]($type/datetime) using a year, month, day,
+/// hour, minute, and second. A datetime that only has a date or only a time
+/// can be created by leaving out the other components.
+///
+/// ## Example
+/// ```example
+/// #let date = datetime(
+///   year: 2012,
+///   month: 8,
+///   day: 3,
+/// )
+///
+/// #date.display() \
+/// #date.display(
+///   "[day].[month].[year]"
+/// )
+/// ```
+///
+/// ## Format
+/// _Note_: Depending on which components of the datetime you specify, Typst
+/// will store it in one of the following three ways:
+/// * If you specify year, month and day, Typst will store just a date.
+/// * If you specify hour, minute and second, Typst will store just a time.
+/// * If you specify all of year, month, day, hour, minute and second, Typst
+///   will store a full datetime.
+///
+/// Depending on how it is stored, the [`display`]($type/datetime.display)
+/// method will choose a different formatting by default.
+///
+/// Display: Datetime
+/// Category: construct
+/// Returns: datetime
+#[func]
+#[scope(
+    scope.define("today", today);
+    scope
+)]
+pub fn datetime(
+    /// The year of the datetime.
+    #[named]
+    #[default]
+    year: Option<YearComponent>,
+    /// The month of the datetime.
+    #[named]
+    #[default]
+    month: Option<MonthComponent>,
+    /// The day of the datetime.
+    #[named]
+    #[default]
+    day: Option<DayComponent>,
+    /// The hour of the datetime.
+    #[named]
+    #[default]
+    hour: Option<HourComponent>,
+    /// The minute of the datetime.
+    #[named]
+    #[default]
+    minute: Option<MinuteComponent>,
+    /// The second of the datetime.
+    #[named]
+    #[default]
+    second: Option<SecondComponent>,
+) -> Value {
+    let time = match (hour, minute, second) {
+        (Some(hour), Some(minute), Some(second)) => Some((hour.0, minute.0, second.0)),
+        (None, None, None) => None,
+        _ => bail!(args.span, "time is incomplete"),
+    };
+
+    let date = match (year, month, day) {
+        (Some(year), Some(month), Some(day)) => Some((year.0, month.0, day.0)),
+        (None, None, None) => None,
+        _ => bail!(args.span, "date is incomplete"),
+    };
+
+    let datetime = match (date, time) {
+        (Some((year, month, day)), Some((hour, minute, second))) => {
+            Datetime::from_ymd_hms(year, month, day, hour, minute, second)
+                .ok_or("datetime is invalid")
+        }
+        (Some((year, month, day)), None) => {
+            Datetime::from_ymd(year, month, day).ok_or("date is invalid")
+        }
+        (None, Some((hour, minute, second))) => {
+            Datetime::from_hms(hour, minute, second).ok_or("time is invalid")
+        }
+        (None, None) => Err("at least one of date or time must be fully specified"),
+    };
+
+    Value::dynamic(datetime.at(args.span)?)
+}
+
+struct YearComponent(i32);
+struct MonthComponent(u8);
+struct DayComponent(u8);
+struct HourComponent(u8);
+struct MinuteComponent(u8);
+struct SecondComponent(u8);
+
+cast_from_value! {
+    YearComponent,
+    v: i64 => match i32::try_from(v) {
+        Ok(n) => Self(n),
+        _ => Err("year is invalid")?
+    }
+}
+
+cast_from_value! {
+    MonthComponent,
+    v: i64 => match u8::try_from(v) {
+        Ok(n) => Self(n),
+        _ => Err("month is invalid")?
+    }
+}
+
+cast_from_value! {
+    DayComponent,
+    v: i64 => match u8::try_from(v) {
+        Ok(n) => Self(n),
+        _ => Err("day is invalid")?
+    }
+}
+
+cast_from_value! {
+    HourComponent,
+    v: i64 => match u8::try_from(v) {
+        Ok(n) => Self(n),
+        _ => Err("hour is invalid")?
+    }
+}
+
+cast_from_value! {
+    MinuteComponent,
+    v: i64 => match u8::try_from(v) {
+        Ok(n) => Self(n),
+        _ => Err("minute is invalid")?
+    }
+}
+
+cast_from_value! {
+    SecondComponent,
+    v: i64 => match u8::try_from(v) {
+        Ok(n) => Self(n),
+        _ => Err("second is invalid")?
+    }
+}
+
+/// Returns the current date.
+///
+/// The date is provided by the environment Typst runs in and stays the same
+/// throughout a single compilation. The command line interface can be told to
+/// use a fixed date for reproducible builds.
+///
+/// ## Example
+/// ```example
+/// Today's date is
+/// #datetime.today().display().
+/// ```
+///
+/// Display: Today
+/// Category: construct
+/// Returns: datetime
+#[func]
+pub fn today(
+    /// An offset to apply to the current UTC date. If set to `{auto}`, the
+    /// offset will be the local offset.
+    #[named]
+    #[default]
+    offset: Smart<i64>,
+) -> Value {
+    let today = vm
+        .world()
+        .today(offset.map_or(None, Some))
+        .ok_or("unable to get the current date")
+        .at(args.span)?;
+    Value::dynamic(today)
+}
+
+/// Create a new duration.
+///
+/// A [duration]($type/duration) is the span of time between two datetimes.
+/// The components are added up, so `{duration(hours: 1, minutes: 30)}` is
+/// an hour and a half.
+///
+/// ## Example
+/// ```example
+/// #let start = datetime(year: 2023, month: 5, day: 1)
+/// #let end = start + duration(weeks: 2, days: 3)
+/// #end.display() \
+/// #(end - start).days()
+/// ```
+///
+/// Display: Duration
+/// Category: construct
+/// Returns: duration
+#[func]
+pub fn duration(
+    /// The number of seconds.
+    #[named]
+    #[default(0)]
+    seconds: i64,
+    /// The number of minutes.
+    #[named]
+    #[default(0)]
+    minutes: i64,
+    /// The number of hours.
+    #[named]
+    #[default(0)]
+    hours: i64,
+    /// The number of days.
+    #[named]
+    #[default(0)]
+    days: i64,
+    /// The number of weeks.
+    #[named]
+    #[default(0)]
+    weeks: i64,
+) -> Value {
+    Value::dynamic(Duration::new(weeks, days, hours, minutes, seconds).at(args.span)?)
+}
+
 /// Create a regular expression from a string.
 ///
 /// The result can be used as a
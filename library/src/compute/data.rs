@@ -1,36 +1,60 @@
 use std::fmt::Write;
 
 use typst::diag::{format_xml_like_error, FileError};
+use typst::eval::Datetime;
 
 use crate::prelude::*;
 
-/// Read plain text from a file.
+/// Read plain text or data from a file.
 ///
-/// The file will be read and returned as a string.
+/// By default, the file will be read as UTF-8 and returned as a string.
+///
+/// If you specify `{encoding: none}`, this returns raw [bytes]($type/bytes)
+/// instead.
 ///
 /// ## Example
 /// ```example
+/// An example for a HTML file: \
 /// #let text = read("data.html")
-///
-/// An example for a HTML file:\
 /// #raw(text, lang: "html")
+///
+/// Raw bytes:
+/// #read("tiger.jpg", encoding: none)
 /// ```
 ///
-/// Display: Plain text
+/// Display: Read
 /// Category: data-loading
-/// Returns: string
+/// Returns: string or bytes
 #[func]
 pub fn read(
     /// Path to a file.
     path: Spanned<EcoString>,
+    /// The encoding to read the file with.
+    ///
+    /// If set to `{none}`, this function returns raw bytes.
+    #[named]
+    #[default(Some(Encoding::Utf8))]
+    encoding: Option<Encoding>,
 ) -> Value {
     let Spanned { v: path, span } = path;
     let path = vm.locate(&path).at(span)?;
     let data = vm.world().file(&path).at(span)?;
-    let text = std::str::from_utf8(&data)
-        .map_err(|_| "file is not valid utf-8")
-        .at(span)?;
-    Value::Str(text.into())
+    match encoding {
+        None => Value::Bytes(data.into()),
+        Some(Encoding::Utf8) => {
+            let text = std::str::from_utf8(&data)
+                .map_err(|_| "file is not valid utf-8")
+                .at(span)?;
+            Value::Str(text.into())
+        }
+    }
+}
+
+/// An encoding of a file.
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash, Cast)]
+pub enum Encoding {
+    /// The Unicode UTF-8 encoding.
+    Utf8,
 }
 
 /// Read structured data from a CSV file.
@@ -210,6 +234,207 @@ fn format_json_error(error: serde_json::Error) -> String {
     format!("failed to parse json file: syntax error in line {}", error.line())
 }
 
+/// Read structured data from a TOML file.
+///
+/// The file must contain a valid TOML table. TOML tables will be
+/// converted into Typst dictionaries, and TOML arrays will be converted into
+/// Typst arrays. Strings, booleans and numbers will be converted into their
+/// Typst equivalents. Dates and times will be converted into
+/// [datetimes]($type/datetime) when they can be represented as such and into
+/// strings otherwise.
+///
+/// The TOML file in the example consists of a table with the keys `title`,
+/// `version`, and `authors`.
+///
+/// ## Example
+/// ```example
+/// #let details = toml("details.toml")
+///
+/// Title: #details.title \
+/// Version: #details.version \
+/// Authors: #(details.authors
+///   .join(", ", last: " and "))
+/// ```
+///
+/// Display: TOML
+/// Category: data-loading
+/// Returns: dictionary
+#[func]
+pub fn toml(
+    /// Path to a TOML file.
+    path: Spanned<EcoString>,
+) -> Value {
+    let Spanned { v: path, span } = path;
+    let path = vm.locate(&path).at(span)?;
+    let data = vm.world().file(&path).at(span)?;
+    let raw = std::str::from_utf8(&data)
+        .map_err(|_| "file is not valid utf-8")
+        .at(span)?;
+    let value: toml::Value = toml::from_str(raw)
+        .map_err(|err| format_toml_error(raw, err))
+        .at(span)?;
+    convert_toml(value)
+}
+
+/// Convert a TOML value to a Typst value.
+fn convert_toml(value: toml::Value) -> Value {
+    match value {
+        toml::Value::String(v) => Value::Str(v.into()),
+        toml::Value::Integer(v) => Value::Int(v),
+        toml::Value::Float(v) => Value::Float(v),
+        toml::Value::Boolean(v) => Value::Bool(v),
+        toml::Value::Array(v) => Value::Array(v.into_iter().map(convert_toml).collect()),
+        toml::Value::Table(v) => Value::Dict(
+            v.into_iter()
+                .map(|(key, value)| (key.into(), convert_toml(value)))
+                .collect(),
+        ),
+        toml::Value::Datetime(v) => match convert_toml_datetime(&v) {
+            Some(datetime) => Value::dynamic(datetime),
+            None => Value::Str(v.to_string().into()),
+        },
+    }
+}
+
+/// Convert a TOML datetime to a Typst datetime, if it can be represented.
+///
+/// Offsets and fractional seconds are not supported by Typst datetimes, so
+/// such values are kept as strings instead.
+fn convert_toml_datetime(v: &toml::value::Datetime) -> Option<Datetime> {
+    if v.offset.is_some() || v.time.map_or(false, |time| time.nanosecond != 0) {
+        return None;
+    }
+
+    match (v.date, v.time) {
+        (Some(date), Some(time)) => Datetime::from_ymd_hms(
+            date.year.into(),
+            date.month,
+            date.day,
+            time.hour,
+            time.minute,
+            time.second,
+        ),
+        (Some(date), None) => Datetime::from_ymd(date.year.into(), date.month, date.day),
+        (None, Some(time)) => Datetime::from_hms(time.hour, time.minute, time.second),
+        (None, None) => None,
+    }
+}
+
+/// Format the user-facing TOML error message.
+fn format_toml_error(text: &str, error: toml::de::Error) -> String {
+    match error.span() {
+        Some(range) => {
+            let (line, column) = line_and_column(text, range.start);
+            format!(
+                "failed to parse toml file: {} in line {line} column {column}",
+                error.message(),
+            )
+        }
+        None => format!("failed to parse toml file: {}", error.message()),
+    }
+}
+
+/// Determine the 1-based line and column of a byte offset in a text.
+fn line_and_column(text: &str, offset: usize) -> (usize, usize) {
+    let before = &text[..offset.min(text.len())];
+    let line_start = before.rfind('\n').map_or(0, |i| i + 1);
+    let line = before.matches('\n').count() + 1;
+    let column = before[line_start..].chars().count() + 1;
+    (line, column)
+}
+
+/// Read structured data from a YAML file.
+///
+/// The file must contain a valid YAML object or array. YAML mappings will be
+/// converted into Typst dictionaries, and YAML sequences will be converted into
+/// Typst arrays. Strings and booleans will be converted into the Typst
+/// equivalents, null values (`null`, `~` or an empty value) will be converted
+/// into `{none}`, and numbers will be converted to floats or integers depending on
+/// whether they are whole numbers. Mapping keys that are not strings are
+/// converted to their string representation.
+///
+/// The YAML files in the example contain objects with authors as keys,
+/// each with a sequence of their own submapping with the keys
+/// "title" and "published"
+///
+/// ## Example
+/// ```example
+/// #let bookshelf(contents) = {
+///   for author, works in contents {
+///     author
+///     for work in works [
+///       - #work.title (#work.published)
+///     ]
+///   }
+/// }
+///
+/// #bookshelf(yaml("scifi-authors.yaml"))
+/// ```
+///
+/// Display: YAML
+/// Category: data-loading
+/// Returns: array or dictionary or none or boolean or integer or float or string
+#[func]
+pub fn yaml(
+    /// Path to a YAML file.
+    path: Spanned<EcoString>,
+) -> Value {
+    let Spanned { v: path, span } = path;
+    let path = vm.locate(&path).at(span)?;
+    let data = vm.world().file(&path).at(span)?;
+    let value: serde_yaml::Value =
+        serde_yaml::from_slice(&data).map_err(format_yaml_error).at(span)?;
+    convert_yaml(value)
+}
+
+/// Convert a YAML value to a Typst value.
+fn convert_yaml(value: serde_yaml::Value) -> Value {
+    match value {
+        serde_yaml::Value::Null => Value::None,
+        serde_yaml::Value::Bool(v) => Value::Bool(v),
+        serde_yaml::Value::Number(v) => match v.as_i64() {
+            Some(int) => Value::Int(int),
+            None => Value::Float(v.as_f64().unwrap_or(f64::NAN)),
+        },
+        serde_yaml::Value::String(v) => Value::Str(v.into()),
+        serde_yaml::Value::Sequence(v) => {
+            Value::Array(v.into_iter().map(convert_yaml).collect())
+        }
+        serde_yaml::Value::Mapping(v) => Value::Dict(
+            v.into_iter()
+                .filter_map(|(key, value)| {
+                    convert_yaml_key(key).map(|key| (key, convert_yaml(value)))
+                })
+                .collect(),
+        ),
+    }
+}
+
+/// Convert a YAML mapping key to a dictionary key.
+///
+/// Keys that are collections cannot be represented and are skipped.
+fn convert_yaml_key(key: serde_yaml::Value) -> Option<Str> {
+    Some(match key {
+        serde_yaml::Value::Null => "null".into(),
+        serde_yaml::Value::Bool(v) => format_str!("{}", v),
+        serde_yaml::Value::Number(v) => format_str!("{}", v),
+        serde_yaml::Value::String(v) => v.into(),
+        serde_yaml::Value::Sequence(_) | serde_yaml::Value::Mapping(_) => return None,
+    })
+}
+
+/// Format the user-facing YAML error message.
+fn format_yaml_error(error: serde_yaml::Error) -> String {
+    match error.location() {
+        Some(location) => format!(
+            "failed to parse yaml file: syntax error in line {} column {}",
+            location.line(),
+            location.column(),
+        ),
+        None => "failed to parse yaml file: syntax error".into(),
+    }
+}
+
 /// Read structured data from an XML file.
 ///
 /// The XML file is parsed into an array of dictionaries and strings. XML nodes
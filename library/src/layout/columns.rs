@@ -82,6 +82,8 @@ impl Layout for ColumnsElem {
             backlog: &backlog,
             last: regions.last,
             expand: Axes::new(true, regions.expand.y),
+            root: regions.root,
+            columns: Some((columns, gutter)),
         };
 
         // Layout the children.
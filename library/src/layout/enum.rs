@@ -5,7 +5,7 @@ use crate::meta::{Numbering, NumberingPattern};
 use crate::prelude::*;
 use crate::text::TextElem;
 
-use super::GridLayouter;
+use super::{Cell, CellGrid, GridLayouter};
 
 /// A numbered list.
 ///
@@ -192,15 +192,27 @@ impl Layout for EnumElem {
                 }
             };
 
+            let tag = Tag::new(vt, Role::ListItem, &item);
             cells.push(Content::empty());
-            cells.push(resolved);
+            cells.push(
+                resolved
+                    .tagged(Tag::new(vt, Role::ListLabel, &item))
+                    .tagged(tag.clone()),
+            );
             cells.push(Content::empty());
-            cells.push(item.body().styled(Self::set_parents(Parent(number))));
+            cells.push(
+                item.body()
+                    .styled(Self::set_parents(Parent(number)))
+                    .tagged(Tag::new(vt, Role::ListBody, &item))
+                    .tagged(tag),
+            );
             number = number.saturating_add(1);
         }
 
-        let layouter = GridLayouter::new(
-            vt,
+        let tag = MetaElem::set_data(vec![Meta::Tag(Tag::new(vt, Role::List, self))]);
+        let styles = styles.chain_one(&tag);
+
+        let grid = CellGrid::new(
             Axes::with_x(&[
                 Sizing::Rel(indent.into()),
                 Sizing::Auto,
@@ -208,10 +220,10 @@ impl Layout for EnumElem {
                 Sizing::Auto,
             ]),
             Axes::with_y(&[gutter.into()]),
-            &cells,
-            regions,
+            cells.into_iter().map(Cell::from).collect(),
             styles,
         );
+        let layouter = GridLayouter::new(vt, &grid, regions, styles, self.span());
 
         Ok(layouter.layout()?.fragment)
     }
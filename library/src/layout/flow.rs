@@ -1,5 +1,10 @@
-use super::{AlignElem, BlockElem, ColbreakElem, ParElem, PlaceElem, Spacing, VElem};
+use super::{
+    AlignElem, BlockElem, ColbreakElem, ParElem, PlaceElem, PlacementScope, Spacing,
+    VElem,
+};
+use crate::meta::{FootnoteElem, FootnoteEntry};
 use crate::prelude::*;
+use crate::text::TextElem;
 use crate::visualize::{CircleElem, EllipseElem, ImageElem, RectElem, SquareElem};
 
 /// Arrange spacing, paragraphs and block-level elements into a flow.
@@ -23,7 +28,7 @@ impl Layout for FlowElem {
         styles: StyleChain,
         regions: Regions,
     ) -> SourceResult<Fragment> {
-        let mut layouter = FlowLayouter::new(regions);
+        let mut layouter = FlowLayouter::new(regions, styles);
 
         for mut child in &self.children() {
             let outer = styles;
@@ -34,7 +39,7 @@ impl Layout for FlowElem {
             }
 
             if let Some(elem) = child.to::<VElem>() {
-                layouter.layout_spacing(elem, styles);
+                layouter.layout_spacing(vt, elem, styles)?;
             } else if let Some(elem) = child.to::<ParElem>() {
                 layouter.layout_par(vt, elem, styles)?;
             } else if child.is::<RectElem>()
@@ -46,6 +51,11 @@ impl Layout for FlowElem {
                 let layoutable = child.with::<dyn Layout>().unwrap();
                 layouter.layout_single(vt, layoutable, styles)?;
             } else if child.is::<MetaElem>() {
+                // Floats carry their own metadata wherever they end up.
+                if layouter.is_floated(styles) {
+                    continue;
+                }
+
                 let mut frame = Frame::new(Size::zero());
                 frame.meta(styles, true);
                 layouter.items.push(FlowItem::Frame(
@@ -71,8 +81,12 @@ impl Layout for FlowElem {
 
 /// Performs flow layout.
 struct FlowLayouter<'a> {
+    /// Whether this is the root flow.
+    root: bool,
     /// The regions to layout children into.
     regions: Regions<'a>,
+    /// The shared styles.
+    styles: StyleChain<'a>,
     /// Whether the flow should expand to fill the region.
     expand: Axes<bool>,
     /// The initial size of `regions.size` that was available before we started
@@ -82,10 +96,36 @@ struct FlowLayouter<'a> {
     last_was_par: bool,
     /// Spacing and layouted blocks.
     items: Vec<FlowItem>,
+    /// Whether the current region already contains the footnote separator.
+    has_footnotes: bool,
+    /// Footnote configuration.
+    footnote_config: FootnoteConfig,
+    /// The number of columns and the gutter between them if the regions are
+    /// columns.
+    columns: Option<(usize, Abs)>,
+    /// The index of the current column in its row.
+    column: usize,
+    /// Floats placed into the current region.
+    floats: Vec<FloatItem>,
+    /// Floats that didn't fit into a previous region and wait for the next
+    /// one, in order.
+    pending: Vec<FloatItem>,
+    /// The locations of elements that were laid out as floats.
+    floated: Vec<Location>,
+    /// The space at the top and bottom of each column in the current row
+    /// that is reserved by floats spanning all columns.
+    spanning: (Abs, Abs),
     /// Finished frames for previous regions.
     finished: Vec<Frame>,
 }
 
+/// Cached footnote configuration.
+struct FootnoteConfig {
+    separator: Content,
+    clearance: Abs,
+    gap: Abs,
+}
+
 /// A prepared item in a flow layout.
 #[derive(Debug)]
 enum FlowItem {
@@ -97,35 +137,76 @@ enum FlowItem {
     Frame(Frame, Axes<Align>, bool),
     /// An absolutely placed frame.
     Placed(Frame),
+    /// A footnote entry or the separator above the footnotes, placed at the
+    /// bottom of the region.
+    Footnote(Frame),
+}
+
+/// A floating frame that is placed at the top or bottom of a region.
+#[derive(Debug)]
+struct FloatItem {
+    /// The laid out float.
+    frame: Frame,
+    /// Where to place the float. Resolved from `None` (auto) to the top or
+    /// bottom once the float is placed.
+    align: Option<Align>,
+    /// The space between the float and the flow.
+    clearance: Abs,
+    /// Whether the float spans all columns.
+    parent: bool,
 }
 
 impl<'a> FlowLayouter<'a> {
     /// Create a new flow layouter.
-    fn new(mut regions: Regions<'a>) -> Self {
+    fn new(mut regions: Regions<'a>, styles: StyleChain<'a>) -> Self {
         let expand = regions.expand;
 
-        // Disable vertical expansion for children.
+        // Disable vertical expansion, root status and columns for children.
         regions.expand.y = false;
+        let root = std::mem::replace(&mut regions.root, false);
+        let columns = regions.columns.take().filter(|&(count, _)| count > 1);
 
         Self {
+            root,
             regions,
+            styles,
             expand,
             initial: regions.size,
             last_was_par: false,
             items: vec![],
+            has_footnotes: false,
+            footnote_config: FootnoteConfig {
+                separator: FootnoteEntry::separator_in(styles),
+                clearance: FootnoteEntry::clearance_in(styles),
+                gap: FootnoteEntry::gap_in(styles),
+            },
+            columns,
+            column: 0,
+            floats: vec![],
+            pending: vec![],
+            floated: vec![],
+            spanning: (Abs::zero(), Abs::zero()),
             finished: vec![],
         }
     }
 
     /// Layout vertical spacing.
-    fn layout_spacing(&mut self, v: &VElem, styles: StyleChain) {
-        self.layout_item(match v.amount() {
-            Spacing::Rel(rel) => FlowItem::Absolute(
-                rel.resolve(styles).relative_to(self.initial.y),
-                v.weakness(styles) > 0,
-            ),
-            Spacing::Fr(fr) => FlowItem::Fractional(fr),
-        });
+    fn layout_spacing(
+        &mut self,
+        vt: &mut Vt,
+        v: &VElem,
+        styles: StyleChain,
+    ) -> SourceResult<()> {
+        self.layout_item(
+            vt,
+            match v.amount() {
+                Spacing::Rel(rel) => FlowItem::Absolute(
+                    rel.resolve(styles).relative_to(self.initial.y),
+                    v.weakness(styles) > 0,
+                ),
+                Spacing::Fr(fr) => FlowItem::Fractional(fr),
+            },
+        )
     }
 
     /// Layout a paragraph.
@@ -142,31 +223,20 @@ impl<'a> FlowLayouter<'a> {
             .layout(vt, styles, consecutive, self.regions.base(), self.regions.expand.x)?
             .into_frames();
 
-        let mut sticky = self.items.len();
-        for (i, item) in self.items.iter().enumerate().rev() {
-            match *item {
-                FlowItem::Absolute(_, _) => {}
-                FlowItem::Frame(.., true) => sticky = i,
-                _ => break,
-            }
-        }
-
+        // The first lines are kept together by the paragraph. If they don't
+        // fit, sticky blocks like headings move along with them.
         if let [first, ..] = frames.as_slice() {
             if !self.regions.size.y.fits(first.height()) && !self.regions.in_last() {
-                let carry: Vec<_> = self.items.drain(sticky..).collect();
-                self.finish_region();
-                for item in carry {
-                    self.layout_item(item);
-                }
+                self.carry_sticky(vt)?;
             }
         }
 
         for (i, frame) in frames.into_iter().enumerate() {
             if i > 0 {
-                self.layout_item(FlowItem::Absolute(leading, true));
+                self.layout_item(vt, FlowItem::Absolute(leading, true))?;
             }
 
-            self.layout_item(FlowItem::Frame(frame, aligns, false));
+            self.layout_item(vt, FlowItem::Frame(frame, aligns, false))?;
         }
 
         self.last_was_par = true;
@@ -185,7 +255,11 @@ impl<'a> FlowLayouter<'a> {
         let sticky = BlockElem::sticky_in(styles);
         let pod = Regions::one(self.regions.base(), Axes::splat(false));
         let frame = content.layout(vt, styles, pod)?.into_frame();
-        self.layout_item(FlowItem::Frame(frame, aligns, sticky));
+        if !sticky && !self.regions.size.y.fits(frame.height()) && !self.regions.in_last()
+        {
+            self.carry_sticky(vt)?;
+        }
+        self.layout_item(vt, FlowItem::Frame(frame, aligns, sticky))?;
         self.last_was_par = false;
         Ok(())
     }
@@ -200,9 +274,13 @@ impl<'a> FlowLayouter<'a> {
         // Placed elements that are out of flow produce placed items which
         // aren't aligned later.
         if let Some(placed) = block.to::<PlaceElem>() {
+            if placed.float(styles) {
+                return self.layout_float(vt, placed, styles);
+            }
+
             if placed.out_of_flow(styles) {
                 let frame = block.layout(vt, styles, self.regions)?.into_frame();
-                self.layout_item(FlowItem::Placed(frame));
+                self.layout_item(vt, FlowItem::Placed(frame))?;
                 return Ok(());
             }
         }
@@ -219,12 +297,23 @@ impl<'a> FlowLayouter<'a> {
 
         // Layout the block itself.
         let sticky = BlockElem::sticky_in(styles);
-        let fragment = block.layout(vt, styles, self.regions)?;
+        let mut fragment = block.layout(vt, styles, self.regions)?;
+
+        // If nothing of the block fits into this region, sticky blocks like
+        // headings move to the next region along with it.
+        if !sticky
+            && fragment.len() > 1
+            && fragment.iter().next().map_or(false, Frame::is_empty)
+            && self.carry_sticky(vt)?
+        {
+            fragment = block.layout(vt, styles, self.regions)?;
+        }
+
         for (i, frame) in fragment.into_iter().enumerate() {
             if i > 0 {
                 self.finish_region();
             }
-            self.layout_item(FlowItem::Frame(frame, aligns, sticky));
+            self.layout_item(vt, FlowItem::Frame(frame, aligns, sticky))?;
         }
 
         self.last_was_par = false;
@@ -232,8 +321,136 @@ impl<'a> FlowLayouter<'a> {
         Ok(())
     }
 
+    /// Finish the region, moving the sticky blocks at its end to the next
+    /// region so that they stay attached to what follows them. Returns
+    /// whether the region was finished.
+    ///
+    /// If the region holds nothing but sticky blocks and weak spacing, it
+    /// isn't finished as it would stay empty otherwise.
+    fn carry_sticky(&mut self, vt: &mut Vt) -> SourceResult<bool> {
+        let mut sticky = self.items.len();
+        for (i, item) in self.items.iter().enumerate().rev() {
+            match *item {
+                FlowItem::Absolute(_, _) | FlowItem::Footnote(_) => {}
+                FlowItem::Frame(.., true) => sticky = i,
+                _ => break,
+            }
+        }
+
+        let alone = self.floats.is_empty()
+            && self.items[..sticky].iter().all(|item| {
+                matches!(item, FlowItem::Absolute(_, true) | FlowItem::Footnote(_))
+            });
+        if alone && sticky < self.items.len() {
+            return Ok(false);
+        }
+
+        // Footnote entries of the carried frames are laid out anew together
+        // with them.
+        let carry: Vec<_> = self.items.drain(sticky..).collect();
+        self.finish_region();
+        for item in carry {
+            if !matches!(item, FlowItem::Footnote(_)) {
+                self.layout_item(vt, item)?;
+            }
+        }
+
+        Ok(true)
+    }
+
+    /// Layout floating content at the top or bottom of the region.
+    fn layout_float(
+        &mut self,
+        vt: &mut Vt,
+        placed: &PlaceElem,
+        styles: StyleChain,
+    ) -> SourceResult<()> {
+        // A float that spans all columns is as wide as all of them together.
+        let parent = placed.scope(styles) == PlacementScope::Parent;
+        let mut pod = self.regions;
+        if let (true, Some((count, gutter))) = (parent, self.columns) {
+            pod.size.x = pod.size.x * count as f64 + gutter * (count - 1) as f64;
+        }
+
+        let frame = placed.layout(vt, styles, pod)?.into_frame();
+        self.floated.extend(elem_locations(styles));
+
+        let align = match placed.alignment(styles).y {
+            Some(GenAlign::Specific(align)) => Some(align),
+            _ => None,
+        };
+
+        let item = FloatItem {
+            frame,
+            align,
+            clearance: placed.clearance(styles),
+            parent: parent && self.columns.is_some(),
+        };
+
+        // Keep the order of floats intact.
+        if !self.pending.is_empty() {
+            self.pending.push(item);
+        } else if let Err(item) = self.try_float(item) {
+            self.pending.push(item);
+        }
+
+        Ok(())
+    }
+
+    /// Whether all elements whose metadata is attached by the given styles
+    /// were laid out as floats.
+    fn is_floated(&self, styles: StyleChain) -> bool {
+        let mut locations = elem_locations(styles).peekable();
+        locations.peek().is_some()
+            && locations.all(|location| self.floated.contains(&location))
+    }
+
+    /// Try to place a float into the current region and give it back if it
+    /// doesn't fit.
+    fn try_float(&mut self, mut item: FloatItem) -> Result<(), FloatItem> {
+        // Floats that span all columns can only be placed into the first
+        // column of a row because the other columns might already be done.
+        if item.parent && self.column > 0 && !self.regions.in_last() {
+            return Err(item);
+        }
+
+        // A float that doesn't even fit into an empty region is placed
+        // anyway.
+        let height = item.frame.height() + item.clearance;
+        let empty = self.floats.is_empty()
+            && !self.items.iter().any(|item| {
+                matches!(item, FlowItem::Frame(frame, ..) if !frame.height().is_zero())
+            });
+        if !self.regions.size.y.fits(height) && !empty && !self.regions.in_last() {
+            return Err(item);
+        }
+
+        // Without an explicit placement, the float moves to the edge that is
+        // closer to its position in the flow.
+        let align = item.align.unwrap_or_else(|| {
+            let used = self.initial.y - self.regions.size.y;
+            if used * 2.0 > self.initial.y {
+                Align::Bottom
+            } else {
+                Align::Top
+            }
+        });
+
+        if item.parent {
+            match align {
+                Align::Bottom => self.spanning.1 += height,
+                _ => self.spanning.0 += height,
+            }
+        }
+
+        self.regions.size.y -= height;
+        item.align = Some(align);
+        self.floats.push(item);
+        Ok(())
+    }
+
     /// Layout a finished frame.
-    fn layout_item(&mut self, item: FlowItem) {
+    fn layout_item(&mut self, vt: &mut Vt, item: FlowItem) -> SourceResult<()> {
         match item {
             FlowItem::Absolute(v, _) => self.regions.size.y -= v,
             FlowItem::Fractional(_) => {}
@@ -244,11 +461,126 @@ impl<'a> FlowLayouter<'a> {
                 }
 
                 self.regions.size.y -= size.y;
+
+                if self.root {
+                    let mut notes = vec![];
+                    find_footnotes(&mut notes, frame);
+                    if !notes.is_empty() {
+                        self.items.push(item);
+                        let force = self.regions.in_last();
+                        if !self.handle_footnotes(vt, &mut notes, force)? {
+                            // The footnotes don't fit, so we move the frame
+                            // and its footnotes to the next region.
+                            let item = self.items.pop();
+                            self.finish_region();
+                            self.items.extend(item);
+                            self.regions.size.y -= size.y;
+                            self.handle_footnotes(vt, &mut notes, true)?;
+                        }
+                        return Ok(());
+                    }
+                }
             }
             FlowItem::Placed(_) => {}
+            FlowItem::Footnote(_) => {}
         }
 
         self.items.push(item);
+        Ok(())
+    }
+
+    /// Lay out the entries for the given footnotes at the bottom of the
+    /// region.
+    ///
+    /// Unless `force` is true, this returns `false` without changing anything
+    /// if an entry doesn't fit into the current region.
+    fn handle_footnotes(
+        &mut self,
+        vt: &mut Vt,
+        notes: &mut Vec<FootnoteElem>,
+        force: bool,
+    ) -> SourceResult<bool> {
+        let items_len = self.items.len();
+        let notes_len = notes.len();
+        let remaining = self.regions.size.y;
+        let had_footnotes = self.has_footnotes;
+        let mut broken = false;
+
+        // Process footnotes one at a time.
+        let mut k = 0;
+        while k < notes.len() {
+            if notes[k].is_ref() {
+                k += 1;
+                continue;
+            }
+
+            if !self.has_footnotes {
+                self.layout_footnote_separator(vt)?;
+            }
+
+            self.regions.size.y -= self.footnote_config.gap;
+            let entry = FootnoteEntry::new(notes[k].clone()).pack();
+
+            // If the entry doesn't fit, abort to keep the footnote and its
+            // entry together.
+            if !force && !broken {
+                let frames = entry.measure(vt, self.styles, self.regions)?;
+                if frames.into_iter().next().map_or(false, |frame| frame.is_empty()) {
+                    notes.truncate(notes_len);
+                    self.items.truncate(items_len);
+                    self.regions.size.y = remaining;
+                    self.has_footnotes = had_footnotes;
+                    return Ok(false);
+                }
+            }
+
+            let frames = entry.layout(vt, self.styles, self.regions)?.into_frames();
+            let prev = notes.len();
+            for (i, mut frame) in frames.into_iter().enumerate() {
+                find_footnotes(notes, &frame);
+                if i > 0 {
+                    broken = true;
+                    self.finish_region();
+                    self.layout_footnote_separator(vt)?;
+                    self.regions.size.y -= self.footnote_config.gap;
+                }
+                self.regions.size.y -= frame.height();
+
+                // The gap was already reserved above, so it becomes part of
+                // the entry's frame.
+                frame.size_mut().y += self.footnote_config.gap;
+                frame.translate(Point::with_y(self.footnote_config.gap));
+                self.items.push(FlowItem::Footnote(frame));
+            }
+
+            k += 1;
+
+            // Process the nested notes before dealing with further top-level
+            // notes.
+            let nested = notes.len() - prev;
+            if nested > 0 {
+                notes[k..].rotate_right(nested);
+            }
+        }
+
+        Ok(true)
+    }
+
+    /// Layout and save the footnote separator, typically a line.
+    fn layout_footnote_separator(&mut self, vt: &mut Vt) -> SourceResult<()> {
+        let expand = Axes::new(self.regions.expand.x, false);
+        let pod = Regions::one(self.regions.base(), expand);
+        let separator = &self.footnote_config.separator;
+
+        let mut frame = separator.layout(vt, self.styles, pod)?.into_frame();
+        frame.size_mut().y += self.footnote_config.clearance;
+        frame.translate(Point::with_y(self.footnote_config.clearance));
+
+        self.has_footnotes = true;
+        self.regions.size.y -= frame.height();
+        self.items.push(FlowItem::Footnote(frame));
+
+        Ok(())
     }
 
     /// Finish the frame for one region.
@@ -262,9 +594,32 @@ impl<'a> FlowLayouter<'a> {
             self.items.pop();
         }
 
+        // Determine the space taken by floats. In all but the first column of
+        // a row, floats spanning all columns take up space, too.
+        let (spanning_top, spanning_bottom) =
+            if self.column > 0 { self.spanning } else { (Abs::zero(), Abs::zero()) };
+        let mut float_top = Abs::zero();
+        let mut float_bottom = Abs::zero();
+        let mut float_width = Abs::zero();
+        for float in &self.floats {
+            let height = float.frame.height() + float.clearance;
+            match float.align {
+                Some(Align::Bottom) => float_bottom += height,
+                _ => float_top += height,
+            }
+            if !float.parent {
+                float_width.set_max(float.frame.width());
+            }
+        }
+
         // Determine the used size.
         let mut fr = Fr::zero();
-        let mut used = Size::zero();
+        let mut used = Size::new(
+            float_width,
+            spanning_top + float_top + float_bottom + spanning_bottom,
+        );
+        let mut footnote_height = Abs::zero();
+        let mut has_footnotes = false;
         for item in &self.items {
             match item {
                 FlowItem::Absolute(v, _) => used.y += *v,
@@ -275,19 +630,29 @@ impl<'a> FlowLayouter<'a> {
                     used.x.set_max(size.x);
                 }
                 FlowItem::Placed(_) => {}
+                FlowItem::Footnote(frame) => {
+                    let size = frame.size();
+                    footnote_height += size.y;
+                    used.y += size.y;
+                    used.x.set_max(size.x);
+                    has_footnotes = true;
+                }
             }
         }
 
         // Determine the size of the flow in this region depending on whether
-        // the region expands. Also account for fractional spacing.
+        // the region expands. Also account for fractional spacing, footnotes
+        // and bottom floats, which are placed at the very bottom.
         let mut size = self.expand.select(self.initial, used).min(self.initial);
-        if fr.get() > 0.0 && self.initial.y.is_finite() {
+        let at_bottom = has_footnotes || !(float_bottom + spanning_bottom).is_zero();
+        if (fr.get() > 0.0 || at_bottom) && self.initial.y.is_finite() {
             size.y = self.initial.y;
         }
 
         let mut output = Frame::new(size);
-        let mut offset = Abs::zero();
+        let mut offset = spanning_top + float_top;
         let mut ruler = Align::Top;
+        let mut footnote_offset = size.y - footnote_height;
 
         // Place all frames.
         for item in self.items.drain(..) {
@@ -310,13 +675,67 @@ impl<'a> FlowLayouter<'a> {
                 FlowItem::Placed(frame) => {
                     output.push_frame(Point::zero(), frame);
                 }
+                FlowItem::Footnote(frame) => {
+                    let y = footnote_offset;
+                    footnote_offset += frame.height();
+                    output.push_frame(Point::with_y(y), frame);
+                }
             }
         }
 
+        // Place the floats. Floats that span all columns start in the first
+        // column and reach into the others, which lie to its left in
+        // right-to-left text.
+        let rtl = TextElem::dir_in(self.styles) == Dir::RTL;
+        let mut top = spanning_top;
+        let mut bottom = size.y - footnote_height - spanning_bottom - float_bottom;
+        for float in self.floats.drain(..) {
+            let x = if float.parent && rtl {
+                size.x - float.frame.width()
+            } else {
+                Abs::zero()
+            };
+
+            let y = if float.align == Some(Align::Bottom) {
+                bottom += float.clearance;
+                let y = bottom;
+                bottom += float.frame.height();
+                y
+            } else {
+                let y = top;
+                top += float.frame.height() + float.clearance;
+                y
+            };
+
+            output.push_frame(Point::new(x, y), float.frame);
+        }
+
         // Advance to the next region.
         self.finished.push(output);
         self.regions.next();
         self.initial = self.regions.size;
+        self.has_footnotes = false;
+
+        // Advance to the next column and reserve the space of floats that
+        // span the current row.
+        if let Some((count, _)) = self.columns {
+            self.column = (self.column + 1) % count;
+            if self.column == 0 {
+                self.spanning = (Abs::zero(), Abs::zero());
+            } else {
+                self.regions.size.y -= self.spanning.0 + self.spanning.1;
+            }
+        }
+
+        // Place deferred floats.
+        let mut pending = std::mem::take(&mut self.pending).into_iter();
+        for item in pending.by_ref() {
+            if let Err(item) = self.try_float(item) {
+                self.pending.push(item);
+                break;
+            }
+        }
+        self.pending.extend(pending);
     }
 
     /// Finish layouting and return the resulting fragment.
@@ -327,7 +746,36 @@ impl<'a> FlowLayouter<'a> {
             }
         }
 
+        // Deferred floats end up in regions of their own.
         self.finish_region();
+        while !self.floats.is_empty() || !self.pending.is_empty() {
+            self.finish_region();
+        }
+
         Fragment::frames(self.finished)
     }
 }
+
+/// The locations of the elements whose metadata is attached by the styles.
+fn elem_locations(styles: StyleChain) -> impl Iterator<Item = Location> {
+    MetaElem::data_in(styles).into_iter().filter_map(|meta| match meta {
+        Meta::Elem(content) => content.location(),
+        _ => None,
+    })
+}
+
+/// Finds all footnotes in the frame.
+fn find_footnotes(notes: &mut Vec<FootnoteElem>, frame: &Frame) {
+    for (_, item) in frame.items() {
+        match item {
+            FrameItem::Group(group) => find_footnotes(notes, &group.frame),
+            FrameItem::Meta(Meta::Elem(content), _)
+                if !notes.iter().any(|note| note.0.location() == content.location()) =>
+            {
+                let Some(footnote) = content.to::<FootnoteElem>() else { continue };
+                notes.push(footnote.clone());
+            }
+            _ => {}
+        }
+    }
+}
@@ -1,7 +1,9 @@
+use std::ops::Range;
+
 use crate::prelude::*;
 use crate::text::TextElem;
 
-use super::Sizing;
+use super::{AlignElem, Sizing};
 
 /// Arrange content in a grid.
 ///
@@ -34,6 +36,12 @@ use super::Sizing;
 /// instead of an array. For example, `columns:` `{3}` is equivalent to
 /// `columns:` `{(auto, auto, auto)}`.
 ///
+/// Cells are populated in row-major order. To merge cells or to place a cell
+/// at a specific position, use the [`grid.cell`]($func/grid.cell) element. To
+/// repeat rows at the top or bottom of every region the grid spans, group them
+/// with [`grid.header`]($func/grid.header) or
+/// [`grid.footer`]($func/grid.footer).
+///
 /// ## Example
 /// ```example
 /// #set text(10pt, style: "italic")
@@ -62,6 +70,12 @@ use super::Sizing;
 /// Display: Grid
 /// Category: layout
 #[element(Layout)]
+#[scope(
+    scope.define("cell", GridCell::func());
+    scope.define("header", GridHeader::func());
+    scope.define("footer", GridFooter::func());
+    scope
+)]
 pub struct GridElem {
     /// Defines the column sizes.
     ///
@@ -94,7 +108,7 @@ pub struct GridElem {
     #[parse(args.named("row-gutter")?.or_else(|| gutter.clone()))]
     pub row_gutter: TrackSizings,
 
-    /// The contents of the table cells.
+    /// The contents of the grid cells.
     ///
     /// The cells are populated in row-major order.
     #[variadic]
@@ -108,22 +122,84 @@ impl Layout for GridElem {
         styles: StyleChain,
         regions: Regions,
     ) -> SourceResult<Fragment> {
+        let tracks = Axes::new(self.columns(styles).0, self.rows(styles).0);
+        let gutter = Axes::new(self.column_gutter(styles).0, self.row_gutter(styles).0);
+
+        // Determine the position of each cell.
+        let mut occupancy = Occupancy::new(tracks.x.len().max(1));
+        let mut cells = vec![];
+        let mut header = None;
+        let mut footer = None;
+        for child in self.children() {
+            let span = child.span();
+            if let Some(group) = child.to::<GridHeader>() {
+                if header.is_some() {
+                    bail!(span, "cannot have more than one header");
+                }
+                let start = occupancy.next_row();
+                if start > 0 {
+                    bail!(span, "header must start at the first row");
+                }
+                for child in group.children() {
+                    cells.push(Self::place(&mut occupancy, child, styles)?);
+                }
+                let rows = start..occupancy.next_row();
+                header = Some(RowGroup { rows, repeat: group.repeat(styles) });
+            } else if let Some(group) = child.to::<GridFooter>() {
+                if footer.is_some() {
+                    bail!(span, "cannot have more than one footer");
+                }
+                let start = occupancy.next_row();
+                for child in group.children() {
+                    cells.push(Self::place(&mut occupancy, child, styles)?);
+                }
+                let rows = start..occupancy.next_row();
+                footer = Some((RowGroup { rows, repeat: group.repeat(styles) }, span));
+            } else {
+                cells.push(Self::place(&mut occupancy, child, styles)?);
+            }
+        }
+
+        if let Some((footer, span)) = &footer {
+            occupancy.check_footer(footer, tracks.y.len()).at(*span)?;
+        }
+
         // Prepare grid layout by unifying content and gutter tracks.
-        let cells = self.children();
-        let layouter = GridLayouter::new(
-            vt,
-            Axes::new(&self.columns(styles).0, &self.rows(styles).0),
-            Axes::new(&self.column_gutter(styles).0, &self.row_gutter(styles).0),
-            &cells,
-            regions,
-            styles,
-        );
+        let grid =
+            CellGrid::with_positions(tracks.as_deref(), gutter.as_deref(), cells, styles)
+                .with_header(header)
+                .with_footer(footer.map(|(footer, _)| footer));
 
         // Measure the columns and layout the grid row-by-row.
+        let layouter = GridLayouter::new(vt, &grid, regions, styles, self.span());
         Ok(layouter.layout()?.fragment)
     }
 }
 
+impl GridElem {
+    /// Determine the position of a child and prepare it for layout.
+    fn place(
+        occupancy: &mut Occupancy,
+        child: Content,
+        styles: StyleChain,
+    ) -> SourceResult<(Axes<usize>, Cell)> {
+        let span = child.span();
+        let cell = child
+            .to::<GridCell>()
+            .cloned()
+            .unwrap_or_else(|| GridCell::new(child));
+        let pos = occupancy
+            .place(
+                cell.x(styles),
+                cell.y(styles),
+                cell.colspan(styles),
+                cell.rowspan(styles),
+            )
+            .at(span)?;
+        Ok((pos, cell.into_cell(styles)))
+    }
+}
+
 /// Track sizing definitions.
 #[derive(Debug, Default, Clone, Eq, PartialEq, Hash)]
 pub struct TrackSizings(pub Vec<Sizing>);
@@ -139,78 +215,477 @@ cast_to_value! {
     v: TrackSizings => v.0.into()
 }
 
-/// Performs grid layout.
-pub struct GridLayouter<'a, 'v> {
-    /// The core context.
-    vt: &'a mut Vt<'v>,
-    /// The grid cells.
-    cells: &'a [Content],
-    /// Whether this is an RTL grid.
-    is_rtl: bool,
-    /// Whether this grid has gutters.
-    has_gutter: bool,
+/// A cell in the grid.
+///
+/// Use this element to merge cells by letting them span multiple columns or
+/// rows, to place a cell at a specific position or to configure a single cell.
+/// Cells without an explicit position are placed into the next free slots in
+/// row-major order.
+///
+/// ## Example
+/// ```example
+/// #grid(
+///   columns: 3,
+///   gutter: 3pt,
+///   grid.cell(colspan: 2, fill: aqua)[Wide],
+///   grid.cell(rowspan: 2, fill: teal)[Tall],
+///   rect[A], rect[B],
+///   grid.cell(x: 0, y: 2, fill: eastern)[Below],
+/// )
+/// ```
+///
+/// Display: Grid Cell
+/// Category: layout
+#[element(Show)]
+pub struct GridCell {
+    /// The cell's body.
+    #[required]
+    pub body: Content,
+
+    /// The cell's column, starting at zero. If set to `{auto}`, the cell is
+    /// placed into the next column that has space for it.
+    pub x: Smart<usize>,
+
+    /// The cell's row, starting at zero. If set to `{auto}`, the cell is
+    /// placed into the next row that has space for it.
+    pub y: Smart<usize>,
+
+    /// The number of columns the cell spans.
+    #[default(NonZeroUsize::ONE)]
+    pub colspan: NonZeroUsize,
+
+    /// The number of rows the cell spans.
+    #[default(NonZeroUsize::ONE)]
+    pub rowspan: NonZeroUsize,
+
+    /// How to fill the cell.
+    pub fill: Option<Paint>,
+
+    /// How to align the cell's content. If set to `{auto}`, the outer
+    /// alignment is used.
+    pub align: Smart<Axes<Option<GenAlign>>>,
+
+    /// How much to pad the cell's content.
+    pub inset: Rel<Length>,
+
+    /// How to stroke the cell's sides.
+    ///
+    /// Accepts the same values as the [rectangle's stroke]($func/rect.stroke).
+    #[resolve]
+    pub stroke: Sides<Option<Option<PartialStroke>>>,
+}
+
+impl GridCell {
+    /// Prepare the cell for layout.
+    fn into_cell(&self, styles: StyleChain) -> Cell {
+        let mut body = self.body();
+
+        let inset = self.inset(styles);
+        if !inset.is_zero() {
+            body = body.padded(Sides::splat(inset));
+        }
+
+        if let Smart::Custom(alignment) = self.align(styles) {
+            body = body.styled(AlignElem::set_alignment(alignment));
+        }
+
+        Cell {
+            body,
+            fill: self.fill(styles),
+            stroke: self.stroke(styles),
+            base_stroke: Sides::splat(None),
+            colspan: self.colspan(styles),
+            rowspan: self.rowspan(styles),
+        }
+    }
+}
+
+impl Show for GridCell {
+    fn show(&self, _: &mut Vt, _: StyleChain) -> SourceResult<Content> {
+        Ok(self.body())
+    }
+}
+
+/// The header of a grid.
+///
+/// The rows of the header are repeated at the top of every region the grid
+/// spans, for example on every page. A header is never left on its own at the
+/// bottom of a region: If none of the following rows fit below it, it moves to
+/// the next region along with them.
+///
+/// The header must be the first child of the grid. Its cells are placed like
+/// any others and may be [`grid.cell`]($func/grid.cell) elements.
+///
+/// ## Example
+/// ```example
+/// #set page(height: 100pt)
+/// #grid(
+///   columns: 2,
+///   gutter: 5pt,
+///   grid.header(strong[Name], strong[Score]),
+///   ..range(6).map(i => ([Player #i], [#(i * 10)])).flatten(),
+/// )
+/// ```
+///
+/// Display: Grid Header
+/// Category: layout
+#[element(Show)]
+pub struct GridHeader {
+    /// Whether the header is repeated in every region. If `{false}`, it only
+    /// appears at the start of the grid.
+    #[default(true)]
+    pub repeat: bool,
+
+    /// The cells of the header.
+    #[variadic]
+    pub children: Vec<Content>,
+}
+
+impl Show for GridHeader {
+    fn show(&self, _: &mut Vt, _: StyleChain) -> SourceResult<Content> {
+        Ok(Content::sequence(self.children()))
+    }
+}
+
+/// The footer of a grid.
+///
+/// The rows of the footer are repeated at the bottom of every region the grid
+/// spans, for example on every page.
+///
+/// The footer must be the last child of the grid. Its cells are placed like
+/// any others and may be [`grid.cell`]($func/grid.cell) elements.
+///
+/// Display: Grid Footer
+/// Category: layout
+#[element(Show)]
+pub struct GridFooter {
+    /// Whether the footer is repeated in every region. If `{false}`, it only
+    /// appears at the end of the grid.
+    #[default(true)]
+    pub repeat: bool,
+
+    /// The cells of the footer.
+    #[variadic]
+    pub children: Vec<Content>,
+}
+
+impl Show for GridFooter {
+    fn show(&self, _: &mut Vt, _: StyleChain) -> SourceResult<Content> {
+        Ok(Content::sequence(self.children()))
+    }
+}
+
+/// A cell that is ready for layout.
+#[derive(Debug, Clone)]
+pub struct Cell {
+    /// The cell's body.
+    pub body: Content,
+    /// The cell's background.
+    pub fill: Option<Paint>,
+    /// Overrides for the strokes of the grid lines at the cell's sides.
+    /// `{None}` keeps the stroke requested by the adjacent cells for that side.
+    pub stroke: Sides<Option<Option<PartialStroke<Abs>>>>,
+    /// The strokes that the grid requests at the cell's sides. A line is drawn
+    /// if either of the cells it separates requests a stroke for it.
+    pub base_stroke: Sides<Option<PartialStroke<Abs>>>,
+    /// The number of columns the cell spans.
+    pub colspan: NonZeroUsize,
+    /// The number of rows the cell spans.
+    pub rowspan: NonZeroUsize,
+}
+
+impl Cell {
+    /// The cell's body, marked as an artifact if the cell is part of a
+    /// repeated copy of a header or footer.
+    fn content(&self, vt: &mut Vt, artifact: bool) -> Content {
+        if artifact {
+            self.body.clone().tagged(Tag::new(vt, Role::Artifact, &self.body))
+        } else {
+            self.body.clone()
+        }
+    }
+}
+
+impl From<Content> for Cell {
+    fn from(body: Content) -> Self {
+        Self {
+            body,
+            fill: None,
+            stroke: Sides::splat(None),
+            base_stroke: Sides::splat(None),
+            colspan: NonZeroUsize::ONE,
+            rowspan: NonZeroUsize::ONE,
+        }
+    }
+}
+
+/// The maximum number of slots in a grid. Cells that would extend a grid
+/// beyond it are rejected instead of filling it with empty slots.
+const MAX_SLOTS: usize = 1 << 16;
+
+/// Keeps track of the occupied slots of a grid while determining the
+/// positions of its cells.
+pub struct Occupancy {
+    /// The number of columns.
+    columns: usize,
+    /// Whether each slot is taken, in row-major order.
+    taken: Vec<bool>,
+    /// The slot from which on cells without an explicit position are placed.
+    cursor: usize,
+    /// The column after and the row below the most recently placed cell.
+    after: Axes<usize>,
+}
+
+impl Occupancy {
+    /// Create an empty grid with the given number of columns.
+    pub fn new(columns: usize) -> Self {
+        Self {
+            columns,
+            taken: vec![],
+            cursor: 0,
+            after: Axes::splat(0),
+        }
+    }
+
+    /// Find the position of a cell and mark the slots it spans as taken.
+    ///
+    /// A cell with an explicit column and row is placed exactly there. If
+    /// only one of them is given, the cell is placed into the first free
+    /// slots in that column or row. Otherwise, it is placed into the next
+    /// free slots in row-major order.
+    pub fn place(
+        &mut self,
+        x: Smart<usize>,
+        y: Smart<usize>,
+        colspan: NonZeroUsize,
+        rowspan: NonZeroUsize,
+    ) -> StrResult<Axes<usize>> {
+        let c = self.columns;
+        let (colspan, rowspan) = (colspan.get(), rowspan.get());
+        if colspan > c {
+            return Err(eco_format!(
+                "cell spans {colspan} columns, but the grid only has {c}"
+            ));
+        }
+
+        // Reject overlong cells before searching for free slots for them.
+        let max = MAX_SLOTS / c;
+        if rowspan > max {
+            return Err(eco_format!(
+                "cell spans {rowspan} rows, but a grid can have at most {max}"
+            ));
+        }
+
+        let pos = match (x, y) {
+            (Smart::Custom(x), Smart::Custom(y)) => {
+                self.check_column(x, colspan)?;
+                self.check_row(y, rowspan)?;
+                if !self.is_free(x, y, colspan, rowspan) {
+                    return Err(eco_format!(
+                        "attempted to place a second cell at column {x}, row {y}"
+                    ));
+                }
+                Axes::new(x, y)
+            }
+            (Smart::Custom(x), Smart::Auto) => {
+                self.check_column(x, colspan)?;
+                let y = (0..).find(|&y| self.is_free(x, y, colspan, rowspan)).unwrap();
+                Axes::new(x, y)
+            }
+            (Smart::Auto, Smart::Custom(y)) => {
+                self.check_row(y, rowspan)?;
+                let Some(x) =
+                    (0..=c - colspan).find(|&x| self.is_free(x, y, colspan, rowspan))
+                else {
+                    return Err(eco_format!("row {y} has no space left for this cell"));
+                };
+                Axes::new(x, y)
+            }
+            (Smart::Auto, Smart::Auto) => {
+                let mut i = self.cursor;
+                while i % c + colspan > c || !self.is_free(i % c, i / c, colspan, rowspan)
+                {
+                    i += 1;
+                }
+                self.cursor = i + 1;
+                Axes::new(i % c, i / c)
+            }
+        };
+
+        // Cells placed automatically may still end up beyond the last row.
+        self.check_row(pos.y, rowspan)?;
+
+        let len = (pos.y + rowspan) * c;
+        if self.taken.len() < len {
+            self.taken.resize(len, false);
+        }
+
+        for y in pos.y..pos.y + rowspan {
+            for x in pos.x..pos.x + colspan {
+                self.taken[y * c + x] = true;
+            }
+        }
+
+        self.after = Axes::new(pos.x + colspan, pos.y + 1);
+        Ok(pos)
+    }
+
+    /// The positions of all free slots in the rows that contain cells and,
+    /// at least, in the first `rows` rows.
+    pub fn free(&self, rows: usize) -> Vec<Axes<usize>> {
+        let len = self.taken.len().max(rows * self.columns);
+        (0..len)
+            .filter(|&i| !self.taken.get(i).copied().unwrap_or(false))
+            .map(|i| Axes::new(i % self.columns, i / self.columns))
+            .collect()
+    }
+
+    /// The column after and the row below the most recently placed cell,
+    /// where lines without an explicit position are drawn.
+    pub fn after(&self) -> Axes<usize> {
+        self.after
+    }
+
+    /// The number of rows that contain occupied slots.
+    pub fn rows(&self) -> usize {
+        self.taken.len() / self.columns
+    }
+
+    /// Move on to the start of the first row after all occupied slots and
+    /// return its index.
+    pub fn next_row(&mut self) -> usize {
+        let c = self.columns;
+        let row = self.rows().max((self.cursor + c - 1) / c);
+        self.cursor = row * c;
+        row
+    }
+
+    /// Ensure that no rows follow the footer, given the number of explicitly
+    /// sized rows.
+    pub fn check_footer(&self, footer: &RowGroup, rows: usize) -> StrResult<()> {
+        if footer.rows.end < self.rows().max(rows) {
+            return Err("footer must end at the last row".into());
+        }
+        Ok(())
+    }
+
+    /// Ensure that a cell starting at column `x` fits into the grid.
+    fn check_column(&self, x: usize, colspan: usize) -> StrResult<()> {
+        if x + colspan > self.columns {
+            return Err(eco_format!(
+                "cell at column {x} spans {colspan} columns, \
+                 but the grid only has {}",
+                self.columns
+            ));
+        }
+        Ok(())
+    }
+
+    /// Ensure that a cell starting at row `y` fits into the grid.
+    fn check_row(&self, y: usize, rowspan: usize) -> StrResult<()> {
+        let fits = y
+            .checked_add(rowspan)
+            .and_then(|end| end.checked_mul(self.columns))
+            .map_or(false, |len| len <= MAX_SLOTS);
+        if !fits {
+            return Err(eco_format!(
+                "cell at row {y} does not fit into the grid, \
+                 which can have at most {} rows",
+                MAX_SLOTS / self.columns
+            ));
+        }
+        Ok(())
+    }
+
+    /// Whether all slots that a cell at the given position would span are
+    /// free.
+    fn is_free(&self, x: usize, y: usize, colspan: usize, rowspan: usize) -> bool {
+        (y..y + rowspan).all(|y| {
+            (x..x + colspan)
+                .all(|x| !self.taken.get(y * self.columns + x).copied().unwrap_or(false))
+        })
+    }
+}
+
+/// A grid of cells with unified content and gutter tracks.
+#[derive(Debug, Clone)]
+pub struct CellGrid {
+    /// The grid's slots in row-major order, including gutter slots. Gutter
+    /// slots are `None` unless a cell spans them.
+    entries: Vec<Option<Entry>>,
     /// The column tracks including gutter tracks.
     cols: Vec<Sizing>,
     /// The row tracks including gutter tracks.
     rows: Vec<Sizing>,
-    /// The regions to layout children into.
-    regions: Regions<'a>,
-    /// The inherited styles.
-    styles: StyleChain<'a>,
-    /// Resolved column sizes.
-    rcols: Vec<Abs>,
-    /// The sum of `rcols`.
-    width: Abs,
-    /// Resolve row sizes, by region.
-    rrows: Vec<Vec<RowPiece>>,
-    /// Rows in the current region.
-    lrows: Vec<Row>,
-    /// The initial size of the current region before we started subtracting.
-    initial: Size,
-    /// Frames for finished regions.
-    finished: Vec<Frame>,
+    /// Whether this grid has gutters.
+    has_gutter: bool,
+    /// Lines drawn at explicit positions, in track coordinates.
+    lines: Vec<Line>,
+    /// The header's row tracks, including the gutter after it.
+    header: Option<RowGroup>,
+    /// The footer's row tracks, including the gutter before it.
+    footer: Option<RowGroup>,
 }
 
-/// The resulting sizes of columns and rows in a grid.
-#[derive(Debug)]
-pub struct GridLayout {
-    /// The fragment.
-    pub fragment: Fragment,
-    /// The column widths.
-    pub cols: Vec<Abs>,
-    /// The heights of the resulting rows segments, by region.
-    pub rows: Vec<Vec<RowPiece>>,
+/// A line drawn along a boundary between the tracks of a grid.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct Line {
+    /// The axis along which the line separates the tracks: `Y` for a
+    /// horizontal line between rows and `X` for a vertical line between
+    /// columns.
+    pub axis: Axis,
+    /// The boundary at which the line is drawn, where zero is the start of
+    /// the first track.
+    pub index: usize,
+    /// The tracks along the line that it spans.
+    pub range: Range<usize>,
+    /// The line's stroke. `{None}` removes the grid's lines there.
+    pub stroke: Option<PartialStroke<Abs>>,
 }
 
-/// Details about a resulting row piece.
-#[derive(Debug)]
-pub struct RowPiece {
-    /// The height of the segment.
-    pub height: Abs,
-    /// The index of the row.
-    pub y: usize,
+/// The rows that make up the header or footer of a grid.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct RowGroup {
+    /// The range of rows.
+    pub rows: Range<usize>,
+    /// Whether the rows are repeated in every region.
+    pub repeat: bool,
 }
 
-/// Produced by initial row layout, auto and relative rows are already finished,
-/// fractional rows not yet.
-enum Row {
-    /// Finished row frame of auto or relative row with y index.
-    Frame(Frame, usize),
-    /// Fractional row with y index.
-    Fr(Fr, usize),
+/// A slot in a cell grid.
+#[derive(Debug, Clone)]
+enum Entry {
+    /// A cell that starts at this slot.
+    Cell(Cell),
+    /// A slot that is spanned by the cell starting at the slot with the given
+    /// index.
+    Merged(usize),
 }
 
-impl<'a, 'v> GridLayouter<'a, 'v> {
-    /// Create a new grid layouter.
-    ///
-    /// This prepares grid layout by unifying content and gutter tracks.
+impl CellGrid {
+    /// Arrange cells that span a single slot each in row-major order.
     pub fn new(
-        vt: &'a mut Vt<'v>,
         tracks: Axes<&[Sizing]>,
         gutter: Axes<&[Sizing]>,
-        cells: &'a [Content],
-        regions: Regions<'a>,
-        styles: StyleChain<'a>,
+        cells: Vec<Cell>,
+        styles: StyleChain,
+    ) -> Self {
+        let c = tracks.x.len().max(1);
+        let cells = cells
+            .into_iter()
+            .enumerate()
+            .map(|(i, cell)| (Axes::new(i % c, i / c), cell))
+            .collect();
+        Self::with_positions(tracks, gutter, cells, styles)
+    }
+
+    /// Arrange cells at the given positions, which must not overlap. Free
+    /// slots are filled with empty cells.
+    pub fn with_positions(
+        tracks: Axes<&[Sizing]>,
+        gutter: Axes<&[Sizing]>,
+        cells: Vec<(Axes<usize>, Cell)>,
+        styles: StyleChain,
     ) -> Self {
         let mut cols = vec![];
         let mut rows = vec![];
@@ -219,13 +694,13 @@ impl<'a, 'v> GridLayouter<'a, 'v> {
         let c = tracks.x.len().max(1);
 
         // Number of content rows: At least as many as given, but also at least
-        // as many as needed to place each item.
-        let r = {
-            let len = cells.len();
-            let given = tracks.y.len();
-            let needed = len / c + (len % c).clamp(0, 1);
-            given.max(needed)
-        };
+        // as many as needed to place each cell.
+        let r = cells
+            .iter()
+            .map(|(pos, cell)| pos.y + cell.rowspan.get())
+            .max()
+            .unwrap_or(0)
+            .max(tracks.y.len());
 
         let has_gutter = gutter.any(|tracks| !tracks.is_empty());
         let auto = Sizing::Auto;
@@ -262,9 +737,339 @@ impl<'a, 'v> GridLayouter<'a, 'v> {
             cols.reverse();
         }
 
-        let rcols = vec![Abs::zero(); cols.len()];
-        let lrows = vec![];
+        // Place the cells. Cells spanning multiple slots also span the gutter
+        // between them.
+        let w = cols.len();
+        let step = if has_gutter { 2 } else { 1 };
+        let span = |n: NonZeroUsize| step * (n.get() - 1) + 1;
+        let mut entries = vec![None; w * rows.len()];
+        for (pos, cell) in cells {
+            // Columns are reversed for RTL, so the cells must be, too.
+            let x = if is_rtl { c - pos.x - cell.colspan.get() } else { pos.x };
+            let parent = step * pos.y * w + step * x;
+            for dy in 0..span(cell.rowspan) {
+                for dx in 0..span(cell.colspan) {
+                    entries[parent + dy * w + dx] = Some(Entry::Merged(parent));
+                }
+            }
+            entries[parent] = Some(Entry::Cell(cell));
+        }
+
+        // Fill the free content slots.
+        for y in (0..rows.len()).step_by(step) {
+            for x in (0..w).step_by(step) {
+                let entry = &mut entries[y * w + x];
+                if entry.is_none() {
+                    *entry = Some(Entry::Cell(Cell::from(Content::empty())));
+                }
+            }
+        }
+
+        Self {
+            entries,
+            cols,
+            rows,
+            has_gutter,
+            lines: vec![],
+            header: None,
+            footer: None,
+        }
+    }
+
+    /// Make the given content rows the grid's header. The header is extended
+    /// to the end of the cells spanning out of it.
+    pub fn with_header(mut self, header: Option<RowGroup>) -> Self {
+        self.header = header.map(|RowGroup { rows, repeat }| {
+            let mut end = self.track(rows.end).min(self.rows.len());
+            let mut y = 0;
+            while y < end {
+                end = end.max(self.row_group_end(y));
+                y += 1;
+            }
+            RowGroup { rows: 0..end, repeat }
+        });
+        self
+    }
+
+    /// Make the given content rows the grid's footer. The footer is extended
+    /// to the start of the cells spanning into it.
+    pub fn with_footer(mut self, footer: Option<RowGroup>) -> Self {
+        self.footer = footer.map(|RowGroup { rows, repeat }| {
+            let start = self.track(rows.start);
+            let start = if self.has_gutter { start.saturating_sub(1) } else { start };
+            let header = self.header.as_ref().map_or(0, |header| header.rows.end);
+            let start = start.clamp(header, self.rows.len());
+            let start = (header..start)
+                .find(|&y| self.row_group_end(y) > start)
+                .unwrap_or(start);
+            RowGroup { rows: start..self.rows.len(), repeat }
+        });
+        self
+    }
+
+    /// Draw lines at the given boundaries between content tracks. Lines that
+    /// come later take precedence.
+    pub fn with_lines(mut self, lines: Vec<Line>, styles: StyleChain) -> Self {
+        let is_rtl = TextElem::dir_in(styles) == Dir::RTL;
+        let c = self.count(self.cols.len());
+        let r = self.count(self.rows.len());
+        for Line { axis, index, range, stroke } in lines {
+            // The number of tracks across and along the line.
+            let (across, along) = match axis {
+                Axis::X => ((c, self.cols.len()), (r, self.rows.len())),
+                Axis::Y => ((r, self.rows.len()), (c, self.cols.len())),
+            };
+
+            let mut index = index.min(across.0);
+            let mut range = range.start.min(along.0)..range.end.min(along.0);
+
+            // Columns are reversed for RTL, so the lines must be, too.
+            if is_rtl {
+                match axis {
+                    Axis::X => index = c - index,
+                    Axis::Y => range = c - range.end..c - range.start,
+                }
+            }
+
+            // A line spanning multiple tracks also spans the gutter between
+            // them.
+            let end = self.track(range.end).saturating_sub(self.has_gutter as usize);
+            self.lines.push(Line {
+                axis,
+                index: self.track(index).min(across.1),
+                range: self.track(range.start)..end.min(along.1),
+                stroke,
+            });
+        }
+        self
+    }
+
+    /// The number of content tracks among the given number of tracks.
+    fn count(&self, tracks: usize) -> usize {
+        if self.has_gutter {
+            (tracks + 1) / 2
+        } else {
+            tracks
+        }
+    }
+
+    /// The track index of the `n`-th content track.
+    fn track(&self, n: usize) -> usize {
+        if self.has_gutter {
+            2 * n
+        } else {
+            n
+        }
+    }
+
+    /// The cell that starts at the slot in column `x` and row `y`.
+    ///
+    /// Returns `None` if it's a gutter slot or a slot spanned by another cell.
+    #[track_caller]
+    fn cell(&self, x: usize, y: usize) -> Option<&Cell> {
+        assert!(x < self.cols.len());
+        assert!(y < self.rows.len());
+        match &self.entries[y * self.cols.len() + x] {
+            Some(Entry::Cell(cell)) => Some(cell),
+            _ => None,
+        }
+    }
+
+    /// The cell that spans the slot in column `x` and row `y` along with the
+    /// index of the slot it starts at.
+    #[track_caller]
+    fn parent(&self, x: usize, y: usize) -> Option<(usize, &Cell)> {
+        let index = y * self.cols.len() + x;
+        match self.entries[index].as_ref()? {
+            Entry::Cell(cell) => Some((index, cell)),
+            &Entry::Merged(parent) => match &self.entries[parent] {
+                Some(Entry::Cell(cell)) => Some((parent, cell)),
+                _ => None,
+            },
+        }
+    }
+
+    /// The number of column tracks a cell spans, including gutter.
+    fn colspan(&self, cell: &Cell) -> usize {
+        self.span(cell.colspan)
+    }
+
+    /// The number of row tracks a cell spans, including gutter.
+    fn rowspan(&self, cell: &Cell) -> usize {
+        self.span(cell.rowspan)
+    }
+
+    /// The number of tracks spanned by a cell spanning `n` content tracks.
+    fn span(&self, n: NonZeroUsize) -> usize {
+        if self.has_gutter {
+            2 * n.get() - 1
+        } else {
+            n.get()
+        }
+    }
+
+    /// The end of the group of rows starting at `y` that are connected by
+    /// cells spanning multiple rows.
+    fn row_group_end(&self, y: usize) -> usize {
+        let mut end = y + 1;
+        let mut i = y;
+        while i < end {
+            for x in 0..self.cols.len() {
+                if let Some(cell) = self.cell(x, i) {
+                    end = end.max(i + self.rowspan(cell));
+                }
+            }
+            i += 1;
+        }
+        end
+    }
+
+    /// The stroke of the grid line between two adjacent slots, where either
+    /// slot may lie outside of the grid.
+    ///
+    /// Explicit lines take precedence over everything else. Otherwise, the
+    /// cells' overrides apply, where the cell after the line takes precedence.
+    /// Without overrides, a line is drawn if either cell requests a stroke for
+    /// it.
+    fn line(
+        &self,
+        before: Option<(usize, usize)>,
+        after: Option<(usize, usize)>,
+        axis: Axis,
+    ) -> Option<Stroke> {
+        let before_cell = before.and_then(|(x, y)| self.parent(x, y));
+        let after_cell = after.and_then(|(x, y)| self.parent(x, y));
+
+        // There are no lines inside of merged cells.
+        if let (Some((a, _)), Some((b, _))) = (before_cell, after_cell) {
+            if a == b {
+                return None;
+            }
+        }
+
+        // A slot's position across and along the line.
+        let split = |(x, y)| match axis {
+            Axis::X => (x, y),
+            Axis::Y => (y, x),
+        };
+
+        // The line is at the start of the slot after it or at the end of the
+        // slot before it.
+        let explicit = self.lines.iter().rev().find(|line| {
+            let hits = |pos: Option<(usize, usize)>, offset: usize| {
+                pos.map(split).map_or(false, |(across, along)| {
+                    across + offset == line.index && line.range.contains(&along)
+                })
+            };
+            line.axis == axis && (hits(after, 0) || hits(before, 1))
+        });
+
+        if let Some(line) = explicit {
+            return line.stroke.clone().map(PartialStroke::unwrap_or_default);
+        }
+
+        let (start, end) = match axis {
+            Axis::X => (Side::Left, Side::Right),
+            Axis::Y => (Side::Top, Side::Bottom),
+        };
+
+        let side = after_cell
+            .and_then(|(_, cell)| cell.stroke.get_ref(start).clone())
+            .or_else(|| {
+                before_cell.and_then(|(_, cell)| cell.stroke.get_ref(end).clone())
+            });
+
+        let base = after_cell
+            .and_then(|(_, cell)| cell.base_stroke.get_ref(start).clone())
+            .or_else(|| {
+                before_cell.and_then(|(_, cell)| cell.base_stroke.get_ref(end).clone())
+            });
+
+        match side {
+            Some(Some(stroke)) => Some(stroke.fold(base.unwrap_or_default())),
+            Some(None) => None,
+            None => base,
+        }
+        .map(PartialStroke::unwrap_or_default)
+    }
+}
+
+/// Performs grid layout.
+pub struct GridLayouter<'a, 'v> {
+    /// The core context.
+    vt: &'a mut Vt<'v>,
+    /// The grid of cells.
+    grid: &'a CellGrid,
+    /// The regions to layout children into.
+    regions: Regions<'a>,
+    /// The inherited styles.
+    styles: StyleChain<'a>,
+    /// The span of the grid, for the fills and lines.
+    span: Span,
+    /// Resolved column sizes.
+    rcols: Vec<Abs>,
+    /// The sum of `rcols`.
+    width: Abs,
+    /// Resolve row sizes, by region.
+    rrows: Vec<Vec<RowPiece>>,
+    /// Rows in the current region.
+    lrows: Vec<Row>,
+    /// The initial size of the current region before we started subtracting.
+    initial: Size,
+    /// Frames for finished regions.
+    finished: Vec<Frame>,
+    /// The number of rows at the start of `lrows` that belong to the header.
+    header_rows: usize,
+    /// Whether the header was already part of a finished region.
+    header_shown: bool,
+    /// The height of the footer, which is reserved in every region.
+    footer_height: Abs,
+    /// The combined height of the header and footer, which is taken away
+    /// from every region.
+    repeated: Abs,
+    /// Whether the cells that are currently laid out are a repeated copy of
+    /// the header or footer.
+    artifact: bool,
+}
+
+/// The resulting sizes of columns and rows in a grid.
+#[derive(Debug)]
+pub struct GridLayout {
+    /// The fragment.
+    pub fragment: Fragment,
+    /// The column widths.
+    pub cols: Vec<Abs>,
+    /// The heights of the resulting rows segments, by region.
+    pub rows: Vec<Vec<RowPiece>>,
+}
 
+/// Details about a resulting row piece.
+#[derive(Debug)]
+pub struct RowPiece {
+    /// The height of the segment.
+    pub height: Abs,
+    /// The index of the row.
+    pub y: usize,
+}
+
+/// Produced by initial row layout, auto and relative rows are already finished,
+/// fractional rows not yet.
+enum Row {
+    /// Finished row frame of auto or relative row with y index.
+    Frame(Frame, usize),
+    /// Fractional row with y index.
+    Fr(Fr, usize),
+}
+
+impl<'a, 'v> GridLayouter<'a, 'v> {
+    /// Create a new grid layouter.
+    pub fn new(
+        vt: &'a mut Vt<'v>,
+        grid: &'a CellGrid,
+        regions: Regions<'a>,
+        styles: StyleChain<'a>,
+        span: Span,
+    ) -> Self {
         // We use these regions for auto row measurement. Since at that moment,
         // columns are already sized, we can enable horizontal expansion.
         let mut regions = regions.clone();
@@ -272,41 +1077,83 @@ impl<'a, 'v> GridLayouter<'a, 'v> {
 
         Self {
             vt,
-            cells,
-            is_rtl,
-            has_gutter,
-            cols,
-            rows,
+            grid,
             regions,
             styles,
-            rcols,
+            span,
+            rcols: vec![Abs::zero(); grid.cols.len()],
             width: Abs::zero(),
             rrows: vec![],
-            lrows,
+            lrows: vec![],
             initial: regions.size,
             finished: vec![],
+            header_rows: 0,
+            header_shown: false,
+            footer_height: Abs::zero(),
+            repeated: Abs::zero(),
+            artifact: false,
         }
     }
 
     /// Determines the columns sizes and then layouts the grid row-by-row.
     pub fn layout(mut self) -> SourceResult<GridLayout> {
+        let grid = self.grid;
         self.measure_columns()?;
 
-        for y in 0..self.rows.len() {
+        // Measure the rows that repeat in every region to reserve space for
+        // them.
+        if let Some(header) = grid.header.as_ref().filter(|header| header.repeat) {
+            let height = self.measure_rows(header.rows.clone())?;
+            self.repeated += height;
+        }
+        if let Some(footer) = grid.footer.as_ref().filter(|footer| footer.repeat) {
+            self.footer_height = self.measure_rows(footer.rows.clone())?;
+            self.repeated += self.footer_height;
+        }
+
+        self.start_region()?;
+
+        // The header is laid out at the start of each region and a repeated
+        // footer at the end of each region.
+        let mut y = grid.header.as_ref().map_or(0, |header| header.rows.end);
+        let end = match &grid.footer {
+            Some(footer) if footer.repeat => footer.rows.start,
+            _ => grid.rows.len(),
+        };
+
+        while y < end {
             // Skip to next region if current one is full, but only for content
             // rows, not for gutter rows.
-            if y % 2 == 0 && self.regions.is_full() {
-                self.finish_region()?;
+            let gutter = grid.has_gutter && y % 2 == 1;
+            if !gutter && self.regions.is_full() {
+                self.finish_region(false)?;
+            }
+
+            // Rows that are connected by cells spanning them are laid out
+            // together.
+            let end = grid.row_group_end(y);
+            if end > y + 1 {
+                self.layout_row_group(y, end)?;
+                y = end;
+                continue;
             }
 
-            match self.rows[y] {
+            match grid.rows[y] {
                 Sizing::Auto => self.layout_auto_row(y)?,
                 Sizing::Rel(v) => self.layout_relative_row(v, y)?,
                 Sizing::Fr(v) => self.lrows.push(Row::Fr(v, y)),
             }
+
+            y += 1;
         }
 
-        self.finish_region()?;
+        self.finish_region(true)?;
+
+        // Add lines and backgrounds.
+        for (frame, rows) in self.finished.iter_mut().zip(&self.rrows) {
+            render_lines(self.grid, frame, &self.rcols, rows, self.span);
+            render_fills(self.grid, frame, &self.rcols, rows, self.span);
+        }
 
         Ok(GridLayout {
             fragment: Fragment::frames(self.finished),
@@ -317,6 +1164,7 @@ impl<'a, 'v> GridLayouter<'a, 'v> {
 
     /// Determine all column sizes.
     fn measure_columns(&mut self) -> SourceResult<()> {
+        let grid = self.grid;
         // Sum of sizes of resolved relative tracks.
         let mut rel = Abs::zero();
 
@@ -325,7 +1173,7 @@ impl<'a, 'v> GridLayouter<'a, 'v> {
 
         // Resolve the size of all relative columns and compute the sum of all
         // fractional tracks.
-        for (&col, rcol) in self.cols.iter().zip(&mut self.rcols) {
+        for (&col, rcol) in grid.cols.iter().zip(&mut self.rcols) {
             match col {
                 Sizing::Auto => {}
                 Sizing::Rel(v) => {
@@ -362,33 +1210,27 @@ impl<'a, 'v> GridLayouter<'a, 'v> {
 
     /// Measure the size that is available to auto columns.
     fn measure_auto_columns(&mut self, available: Abs) -> SourceResult<(Abs, usize)> {
+        let grid = self.grid;
         let mut auto = Abs::zero();
         let mut count = 0;
 
         // Determine size of auto columns by laying out all cells in those
         // columns, measuring them and finding the largest one.
-        for (x, &col) in self.cols.iter().enumerate() {
+        for (x, &col) in grid.cols.iter().enumerate() {
             if col != Sizing::Auto {
                 continue;
             }
 
             let mut resolved = Abs::zero();
-            for y in 0..self.rows.len() {
-                if let Some(cell) = self.cell(x, y) {
-                    // For relative rows, we can already resolve the correct
-                    // base and for auto and fr we could only guess anyway.
-                    let height = match self.rows[y] {
-                        Sizing::Rel(v) => {
-                            v.resolve(self.styles).relative_to(self.regions.base().y)
-                        }
-                        _ => self.regions.base().y,
-                    };
-
-                    let size = Size::new(available, height);
-                    let pod = Regions::one(size, Axes::splat(false));
-                    let frame = cell.measure(self.vt, self.styles, pod)?.into_frame();
-                    resolved.set_max(frame.width());
+            for y in 0..grid.rows.len() {
+                // Cells spanning multiple columns are measured below.
+                let Some(cell) = grid.cell(x, y) else { continue };
+                if grid.colspan(cell) > 1 {
+                    continue;
                 }
+
+                let frame = self.measure_cell(cell, y, available)?;
+                resolved.set_max(frame.width());
             }
 
             self.rcols[x] = resolved;
@@ -396,16 +1238,71 @@ impl<'a, 'v> GridLayouter<'a, 'v> {
             count += 1;
         }
 
+        // Cells spanning multiple columns may need more space than the
+        // columns they span. The last auto column they span grows to make up
+        // for that. If they span a fractional column, that one will grow
+        // instead.
+        for y in 0..grid.rows.len() {
+            for x in 0..grid.cols.len() {
+                let Some(cell) = grid.cell(x, y) else { continue };
+                let span = x..x + grid.colspan(cell);
+                if span.len() == 1
+                    || grid.cols[span.clone()]
+                        .iter()
+                        .any(|col| matches!(col, Sizing::Fr(_)))
+                {
+                    continue;
+                }
+
+                let Some(last) =
+                    span.clone().rev().find(|&i| grid.cols[i] == Sizing::Auto)
+                else {
+                    continue;
+                };
+
+                let frame = self.measure_cell(cell, y, available)?;
+                let current: Abs = self.rcols[span].iter().sum();
+                if frame.width() > current {
+                    let excess = frame.width() - current;
+                    self.rcols[last] += excess;
+                    auto += excess;
+                }
+            }
+        }
+
         Ok((auto, count))
     }
 
+    /// Measure a cell in row `y` for auto column sizing.
+    fn measure_cell(
+        &mut self,
+        cell: &Cell,
+        y: usize,
+        available: Abs,
+    ) -> SourceResult<Frame> {
+        let grid = self.grid;
+        // For relative rows, we can already resolve the correct base and for
+        // auto and fr we could only guess anyway.
+        let height = match grid.rows[y] {
+            Sizing::Rel(v) if grid.rowspan(cell) == 1 => {
+                v.resolve(self.styles).relative_to(self.regions.base().y)
+            }
+            _ => self.regions.base().y,
+        };
+
+        let size = Size::new(available, height);
+        let pod = Regions::one(size, Axes::splat(false));
+        Ok(cell.body.measure(self.vt, self.styles, pod)?.into_frame())
+    }
+
     /// Distribute remaining space to fractional columns.
     fn grow_fractional_columns(&mut self, remaining: Abs, fr: Fr) {
+        let grid = self.grid;
         if fr.is_zero() {
             return;
         }
 
-        for (&col, rcol) in self.cols.iter().zip(&mut self.rcols) {
+        for (&col, rcol) in grid.cols.iter().zip(&mut self.rcols) {
             if let Sizing::Fr(v) = col {
                 *rcol = v.share(fr, remaining);
             }
@@ -414,6 +1311,7 @@ impl<'a, 'v> GridLayouter<'a, 'v> {
 
     /// Redistribute space to auto columns so that each gets a fair share.
     fn shrink_auto_columns(&mut self, available: Abs, count: usize) {
+        let grid = self.grid;
         let mut last;
         let mut fair = -Abs::inf();
         let mut redistribute = available;
@@ -426,7 +1324,7 @@ impl<'a, 'v> GridLayouter<'a, 'v> {
             last = fair;
             fair = redistribute / (overlarge as f64);
 
-            for (&col, &rcol) in self.cols.iter().zip(&self.rcols) {
+            for (&col, &rcol) in grid.cols.iter().zip(&self.rcols) {
                 // Remove an auto column if it is not overlarge (rcol <= fair),
                 // but also hasn't already been removed (rcol > last).
                 if col == Sizing::Auto && rcol <= fair && rcol > last {
@@ -438,7 +1336,7 @@ impl<'a, 'v> GridLayouter<'a, 'v> {
         }
 
         // Redistribute space fairly among overlarge columns.
-        for (&col, rcol) in self.cols.iter().zip(&mut self.rcols) {
+        for (&col, rcol) in grid.cols.iter().zip(&mut self.rcols) {
             if col == Sizing::Auto && *rcol > fair {
                 *rcol = fair;
             }
@@ -448,16 +1346,28 @@ impl<'a, 'v> GridLayouter<'a, 'v> {
     /// Layout a row with automatic height. Such a row may break across multiple
     /// regions.
     fn layout_auto_row(&mut self, y: usize) -> SourceResult<()> {
+        let grid = self.grid;
         let mut resolved: Vec<Abs> = vec![];
         let mut skip = false;
 
+        // The following regions lose the space of the header and footer.
+        let backlog: Vec<Abs> = self
+            .regions
+            .backlog
+            .iter()
+            .map(|&height| height - self.repeated)
+            .collect();
+        let mut regions = self.regions;
+        regions.backlog = &backlog;
+        regions.last = regions.last.map(|height| height - self.repeated);
+
         // Determine the size for each region of the row.
-        for (x, &rcol) in self.rcols.iter().enumerate() {
-            if let Some(cell) = self.cell(x, y) {
-                let mut pod = self.regions;
-                pod.size.x = rcol;
+        for x in 0..grid.cols.len() {
+            if let Some(cell) = grid.cell(x, y) {
+                let mut pod = regions;
+                pod.size.x = self.cell_width(x, cell);
 
-                let frames = cell.measure(self.vt, self.styles, pod)?.into_frames();
+                let frames = cell.body.measure(self.vt, self.styles, pod)?.into_frames();
                 if let [first, rest @ ..] = frames.as_slice() {
                     skip |=
                         first.is_empty() && rest.iter().any(|frame| !frame.is_empty());
@@ -489,16 +1399,16 @@ impl<'a, 'v> GridLayouter<'a, 'v> {
         }
 
         // Skip the first region if it's empty for some cell.
-        if skip && !self.regions.in_last() {
-            self.finish_region()?;
+        if skip && !regions.in_last() {
+            self.finish_region(false)?;
             resolved.remove(0);
+            regions.next();
         }
 
         // Expand all but the last region.
         // Skip the first region if the space is eaten up by an fr row.
         let len = resolved.len();
-        for (region, target) in self
-            .regions
+        for (region, target) in regions
             .iter()
             .zip(&mut resolved[..len - 1])
             .skip(self.lrows.iter().any(|row| matches!(row, Row::Fr(..))) as usize)
@@ -512,7 +1422,7 @@ impl<'a, 'v> GridLayouter<'a, 'v> {
         for (i, frame) in fragment.into_iter().enumerate() {
             self.push_row(frame, y);
             if i + 1 < len {
-                self.finish_region()?;
+                self.finish_region(false)?;
             }
         }
 
@@ -528,10 +1438,10 @@ impl<'a, 'v> GridLayouter<'a, 'v> {
         // Skip to fitting region.
         let height = frame.height();
         while !self.regions.size.y.fits(height) && !self.regions.in_last() {
-            self.finish_region()?;
+            self.finish_region(false)?;
 
             // Don't skip multiple regions for gutter and don't push a row.
-            if y % 2 == 1 {
+            if self.grid.has_gutter && y % 2 == 1 {
                 return Ok(());
             }
         }
@@ -541,20 +1451,159 @@ impl<'a, 'v> GridLayouter<'a, 'v> {
         Ok(())
     }
 
+    /// Layout the rows from `start` to `end`, which are connected by cells
+    /// spanning them. Such rows cannot break across multiple regions and are
+    /// kept together.
+    fn layout_row_group(&mut self, start: usize, end: usize) -> SourceResult<()> {
+        let rows = self.layout_rows(start..end)?;
+
+        // Move the rows to the next region if they don't fit, unless the
+        // region holds nothing but the header.
+        let height: Abs = rows
+            .iter()
+            .map(|row| match row {
+                Row::Frame(frame, _) => frame.height(),
+                Row::Fr(..) => Abs::zero(),
+            })
+            .sum();
+        if !self.regions.size.y.fits(height)
+            && !self.regions.in_last()
+            && self.lrows.len() > self.header_rows
+        {
+            self.finish_region(false)?;
+        }
+
+        for row in rows {
+            match row {
+                Row::Frame(frame, y) => self.push_row(frame, y),
+                row => self.lrows.push(row),
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Measure the combined height of the given rows without breaking them
+    /// across regions.
+    fn measure_rows(&mut self, rows: Range<usize>) -> SourceResult<Abs> {
+        Ok(self
+            .layout_rows(rows)?
+            .iter()
+            .map(|row| match row {
+                Row::Frame(frame, _) => frame.height(),
+                Row::Fr(..) => Abs::zero(),
+            })
+            .sum())
+    }
+
+    /// Layout the given rows without breaking them across regions. Cells
+    /// spanning multiple rows make the rows they span grow if necessary.
+    fn layout_rows(&mut self, range: Range<usize>) -> SourceResult<Vec<Row>> {
+        let grid = self.grid;
+        let (start, end) = (range.start, range.end);
+        let mut rows = vec![];
+        for y in start..end {
+            rows.push(match grid.rows[y] {
+                Sizing::Auto => {
+                    let height = self.measure_unbroken_row(y)?;
+                    Row::Frame(self.layout_single_row(height, y)?, y)
+                }
+                Sizing::Rel(v) => {
+                    let height =
+                        v.resolve(self.styles).relative_to(self.regions.base().y);
+                    Row::Frame(self.layout_single_row(height, y)?, y)
+                }
+                Sizing::Fr(v) => Row::Fr(v, y),
+            });
+        }
+
+        // Cells spanning multiple rows may need more space than the rows they
+        // span. The last auto row they span grows to make up for that. If they
+        // span a fractional row, that one will grow instead.
+        for y in start..end {
+            for x in 0..grid.cols.len() {
+                let Some(cell) = grid.cell(x, y) else { continue };
+                let span = y - start..y - start + grid.rowspan(cell);
+                if span.len() == 1
+                    || rows[span.clone()].iter().any(|row| matches!(row, Row::Fr(..)))
+                {
+                    continue;
+                }
+
+                let Some(last) =
+                    span.clone().rev().find(|&i| grid.rows[start + i] == Sizing::Auto)
+                else {
+                    continue;
+                };
+
+                let size = Size::new(self.cell_width(x, cell), self.regions.base().y);
+                let pod = Regions::one(size, Axes::new(true, false));
+                let needed =
+                    cell.body.measure(self.vt, self.styles, pod)?.into_frame().height();
+
+                let current: Abs = rows[span]
+                    .iter()
+                    .map(|row| match row {
+                        Row::Frame(frame, _) => frame.height(),
+                        Row::Fr(..) => Abs::zero(),
+                    })
+                    .sum();
+
+                if needed > current {
+                    let Row::Frame(frame, _) = &rows[last] else { continue };
+                    let height = frame.height() + needed - current;
+                    rows[last] = Row::Frame(
+                        self.layout_single_row(height, start + last)?,
+                        start + last,
+                    );
+                }
+            }
+        }
+
+        Ok(rows)
+    }
+
+    /// Measure the height of an auto row without breaking it across regions.
+    ///
+    /// Cells spanning multiple rows are not taken into account.
+    fn measure_unbroken_row(&mut self, y: usize) -> SourceResult<Abs> {
+        let grid = self.grid;
+        let mut height = Abs::zero();
+        for x in 0..grid.cols.len() {
+            let Some(cell) = grid.cell(x, y) else { continue };
+            if grid.rowspan(cell) > 1 {
+                continue;
+            }
+
+            let size = Size::new(self.cell_width(x, cell), self.regions.base().y);
+            let pod = Regions::one(size, Axes::new(true, false));
+            let frame = cell.body.measure(self.vt, self.styles, pod)?.into_frame();
+            height.set_max(frame.height());
+        }
+        Ok(height)
+    }
+
     /// Layout a row with fixed height and return its frame.
+    ///
+    /// Cells spanning multiple rows are laid out once their region is
+    /// finished.
     fn layout_single_row(&mut self, height: Abs, y: usize) -> SourceResult<Frame> {
+        let grid = self.grid;
         let mut output = Frame::new(Size::new(self.width, height));
         let mut pos = Point::zero();
 
         for (x, &rcol) in self.rcols.iter().enumerate() {
-            if let Some(cell) = self.cell(x, y) {
-                let size = Size::new(rcol, height);
-                let mut pod = Regions::one(size, Axes::splat(true));
-                if self.rows[y] == Sizing::Auto {
-                    pod.full = self.regions.full;
+            if let Some(cell) = grid.cell(x, y) {
+                if grid.rowspan(cell) == 1 {
+                    let size = Size::new(self.cell_width(x, cell), height);
+                    let mut pod = Regions::one(size, Axes::splat(true));
+                    if grid.rows[y] == Sizing::Auto {
+                        pod.full = self.regions.full;
+                    }
+                    let body = cell.content(self.vt, self.artifact);
+                    let frame = body.layout(self.vt, self.styles, pod)?.into_frame();
+                    output.push_frame(pos, frame);
                 }
-                let frame = cell.layout(self.vt, self.styles, pod)?.into_frame();
-                output.push_frame(pos, frame);
             }
 
             pos.x += rcol;
@@ -565,6 +1614,7 @@ impl<'a, 'v> GridLayouter<'a, 'v> {
 
     /// Layout a row spanning multiple regions.
     fn layout_multi_row(&mut self, heights: &[Abs], y: usize) -> SourceResult<Fragment> {
+        let grid = self.grid;
         // Prepare frames.
         let mut outputs: Vec<_> = heights
             .iter()
@@ -580,11 +1630,11 @@ impl<'a, 'v> GridLayouter<'a, 'v> {
         // Layout the row.
         let mut pos = Point::zero();
         for (x, &rcol) in self.rcols.iter().enumerate() {
-            if let Some(cell) = self.cell(x, y) {
-                pod.size.x = rcol;
+            if let Some(cell) = grid.cell(x, y) {
+                pod.size.x = self.cell_width(x, cell);
 
                 // Push the layouted frames into the individual output frames.
-                let fragment = cell.layout(self.vt, self.styles, pod)?;
+                let fragment = cell.body.layout(self.vt, self.styles, pod)?;
                 for (output, frame) in outputs.iter_mut().zip(fragment) {
                     output.push_frame(pos, frame);
                 }
@@ -596,14 +1646,77 @@ impl<'a, 'v> GridLayouter<'a, 'v> {
         Ok(Fragment::frames(outputs))
     }
 
+    /// The width of a cell starting in column `x`.
+    fn cell_width(&self, x: usize, cell: &Cell) -> Abs {
+        let grid = self.grid;
+        self.rcols[x..x + grid.colspan(cell)].iter().sum()
+    }
+
     /// Push a row frame into the current region.
     fn push_row(&mut self, frame: Frame, y: usize) {
         self.regions.size.y -= frame.height();
         self.lrows.push(Row::Frame(frame, y));
     }
 
-    /// Finish rows for one region.
-    fn finish_region(&mut self) -> SourceResult<()> {
+    /// Prepare a new region by laying out the header and reserving space for
+    /// the footer.
+    fn start_region(&mut self) -> SourceResult<()> {
+        let grid = self.grid;
+        self.regions.size.y -= self.footer_height;
+        self.header_rows = 0;
+
+        let Some(header) = &grid.header else { return Ok(()) };
+        if !header.repeat && self.header_shown {
+            return Ok(());
+        }
+
+        self.artifact = self.header_shown;
+        let rows = self.layout_rows(header.rows.clone())?;
+        self.artifact = false;
+
+        self.header_rows = rows.len();
+        for row in rows {
+            match row {
+                Row::Frame(frame, y) => self.push_row(frame, y),
+                row => self.lrows.push(row),
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Finish rows for one region. If this is the `last` region, the footer
+    /// is the original instead of a repeated copy.
+    fn finish_region(&mut self, last: bool) -> SourceResult<()> {
+        let grid = self.grid;
+
+        // Don't leave the header on its own at the end of a region. Instead,
+        // it moves to the next region along with the rows that follow it.
+        if !last
+            && self.header_rows > 0
+            && self.lrows.len() == self.header_rows
+            && !self.regions.in_last()
+        {
+            self.lrows.clear();
+            self.header_rows = 0;
+        }
+
+        // Add the footer below the other rows.
+        let footer = self.lrows.len();
+        if let Some(group) = grid.footer.as_ref().filter(|footer| footer.repeat) {
+            if last || !self.lrows.is_empty() {
+                self.artifact = !last;
+                let rows = self.layout_rows(group.rows.clone())?;
+                self.artifact = false;
+                self.lrows.extend(rows);
+            }
+        }
+
+        // Which rows belong to a repeated copy of the header or footer.
+        let header = if self.header_shown { self.header_rows } else { 0 };
+        let footer = if last { usize::MAX } else { footer };
+        let repeated = |i: usize| i < header || i >= footer;
+
         // Determine the height of existing rows in the region.
         let mut used = Abs::zero();
         let mut fr = Fr::zero();
@@ -627,13 +1740,16 @@ impl<'a, 'v> GridLayouter<'a, 'v> {
         let mut rrows = vec![];
 
         // Place finished rows and layout fractional rows.
-        for row in std::mem::take(&mut self.lrows) {
+        for (i, row) in std::mem::take(&mut self.lrows).into_iter().enumerate() {
             let (frame, y) = match row {
                 Row::Frame(frame, y) => (frame, y),
                 Row::Fr(v, y) => {
                     let remaining = self.regions.full - used;
                     let height = v.share(fr, remaining);
-                    (self.layout_single_row(height, y)?, y)
+                    self.artifact = repeated(i);
+                    let frame = self.layout_single_row(height, y)?;
+                    self.artifact = false;
+                    (frame, y)
                 }
             };
 
@@ -643,38 +1759,160 @@ impl<'a, 'v> GridLayouter<'a, 'v> {
             pos.y += height;
         }
 
+        // Layout the cells spanning multiple rows now that the heights of
+        // their rows are known.
+        let mut dy = Abs::zero();
+        for (i, row) in rrows.iter().enumerate() {
+            let mut dx = Abs::zero();
+            for (x, &rcol) in self.rcols.iter().enumerate() {
+                if let Some(cell) = grid.cell(x, row.y) {
+                    let span = grid.rowspan(cell);
+                    if span > 1 {
+                        let height = rrows[i..]
+                            .iter()
+                            .take_while(|piece| piece.y < row.y + span)
+                            .map(|piece| piece.height)
+                            .sum();
+                        let size = Size::new(self.cell_width(x, cell), height);
+                        let pod = Regions::one(size, Axes::splat(true));
+                        let body = cell.content(self.vt, repeated(i));
+                        let frame = body.layout(self.vt, self.styles, pod)?.into_frame();
+                        output.push_frame(Point::new(dx, dy), frame);
+                    }
+                }
+                dx += rcol;
+            }
+            dy += row.height;
+        }
+
+        if self.header_rows > 0 {
+            self.header_shown = true;
+        }
+
         self.finished.push(output);
         self.rrows.push(rrows);
         self.regions.next();
         self.initial = self.regions.size;
 
+        if !last {
+            self.start_region()?;
+        }
+
         Ok(())
     }
+}
 
-    /// Get the content of the cell in column `x` and row `y`.
-    ///
-    /// Returns `None` if it's a gutter cell.
-    #[track_caller]
-    fn cell(&self, mut x: usize, y: usize) -> Option<&'a Content> {
-        assert!(x < self.cols.len());
-        assert!(y < self.rows.len());
+/// Render the grid lines of one region of a grid.
+fn render_lines(
+    grid: &CellGrid,
+    frame: &mut Frame,
+    cols: &[Abs],
+    rows: &[RowPiece],
+    span: Span,
+) {
+    // A region can end up empty when its header moves on to the next one.
+    if rows.is_empty() {
+        return;
+    }
 
-        // Columns are reorder, but the cell slice is not.
-        if self.is_rtl {
-            x = self.cols.len() - 1 - x;
+    // Render horizontal lines.
+    let mut dy = Abs::zero();
+    for k in 0..=rows.len() {
+        let before = k.checked_sub(1).map(|i| rows[i].y);
+        let after = rows.get(k).map(|row| row.y);
+        let strokes = cols.iter().enumerate().map(|(x, &col)| {
+            let stroke =
+                grid.line(before.map(|y| (x, y)), after.map(|y| (x, y)), Axis::Y);
+            (col, stroke)
+        });
+
+        for (offset, length, stroke) in segments(strokes) {
+            let half = stroke.thickness / 2.0;
+            let target = Point::with_x(length + stroke.thickness);
+            let hline = Geometry::Line(target).stroked(stroke);
+            frame.prepend(Point::new(offset - half, dy), FrameItem::Shape(hline, span));
         }
 
-        if self.has_gutter {
-            // Even columns and rows are children, odd ones are gutter.
-            if x % 2 == 0 && y % 2 == 0 {
-                let c = 1 + self.cols.len() / 2;
-                self.cells.get((y / 2) * c + x / 2)
-            } else {
-                None
+        if let Some(row) = rows.get(k) {
+            dy += row.height;
+        }
+    }
+
+    // Render vertical lines.
+    let mut dx = Abs::zero();
+    for k in 0..=cols.len() {
+        let before = k.checked_sub(1);
+        let after = (k < cols.len()).then_some(k);
+        let strokes = rows.iter().map(|row| {
+            let stroke =
+                grid.line(before.map(|x| (x, row.y)), after.map(|x| (x, row.y)), Axis::X);
+            (row.height, stroke)
+        });
+
+        for (offset, length, stroke) in segments(strokes) {
+            let half = stroke.thickness / 2.0;
+            let target = Point::with_y(length + stroke.thickness);
+            let vline = Geometry::Line(target).stroked(stroke);
+            frame.prepend(Point::new(dx, offset - half), FrameItem::Shape(vline, span));
+        }
+
+        if let Some(&col) = cols.get(k) {
+            dx += col;
+        }
+    }
+}
+
+/// Merge consecutive pieces of a grid line with the same stroke into
+/// segments, returned as offset, length and stroke.
+fn segments(
+    pieces: impl Iterator<Item = (Abs, Option<Stroke>)>,
+) -> Vec<(Abs, Abs, Stroke)> {
+    let mut segments: Vec<(Abs, Abs, Stroke)> = vec![];
+    let mut offset = Abs::zero();
+    for (length, stroke) in pieces {
+        if let Some(stroke) = stroke {
+            match segments.last_mut() {
+                Some((start, len, prev))
+                    if *start + *len == offset && *prev == stroke =>
+                {
+                    *len += length;
+                }
+                _ => segments.push((offset, length, stroke)),
             }
-        } else {
-            let c = self.cols.len();
-            self.cells.get(y * c + x)
         }
+        offset += length;
+    }
+    segments
+}
+
+/// Render the cell backgrounds of one region of a grid.
+fn render_fills(
+    grid: &CellGrid,
+    frame: &mut Frame,
+    cols: &[Abs],
+    rows: &[RowPiece],
+    span: Span,
+) {
+    let mut dy = Abs::zero();
+    for (i, row) in rows.iter().enumerate() {
+        let mut dx = Abs::zero();
+        for (x, &col) in cols.iter().enumerate() {
+            if let Some(cell) = grid.cell(x, row.y) {
+                if let Some(fill) = &cell.fill {
+                    let width = cols[x..x + grid.colspan(cell)].iter().sum();
+                    let rowspan = grid.rowspan(cell);
+                    let height = rows[i..]
+                        .iter()
+                        .take_while(|piece| piece.y < row.y + rowspan)
+                        .map(|piece| piece.height)
+                        .sum();
+                    let size = Size::new(width, height);
+                    let rect = Geometry::Rect(size).filled(fill.clone());
+                    frame.prepend(Point::new(dx, dy), FrameItem::Shape(rect, span));
+                }
+            }
+            dx += col;
+        }
+        dy += row.height;
     }
 }
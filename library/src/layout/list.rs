@@ -2,7 +2,7 @@ use crate::layout::{BlockElem, ParElem, Sizing, Spacing};
 use crate::prelude::*;
 use crate::text::TextElem;
 
-use super::GridLayouter;
+use super::{Cell, CellGrid, GridLayouter};
 
 /// A bullet list.
 ///
@@ -132,14 +132,27 @@ impl Layout for ListElem {
 
         let mut cells = vec![];
         for item in self.children() {
+            let tag = Tag::new(vt, Role::ListItem, &item);
             cells.push(Content::empty());
-            cells.push(marker.clone());
+            cells.push(
+                marker
+                    .clone()
+                    .tagged(Tag::new(vt, Role::ListLabel, &item))
+                    .tagged(tag.clone()),
+            );
             cells.push(Content::empty());
-            cells.push(item.body().styled(Self::set_depth(Depth)));
+            cells.push(
+                item.body()
+                    .styled(Self::set_depth(Depth))
+                    .tagged(Tag::new(vt, Role::ListBody, &item))
+                    .tagged(tag),
+            );
         }
 
-        let layouter = GridLayouter::new(
-            vt,
+        let tag = MetaElem::set_data(vec![Meta::Tag(Tag::new(vt, Role::List, self))]);
+        let styles = styles.chain_one(&tag);
+
+        let grid = CellGrid::new(
             Axes::with_x(&[
                 Sizing::Rel(indent.into()),
                 Sizing::Auto,
@@ -147,10 +160,10 @@ impl Layout for ListElem {
                 Sizing::Auto,
             ]),
             Axes::with_y(&[gutter.into()]),
-            &cells,
-            regions,
+            cells.into_iter().map(Cell::from).collect(),
             styles,
         );
+        let layouter = GridLayouter::new(vt, &grid, regions, styles, self.span());
 
         Ok(layouter.layout()?.fragment)
     }
@@ -303,7 +303,8 @@ impl PageElem {
         child = child.padded(padding);
 
         // Layout the child.
-        let regions = Regions::repeat(size, size.map(Abs::is_finite));
+        let mut regions = Regions::repeat(size, size.map(Abs::is_finite));
+        regions.root = true;
         let mut fragment = child.layout(vt, styles, regions)?;
 
         let fill = self.fill(styles);
@@ -349,10 +350,13 @@ impl PageElem {
                     align = Align::CENTER_HORIZON.into();
                 };
 
+                // Marginals repeat on every page, so they aren't part of the
+                // document's logical structure.
                 let pod = Regions::one(area, Axes::splat(true));
                 let sub = content
                     .clone()
                     .styled(AlignElem::set_alignment(align))
+                    .tagged(Tag::new(vt, Role::Artifact, content))
                     .layout(vt, styles, pod)?
                     .into_frame();
                 if ptr::eq(marginal, &header) || ptr::eq(marginal, &background) {
@@ -362,8 +366,8 @@ impl PageElem {
                 }
             }
 
-            if let Some(fill) = fill {
-                frame.fill(fill);
+            if let Some(fill) = &fill {
+                frame.fill(fill.clone());
             }
         }
 
@@ -98,6 +98,31 @@ pub struct ParElem {
     #[resolve]
     pub hanging_indent: Length,
 
+    /// The minimum number of lines at the start of a paragraph that are kept
+    /// together at the bottom of a region before the paragraph breaks.
+    ///
+    /// If fewer lines fit into the region, they move to the next region along
+    /// with the rest of the paragraph. Setting this to `{1}` allows single
+    /// orphaned lines.
+    ///
+    /// ```example
+    /// #set page(height: 60pt)
+    /// #set par(orphans: 3)
+    /// #v(20pt)
+    /// #lorem(16)
+    /// ```
+    #[default(NonZeroUsize::new(2).unwrap())]
+    pub orphans: NonZeroUsize,
+
+    /// The minimum number of lines at the end of a paragraph that are kept
+    /// together at the top of a region after the paragraph breaks.
+    ///
+    /// If the paragraph would otherwise end with fewer lines in a new region,
+    /// lines from the previous region move along. Setting this to `{1}` allows
+    /// single widowed lines.
+    #[default(NonZeroUsize::new(2).unwrap())]
+    pub widows: NonZeroUsize,
+
     /// The contents of the paragraph.
     #[external]
     pub body: Content,
@@ -109,11 +134,11 @@ pub struct ParElem {
 }
 
 impl Construct for ParElem {
-    fn construct(_: &mut Vm, args: &mut Args) -> SourceResult<Content> {
+    fn construct(vm: &mut Vm, args: &mut Args) -> SourceResult<Content> {
         // The paragraph constructor is special: It doesn't create a paragraph
         // element. Instead, it just ensures that the passed content lives in a
         // separate paragraph and styles it.
-        let styles = Self::set(args)?;
+        let styles = Self::set(vm, args)?;
         let body = args.expect::<Content>("body")?;
         Ok(Content::sequence([
             ParbreakElem::new().pack(),
@@ -148,6 +173,16 @@ impl ParElem {
             let mut vt = Vt { world, tracer, provider, introspector };
             let children = par.children();
 
+            // Mark the paragraph as part of the document's structure, unless
+            // it just holds the text of an element with a role of its own.
+            let tag = Tag::new(&mut vt, Role::Paragraph, par);
+            let tag = MetaElem::set_data(vec![Meta::Tag(tag)]);
+            let outer = styles;
+            let styles = match MetaElem::role_in(outer) {
+                Some(Role::Heading(_) | Role::Caption | Role::ListLabel) => outer,
+                _ => outer.chain_one(&tag),
+            };
+
             // Collect all text into one string for BiDi analysis.
             let (text, segments, spans) = collect(&children, &styles, consecutive)?;
 
@@ -1173,17 +1208,28 @@ fn finalize(
         .map(|line| commit(vt, p, line, width, region.y))
         .collect::<SourceResult<_>>()?;
 
-    // Prevent orphans.
+    // Mark the lines as part of the paragraph.
+    for frame in &mut frames {
+        frame.tag(p.styles);
+    }
+
+    // Prevent orphans by keeping the first lines together.
     let leading = ParElem::leading_in(p.styles);
-    if frames.len() >= 2 && !frames[1].is_empty() {
+    for _ in 1..ParElem::orphans_in(p.styles).get() {
+        if frames.len() < 2 || frames[1].is_empty() {
+            break;
+        }
         let second = frames.remove(1);
         let first = &mut frames[0];
         merge(first, second, leading);
     }
 
-    // Prevent widows.
-    let len = frames.len();
-    if len >= 2 && !frames[len - 2].is_empty() {
+    // Prevent widows by keeping the last lines together.
+    for _ in 1..ParElem::widows_in(p.styles).get() {
+        let len = frames.len();
+        if len < 2 || frames[len - 2].is_empty() {
+            break;
+        }
         let second = frames.pop().unwrap();
         let first = frames.last_mut().unwrap();
         merge(first, second, leading);
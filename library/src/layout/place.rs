@@ -6,6 +6,8 @@ use crate::prelude::*;
 /// always relative to its parent container and will be in the foreground of all
 /// other content in the container. Page margins will be respected.
 ///
+/// Floating placed content is different: It is moved to the top or bottom of
+/// the current region or column and the remaining content flows around it.
 ///
 /// ## Example
 /// ```example
@@ -30,6 +32,10 @@ pub struct PlaceElem {
     /// When an axis of the page is `{auto}` sized, all alignments relative to that
     /// axis will be ignored, instead, the item will be placed in the origin of the
     /// axis.
+    ///
+    /// For floating content, the vertical alignment must be `{top}` or
+    /// `{bottom}`. If it is omitted, the content floats to whichever of the two
+    /// is closer to its position in the flow.
     #[positional]
     #[default(Axes::with_x(Some(GenAlign::Start)))]
     pub alignment: Axes<Option<GenAlign>>,
@@ -48,6 +54,45 @@ pub struct PlaceElem {
     /// The vertical displacement of the placed content.
     pub dy: Rel<Length>,
 
+    /// Whether the placed content floats.
+    ///
+    /// Floating content is placed at the top or bottom of the current region
+    /// or column and pushes the surrounding content aside. If it doesn't fit
+    /// into the remaining space, it is deferred to the next region.
+    ///
+    /// ```example
+    /// #set page(height: 150pt)
+    /// #let note(where, body) = place(
+    ///   center + where,
+    ///   float: true,
+    ///   clearance: 6pt,
+    ///   rect(body),
+    /// )
+    ///
+    /// #lorem(10)
+    /// #note(bottom)[Bottom 1]
+    /// #note(bottom)[Bottom 2]
+    /// #lorem(40)
+    /// #note(top)[Top]
+    /// #lorem(10)
+    /// ```
+    #[default(false)]
+    pub float: bool,
+
+    /// The amount of space between floating content and the rest of the flow.
+    #[resolve]
+    #[default(Em::new(1.5).into())]
+    pub clearance: Length,
+
+    /// Relative to which container floating content is placed.
+    ///
+    /// With `{"column"}`, the content floats in the column it appears in. With
+    /// `{"parent"}`, it spans all columns of the surrounding
+    /// [columns]($func/columns) and is moved to the next row of columns if
+    /// some of the current row's columns were already filled.
+    #[default(PlacementScope::Column)]
+    pub scope: PlacementScope,
+
     /// The content to place.
     #[required]
     pub body: Content,
@@ -60,6 +105,30 @@ impl Layout for PlaceElem {
         styles: StyleChain,
         regions: Regions,
     ) -> SourceResult<Fragment> {
+        // Floating content keeps its natural height so that the flow can
+        // reserve space for it.
+        if self.float(styles) {
+            let alignment = self.alignment(styles);
+            if !matches!(
+                alignment.y,
+                None | Some(GenAlign::Specific(Align::Top | Align::Bottom))
+            ) {
+                bail!(
+                    self.span(),
+                    "floating placement must be `auto`, `top`, or `bottom`"
+                );
+            }
+
+            let base = regions.base();
+            let expand = Axes::new(base.x.is_finite(), false);
+            let pod = Regions::one(base, expand);
+            let child = self
+                .body()
+                .moved(Axes::new(self.dx(styles), self.dy(styles)))
+                .aligned(Axes::with_x(alignment.x));
+            return child.layout(vt, styles, pod);
+        }
+
         let out_of_flow = self.out_of_flow(styles);
 
         // The pod is the base area of the region because for absolute
@@ -91,10 +160,19 @@ impl PlaceElem {
     /// base origin. Instead of relative to the parent's current flow/cursor
     /// position.
     pub fn out_of_flow(&self, styles: StyleChain) -> bool {
-        self.alignment(styles).y.is_some()
+        self.alignment(styles).y.is_some() && !self.float(styles)
     }
 }
 
+/// Relative to which container floating content is placed.
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash, Cast)]
+pub enum PlacementScope {
+    /// Place into the current column.
+    Column,
+    /// Place into the parent container, spanning all of its columns.
+    Parent,
+}
+
 impl Behave for PlaceElem {
     fn behaviour(&self) -> Behaviour {
         Behaviour::Ignorant
@@ -17,6 +17,15 @@ pub struct Regions<'a> {
     /// Whether elements should expand to fill the regions instead of shrinking
     /// to fit the content.
     pub expand: Axes<bool>,
+    /// Whether these are the root regions or direct descendants.
+    ///
+    /// True for the padded page regions and columns directly placed in them.
+    pub root: bool,
+    /// The number of columns and the gutter between them if these regions are
+    /// the columns of a [`ColumnsElem`](super::ColumnsElem).
+    ///
+    /// The first region is always the first column of a row.
+    pub columns: Option<(usize, Abs)>,
 }
 
 impl Regions<'_> {
@@ -28,6 +37,8 @@ impl Regions<'_> {
             backlog: &[],
             last: None,
             expand,
+            root: false,
+            columns: None,
         }
     }
 
@@ -39,6 +50,8 @@ impl Regions<'_> {
             backlog: &[],
             last: Some(size.y),
             expand,
+            root: false,
+            columns: None,
         }
     }
 
@@ -67,6 +80,8 @@ impl Regions<'_> {
             backlog,
             last: self.last.map(|y| f(Size::new(x, y)).y),
             expand: self.expand,
+            root: self.root,
+            columns: self.columns,
         }
     }
 
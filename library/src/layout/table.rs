@@ -1,4 +1,8 @@
-use crate::layout::{AlignElem, GridLayouter, TrackSizings};
+use typst::util::hash128;
+
+use crate::layout::{
+    AlignElem, Cell, CellGrid, GridLayouter, Line, Occupancy, RowGroup, TrackSizings,
+};
 use crate::meta::LocalName;
 use crate::prelude::*;
 
@@ -10,6 +14,13 @@ use crate::prelude::*;
 /// the [grid documentation]($func/grid) for more information on how to size the
 /// table tracks.
 ///
+/// To merge cells, place a cell at a specific position or configure a single
+/// cell, use the [`table.cell`]($func/table.cell) element. To repeat rows at
+/// the top or bottom of every page the table spans, group them with
+/// [`table.header`]($func/table.header) or [`table.footer`]($func/table.footer).
+/// To draw individual rules, use [`table.hline`]($func/table.hline) and
+/// [`table.vline`]($func/table.vline).
+///
 /// ## Example
 /// ```example
 /// #table(
@@ -33,6 +44,14 @@ use crate::prelude::*;
 /// Display: Table
 /// Category: layout
 #[element(Layout, LocalName)]
+#[scope(
+    scope.define("cell", TableCell::func());
+    scope.define("header", TableHeader::func());
+    scope.define("footer", TableFooter::func());
+    scope.define("hline", TableHLine::func());
+    scope.define("vline", TableVLine::func());
+    scope
+)]
 pub struct TableElem {
     /// Defines the column sizes. See the [grid documentation]($func/grid) for
     /// more information on track sizing.
@@ -101,11 +120,31 @@ pub struct TableElem {
     /// How to stroke the cells.
     ///
     /// This can be a color, a stroke width, both, or `{none}` to disable
-    /// the stroke.
-    #[resolve]
-    #[fold]
-    #[default(Some(PartialStroke::default()))]
-    pub stroke: Option<PartialStroke>,
+    /// the stroke. Dashed or dotted strokes can be configured with a stroke
+    /// dictionary as described in the [line's
+    /// documentation]($func/line.stroke). To stroke the sides of the cells
+    /// differently, pass a dictionary with strokes for individual sides as
+    /// for the [rectangle's stroke]($func/rect.stroke).
+    ///
+    /// This can also be a function that returns any of these. The function is
+    /// passed the cell's column and row index, starting at zero. A line
+    /// between two cells is drawn if either of them has a stroke for the side
+    /// they share, where the stroke of the cell to the right or below takes
+    /// precedence.
+    ///
+    /// ```example
+    /// #table(
+    ///   columns: 3,
+    ///   stroke: (x, y) => if y == 0 { (bottom: 0.7pt) },
+    ///   table.hline(),
+    ///   [*Name*], [*Age*], [*City*],
+    ///   [Anna], [29], [Oslo],
+    ///   [Ben], [41], [Rome],
+    ///   table.hline(),
+    /// )
+    /// ```
+    #[default(Celled::Value(Sides::splat(Some(Some(PartialStroke::default())))))]
+    pub stroke: Celled<Sides<Option<Option<PartialStroke>>>>,
 
     /// How much to pad the cells's content.
     ///
@@ -125,104 +164,463 @@ impl Layout for TableElem {
         styles: StyleChain,
         regions: Regions,
     ) -> SourceResult<Fragment> {
-        let inset = self.inset(styles);
-        let align = self.align(styles);
-
         let tracks = Axes::new(self.columns(styles).0, self.rows(styles).0);
         let gutter = Axes::new(self.column_gutter(styles).0, self.row_gutter(styles).0);
-        let cols = tracks.x.len().max(1);
-        let cells: Vec<_> = self
-            .children()
-            .into_iter()
-            .enumerate()
-            .map(|(i, child)| {
-                let mut child = child.padded(Sides::splat(inset));
-
-                let x = i % cols;
-                let y = i / cols;
-                if let Smart::Custom(alignment) = align.resolve(vt, x, y)? {
-                    child = child.styled(AlignElem::set_alignment(alignment));
+
+        // Determine the position of each cell and line.
+        let mut occupancy = Occupancy::new(tracks.x.len().max(1));
+        let mut children = vec![];
+        let mut lines = vec![];
+        let mut header = None;
+        let mut footer = None;
+        let mut place = |occupancy: &mut Occupancy, child| {
+            Self::place(occupancy, child, styles, &mut children, &mut lines)
+        };
+
+        for child in self.children() {
+            let span = child.span();
+            if let Some(group) = child.to::<TableHeader>() {
+                if header.is_some() {
+                    bail!(span, "cannot have more than one header");
+                }
+                let start = occupancy.next_row();
+                if start > 0 {
+                    bail!(span, "header must start at the first row");
+                }
+                for child in group.children() {
+                    place(&mut occupancy, child)?;
+                }
+                let rows = start..occupancy.next_row();
+                header = Some(RowGroup { rows, repeat: group.repeat(styles) });
+            } else if let Some(group) = child.to::<TableFooter>() {
+                if footer.is_some() {
+                    bail!(span, "cannot have more than one footer");
                 }
+                let start = occupancy.next_row();
+                for child in group.children() {
+                    place(&mut occupancy, child)?;
+                }
+                let rows = start..occupancy.next_row();
+                footer = Some((RowGroup { rows, repeat: group.repeat(styles) }, span));
+            } else {
+                place(&mut occupancy, child)?;
+            }
+        }
+
+        if let Some((footer, span)) = &footer {
+            occupancy.check_footer(footer, tracks.y.len()).at(*span)?;
+        }
+        let footer = footer.map(|(footer, _)| footer);
+
+        // Ensure that the lines lie within the table.
+        let size = Axes::new(tracks.x.len().max(1), occupancy.rows().max(tracks.y.len()));
+        for (line, span) in &lines {
+            let (kind, max) = match line.axis {
+                Axis::X => ("column", size.x),
+                Axis::Y => ("row", size.y),
+            };
+            if line.index > max {
+                bail!(*span, "cannot draw a line at {kind} {}", line.index);
+            }
+        }
+        let lines = lines.into_iter().map(|(line, _)| line).collect();
+
+        // Fill the free slots with empty cells so that they are filled and
+        // stroked like all others.
+        for pos in occupancy.free(tracks.y.len()) {
+            children.push((pos, TableCell::new(Content::empty())));
+        }
 
-                Ok(child)
+        // Identifies the table and its rows and cells in the document's
+        // structure. All cells of a row belong to the same row element and
+        // all rows of the header or footer to the same header or footer.
+        let id = hash128(self);
+        let header_tag = header.as_ref().map(|_| Tag::new(vt, Role::TableHeader, &id));
+        let footer_tag = footer.as_ref().map(|_| Tag::new(vt, Role::TableFooter, &id));
+        let count = children.iter().map(|(pos, _)| pos.y + 1).max().unwrap_or(0);
+        let rows: Vec<Vec<Tag>> = (0..count)
+            .map(|y| {
+                let mut tags = vec![Tag::new(vt, Role::TableRow, &(id, y))];
+                if header.as_ref().map_or(false, |h| h.rows.contains(&y)) {
+                    tags.extend(header_tag.clone());
+                } else if footer.as_ref().map_or(false, |f| f.rows.contains(&y)) {
+                    tags.extend(footer_tag.clone());
+                }
+                tags
+            })
+            .collect();
+        let cells = children
+            .into_iter()
+            .map(|(pos, cell)| {
+                let tags = &rows[pos.y];
+                Ok((pos, self.prepare_cell(vt, cell, pos, id, tags, styles)?))
             })
             .collect::<SourceResult<_>>()?;
 
-        let fill = self.fill(styles);
-        let stroke = self.stroke(styles).map(PartialStroke::unwrap_or_default);
+        let tag = MetaElem::set_data(vec![Meta::Tag(Tag::new(vt, Role::Table, &id))]);
+        let styles = styles.chain_one(&tag);
 
         // Prepare grid layout by unifying content and gutter tracks.
-        let layouter = GridLayouter::new(
-            vt,
-            tracks.as_deref(),
-            gutter.as_deref(),
-            &cells,
-            regions,
-            styles,
-        );
+        let grid =
+            CellGrid::with_positions(tracks.as_deref(), gutter.as_deref(), cells, styles)
+                .with_header(header)
+                .with_footer(footer)
+                .with_lines(lines, styles);
 
         // Measure the columns and layout the grid row-by-row.
-        let mut layout = layouter.layout()?;
-
-        // Add lines and backgrounds.
-        for (frame, rows) in layout.fragment.iter_mut().zip(&layout.rows) {
-            // Render table lines.
-            if let Some(stroke) = stroke {
-                let thickness = stroke.thickness;
-                let half = thickness / 2.0;
-
-                // Render horizontal lines.
-                for offset in points(rows.iter().map(|piece| piece.height)) {
-                    let target = Point::with_x(frame.width() + thickness);
-                    let hline = Geometry::Line(target).stroked(stroke);
-                    frame.prepend(
-                        Point::new(-half, offset),
-                        FrameItem::Shape(hline, self.span()),
-                    );
-                }
+        let layouter = GridLayouter::new(vt, &grid, regions, styles, self.span());
+        Ok(layouter.layout()?.fragment)
+    }
+}
 
-                // Render vertical lines.
-                for offset in points(layout.cols.iter().copied()) {
-                    let target = Point::with_y(frame.height() + thickness);
-                    let vline = Geometry::Line(target).stroked(stroke);
-                    frame.prepend(
-                        Point::new(offset, -half),
-                        FrameItem::Shape(vline, self.span()),
-                    );
-                }
-            }
+impl TableElem {
+    /// Determine the position of a cell or line.
+    fn place(
+        occupancy: &mut Occupancy,
+        child: Content,
+        styles: StyleChain,
+        cells: &mut Vec<(Axes<usize>, TableCell)>,
+        lines: &mut Vec<(Line, Span)>,
+    ) -> SourceResult<()> {
+        let span = child.span();
+        if let Some(hline) = child.to::<TableHLine>() {
+            let line = Line {
+                axis: Axis::Y,
+                index: hline.y(styles).unwrap_or(occupancy.after().y),
+                range: hline.start(styles)..hline.end(styles).unwrap_or(usize::MAX),
+                stroke: hline.stroke(styles),
+            };
+            lines.push((line, span));
+            return Ok(());
+        }
 
-            // Render cell backgrounds.
-            let mut dx = Abs::zero();
-            for (x, &col) in layout.cols.iter().enumerate() {
-                let mut dy = Abs::zero();
-                for row in rows {
-                    if let Some(fill) = fill.resolve(vt, x, row.y)? {
-                        let pos = Point::new(dx, dy);
-                        let size = Size::new(col, row.height);
-                        let rect = Geometry::Rect(size).filled(fill);
-                        frame.prepend(pos, FrameItem::Shape(rect, self.span()));
-                    }
-                    dy += row.height;
-                }
-                dx += col;
-            }
+        if let Some(vline) = child.to::<TableVLine>() {
+            let line = Line {
+                axis: Axis::X,
+                index: vline.x(styles).unwrap_or(occupancy.after().x),
+                range: vline.start(styles)..vline.end(styles).unwrap_or(usize::MAX),
+                stroke: vline.stroke(styles),
+            };
+            lines.push((line, span));
+            return Ok(());
         }
 
-        Ok(layout.fragment)
+        let cell = child
+            .to::<TableCell>()
+            .cloned()
+            .unwrap_or_else(|| TableCell::new(child));
+        let pos = occupancy
+            .place(
+                cell.x(styles),
+                cell.y(styles),
+                cell.colspan(styles),
+                cell.rowspan(styles),
+            )
+            .at(span)?;
+        cells.push((pos, cell));
+        Ok(())
     }
-}
 
-/// Turn an iterator extents into an iterator of offsets before, in between, and
-/// after the extents, e.g. [10mm, 5mm] -> [0mm, 10mm, 15mm].
-fn points(extents: impl IntoIterator<Item = Abs>) -> impl Iterator<Item = Abs> {
-    let mut offset = Abs::zero();
-    std::iter::once(Abs::zero())
-        .chain(extents.into_iter())
-        .map(move |extent| {
-            offset += extent;
-            offset
+    /// Prepare a cell at its final position for layout, filling in the
+    /// table's properties where the cell doesn't override them. The `part`
+    /// is the role of the header or footer that the cell belongs to, if any.
+    fn prepare_cell(
+        &self,
+        vt: &mut Vt,
+        cell: TableCell,
+        pos: Axes<usize>,
+        id: u128,
+        tags: &[Tag],
+        styles: StyleChain,
+    ) -> SourceResult<Cell> {
+        let Axes { x, y } = pos;
+        let inset = cell.inset(styles).unwrap_or(self.inset(styles));
+        let mut body = cell.body().padded(Sides::splat(inset));
+
+        let align = match cell.align(styles) {
+            Smart::Custom(align) => Smart::Custom(align),
+            Smart::Auto => self.align(styles).resolve(vt, x, y)?,
+        };
+        if let Smart::Custom(alignment) = align {
+            body = body.styled(AlignElem::set_alignment(alignment));
+        }
+
+        let fill = match cell.fill(styles) {
+            Smart::Custom(fill) => fill,
+            Smart::Auto => self.fill(styles).resolve(vt, x, y)?,
+        };
+
+        // Sides without a stroke don't request a line.
+        let base_stroke = self
+            .stroke(styles)
+            .resolve(vt, x, y)?
+            .resolve(styles)
+            .map(Option::flatten);
+
+        // The cell's own tag comes first, followed by those of its row and
+        // of the header or footer that contains it.
+        let header = tags.iter().any(|tag| tag.role == Role::TableHeader);
+        let role = if header { Role::TableHeaderCell } else { Role::TableCell };
+        body = body.tagged(Tag::new(vt, role, &(id, x, y)));
+        for tag in tags {
+            body = body.tagged(tag.clone());
+        }
+
+        Ok(Cell {
+            body,
+            fill,
+            stroke: cell.stroke(styles),
+            base_stroke,
+            colspan: cell.colspan(styles),
+            rowspan: cell.rowspan(styles),
         })
+    }
+}
+
+/// A cell in the table.
+///
+/// Use this element to merge cells by letting them span multiple columns or
+/// rows, to place a cell at a specific position or to override the table's
+/// properties for a single cell. Cells without an explicit position are placed
+/// into the next free slots in row-major order.
+///
+/// ## Example
+/// ```example
+/// #table(
+///   columns: 3,
+///   table.cell(colspan: 3, align: center)[*Results*],
+///   table.cell(rowspan: 2)[Trial],
+///   [Day 1], [Day 2],
+///   [12], [14],
+///   table.cell(x: 2, y: 3, fill: yellow)[Best],
+/// )
+/// ```
+///
+/// Display: Table Cell
+/// Category: layout
+#[element(Show)]
+pub struct TableCell {
+    /// The cell's body.
+    #[required]
+    pub body: Content,
+
+    /// The cell's column, starting at zero. If set to `{auto}`, the cell is
+    /// placed into the next column that has space for it.
+    pub x: Smart<usize>,
+
+    /// The cell's row, starting at zero. If set to `{auto}`, the cell is
+    /// placed into the next row that has space for it.
+    pub y: Smart<usize>,
+
+    /// The number of columns the cell spans.
+    #[default(NonZeroUsize::ONE)]
+    pub colspan: NonZeroUsize,
+
+    /// The number of rows the cell spans.
+    #[default(NonZeroUsize::ONE)]
+    pub rowspan: NonZeroUsize,
+
+    /// How to fill the cell. If set to `{auto}`, the table's fill is used.
+    pub fill: Smart<Option<Paint>>,
+
+    /// How to align the cell's content. If set to `{auto}`, the table's
+    /// alignment is used.
+    pub align: Smart<Axes<Option<GenAlign>>>,
+
+    /// How much to pad the cell's content. If set to `{auto}`, the table's
+    /// inset is used.
+    pub inset: Smart<Rel<Length>>,
+
+    /// How to stroke the cell's sides, overriding the table's stroke.
+    ///
+    /// Accepts the same values as the [rectangle's stroke]($func/rect.stroke).
+    /// Sides that are not specified keep the table's stroke. Between two
+    /// adjacent cells, the stroke of the cell to the right or below takes
+    /// precedence. Setting a side to `{none}` removes the line there.
+    ///
+    /// ```example
+    /// #table(
+    ///   columns: 3,
+    ///   [A], table.cell(stroke: 2pt + red)[B], [C],
+    /// )
+    /// ```
+    #[resolve]
+    pub stroke: Sides<Option<Option<PartialStroke>>>,
+}
+
+impl Show for TableCell {
+    fn show(&self, _: &mut Vt, _: StyleChain) -> SourceResult<Content> {
+        Ok(self.body())
+    }
+}
+
+/// The header of a table.
+///
+/// The rows of the header are repeated at the top of every page the table
+/// spans. A header is never left on its own at the bottom of a page: If none of
+/// the following rows fit below it, it moves to the next page along with them.
+/// In accessible PDFs, the header's cells are marked as header cells and its
+/// repetitions are excluded from the document's structure.
+///
+/// The header must be the first child of the table. Its cells are placed like
+/// any others and may be [`table.cell`]($func/table.cell) elements.
+///
+/// ## Example
+/// ```example
+/// #set page(height: 120pt)
+/// #table(
+///   columns: 2,
+///   table.header[*Name*][*Score*],
+///   ..range(8).map(i => ([Player #i], [#(i * 10)])).flatten(),
+///   table.footer[*Total*][280],
+/// )
+/// ```
+///
+/// Display: Table Header
+/// Category: layout
+#[element(Show)]
+pub struct TableHeader {
+    /// Whether the header is repeated on every page. If `{false}`, it only
+    /// appears at the start of the table.
+    #[default(true)]
+    pub repeat: bool,
+
+    /// The cells of the header.
+    #[variadic]
+    pub children: Vec<Content>,
+}
+
+impl Show for TableHeader {
+    fn show(&self, _: &mut Vt, _: StyleChain) -> SourceResult<Content> {
+        Ok(Content::sequence(self.children()))
+    }
+}
+
+/// The footer of a table.
+///
+/// The rows of the footer are repeated at the bottom of every page the table
+/// spans. In accessible PDFs, its repetitions are excluded from the
+/// document's structure.
+///
+/// The footer must be the last child of the table. Its cells are placed like
+/// any others and may be [`table.cell`]($func/table.cell) elements.
+///
+/// Display: Table Footer
+/// Category: layout
+#[element(Show)]
+pub struct TableFooter {
+    /// Whether the footer is repeated on every page. If `{false}`, it only
+    /// appears at the end of the table.
+    #[default(true)]
+    pub repeat: bool,
+
+    /// The cells of the footer.
+    #[variadic]
+    pub children: Vec<Content>,
+}
+
+impl Show for TableFooter {
+    fn show(&self, _: &mut Vt, _: StyleChain) -> SourceResult<Content> {
+        Ok(Content::sequence(self.children()))
+    }
+}
+
+/// A horizontal line in the table.
+///
+/// The line is drawn between two rows and takes precedence over the strokes
+/// of the table and its cells. It is not drawn through cells that span the
+/// rows it separates.
+///
+/// ## Example
+/// ```example
+/// #table(
+///   columns: 3,
+///   stroke: none,
+///   table.hline(stroke: 1pt),
+///   [*Planet*], [*Moons*], [*Rings*],
+///   table.hline(stroke: 0.5pt),
+///   [Earth], [1], [No],
+///   [Saturn], [146], [Yes],
+///   table.hline(start: 1, stroke: (dash: "dotted")),
+///   [Total], [147], [],
+///   table.hline(stroke: 1pt),
+/// )
+/// ```
+///
+/// Display: Table Horizontal Line
+/// Category: layout
+#[element(Show)]
+pub struct TableHLine {
+    /// The row above which the line is drawn, starting at zero. A line at the
+    /// number of rows is drawn at the bottom of the table. If set to
+    /// `{auto}`, the line is drawn below the row of the preceding cell.
+    pub y: Smart<usize>,
+
+    /// The column at which the line starts.
+    pub start: usize,
+
+    /// The column before which the line ends. If set to `{auto}`, the line
+    /// extends to the end of the table.
+    pub end: Smart<usize>,
+
+    /// How to stroke the line. If set to `{none}`, the table's lines are
+    /// removed there instead.
+    #[resolve]
+    #[fold]
+    #[default(Some(PartialStroke::default()))]
+    pub stroke: Option<PartialStroke>,
+}
+
+impl Show for TableHLine {
+    fn show(&self, _: &mut Vt, _: StyleChain) -> SourceResult<Content> {
+        Ok(Content::empty())
+    }
+}
+
+/// A vertical line in the table.
+///
+/// The line is drawn between two columns and takes precedence over the
+/// strokes of the table and its cells. It is not drawn through cells that
+/// span the columns it separates.
+///
+/// ## Example
+/// ```example
+/// #table(
+///   columns: 3,
+///   stroke: none,
+///   [A], table.vline(), [B], [C],
+///   [D], [E], [F],
+/// )
+/// ```
+///
+/// Display: Table Vertical Line
+/// Category: layout
+#[element(Show)]
+pub struct TableVLine {
+    /// The column before which the line is drawn, starting at zero. A line at
+    /// the number of columns is drawn at the end of the table. If set to
+    /// `{auto}`, the line is drawn after the column of the preceding cell.
+    pub x: Smart<usize>,
+
+    /// The row at which the line starts.
+    pub start: usize,
+
+    /// The row before which the line ends. If set to `{auto}`, the line
+    /// extends to the end of the table.
+    pub end: Smart<usize>,
+
+    /// How to stroke the line. If set to `{none}`, the table's lines are
+    /// removed there instead.
+    #[resolve]
+    #[fold]
+    #[default(Some(PartialStroke::default()))]
+    pub stroke: Option<PartialStroke>,
+}
+
+impl Show for TableVLine {
+    fn show(&self, _: &mut Vt, _: StyleChain) -> SourceResult<Content> {
+        Ok(Content::empty())
+    }
 }
 
 /// A value that can be configured per cell.
@@ -84,6 +84,8 @@ fn global(math: Module, calc: Module) -> Module {
     global.define("ellipse", visualize::EllipseElem::func());
     global.define("circle", visualize::CircleElem::func());
     global.define("polygon", visualize::PolygonElem::func());
+    global.define("gradient", visualize::gradient::module());
+    global.define("pattern", visualize::pattern);
 
     // Meta.
     global.define("document", meta::DocumentElem::func());
@@ -92,14 +94,17 @@ fn global(math: Module, calc: Module) -> Module {
     global.define("outline", meta::OutlineElem::func());
     global.define("heading", meta::HeadingElem::func());
     global.define("figure", meta::FigureElem::func());
+    global.define("footnote", meta::FootnoteElem::func());
     global.define("cite", meta::CiteElem::func());
     global.define("bibliography", meta::BibliographyElem::func());
+    global.define("metadata", meta::MetadataElem::func());
     global.define("locate", meta::locate);
     global.define("style", meta::style);
     global.define("counter", meta::counter);
     global.define("numbering", meta::numbering);
     global.define("state", meta::state);
     global.define("query", meta::query);
+    global.define("selector", meta::selector);
 
     // Symbols.
     global.define("sym", symbols::sym());
@@ -118,13 +123,19 @@ fn global(math: Module, calc: Module) -> Module {
     global.define("cmyk", compute::cmyk);
     global.define("symbol", compute::symbol);
     global.define("str", compute::str);
+    global.define("bytes", compute::bytes);
+    global.define("array", compute::array);
     global.define("label", compute::label);
     global.define("regex", compute::regex);
+    global.define("datetime", compute::datetime);
+    global.define("duration", compute::duration);
     global.define("range", compute::range);
     global.define("read", compute::read);
     global.define("csv", compute::csv);
     global.define("json", compute::json);
     global.define("xml", compute::xml);
+    global.define("toml", compute::toml);
+    global.define("yaml", compute::yaml);
 
     // Calc.
     global.define("calc", calc);
@@ -218,7 +218,7 @@ impl GlyphFragment {
         let item = TextItem {
             font: self.font.clone(),
             size: self.font_size,
-            fill: self.fill,
+            fill: self.fill.clone(),
             lang: self.lang,
             glyphs: vec![Glyph {
                 id: self.id.0,
@@ -150,7 +150,13 @@ impl Show for BibliographyElem {
         if works.references.iter().any(|(prefix, _)| prefix.is_some()) {
             let mut cells = vec![];
             for (prefix, reference) in &works.references {
-                cells.push(prefix.clone().unwrap_or_default());
+                // The prefix links to the first citation of the reference.
+                cells.push(match prefix {
+                    Some(prefix) => {
+                        prefix.clone().tagged(Tag::new(vt, Role::Link, prefix))
+                    }
+                    None => Content::empty(),
+                });
                 cells.push(reference.clone());
             }
 
@@ -310,13 +316,16 @@ impl Show for CiteElem {
 
         let works = Works::new(vt).at(self.span())?;
         let location = self.0.location().unwrap();
-        works
+        let citation = works
             .citations
             .get(&location)
             .cloned()
             .flatten()
             .ok_or("bibliography does not contain this key")
-            .at(self.span())
+            .at(self.span())?;
+
+        // The citation links to the entries it cites.
+        Ok(citation.tagged(Tag::new(vt, Role::Link, self)))
     }
 }
 
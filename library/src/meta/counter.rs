@@ -314,6 +314,20 @@ impl Counter {
         Ok(state)
     }
 
+    /// Get the value of the counter at the end of the given page.
+    pub fn at_page(&self, vt: &mut Vt, page: NonZeroUsize) -> SourceResult<CounterState> {
+        let sequence = self.sequence(vt)?;
+        let (mut state, prev) = sequence
+            .iter()
+            .rfind(|(_, stop)| *stop <= page)
+            .cloned()
+            .unwrap_or_else(|| (self.initial(), NonZeroUsize::ONE));
+        if self.is_page() {
+            state.step(NonZeroUsize::ONE, page.get() - prev.get());
+        }
+        Ok(state)
+    }
+
     /// Get the value of the state at the final location.
     pub fn final_(&self, vt: &mut Vt, _: Location) -> SourceResult<CounterState> {
         let sequence = self.sequence(vt)?;
@@ -371,10 +385,7 @@ impl Counter {
         introspector: Tracked<Introspector>,
     ) -> SourceResult<EcoVec<(CounterState, NonZeroUsize)>> {
         let mut vt = Vt { world, tracer, provider, introspector };
-        let mut state = CounterState(match &self.0 {
-            CounterKey::Selector(_) => smallvec![0],
-            _ => smallvec![1],
-        });
+        let mut state = self.initial();
         let mut page = NonZeroUsize::ONE;
         let mut stops = eco_vec![(state.clone(), page)];
 
@@ -406,6 +417,14 @@ impl Counter {
         Ok(stops)
     }
 
+    /// The state of the counter before any updates.
+    fn initial(&self) -> CounterState {
+        CounterState(match &self.0 {
+            CounterKey::Selector(_) => smallvec![0],
+            _ => smallvec![1],
+        })
+    }
+
     /// The selector relevant for this counter's updates.
     fn selector(&self) -> Selector {
         let mut selector =
@@ -1,3 +1,4 @@
+use super::{Counter, CounterKey};
 use crate::layout::{LayoutRoot, PageElem};
 use crate::prelude::*;
 
@@ -31,6 +32,7 @@ impl LayoutRoot for DocumentElem {
     /// Layout the document into a sequence of frames, one per page.
     fn layout_root(&self, vt: &mut Vt, styles: StyleChain) -> SourceResult<Document> {
         let mut pages = vec![];
+        let mut page_labels = vec![];
 
         for mut child in &self.children() {
             let outer = styles;
@@ -41,8 +43,21 @@ impl LayoutRoot for DocumentElem {
             }
 
             if let Some(page) = child.to::<PageElem>() {
+                let numbering = page.numbering(styles);
                 let fragment = page.layout(vt, styles)?;
-                pages.extend(fragment);
+                for frame in fragment {
+                    pages.push(frame);
+                    let label = match &numbering {
+                        Some(numbering) => {
+                            let index = NonZeroUsize::new(pages.len()).unwrap();
+                            let state =
+                                Counter::new(CounterKey::Page).at_page(vt, index)?;
+                            numbering.page_label(vt, state.first())?
+                        }
+                        None => None,
+                    };
+                    page_labels.push(label);
+                }
             } else {
                 bail!(child.span(), "unexpected document child");
             }
@@ -52,6 +67,7 @@ impl LayoutRoot for DocumentElem {
             pages,
             title: self.title(styles),
             author: self.author(styles).0,
+            page_labels,
         })
     }
 }
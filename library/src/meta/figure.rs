@@ -1,9 +1,10 @@
 use std::str::FromStr;
 
 use super::{Count, Counter, CounterUpdate, LocalName, Numbering, NumberingPattern};
-use crate::layout::{BlockElem, VElem};
+use crate::layout::{BlockElem, PlaceElem, PlacementScope, VElem};
 use crate::prelude::*;
 use crate::text::TextElem;
+use crate::visualize::ImageElem;
 
 /// A figure with an optional caption.
 ///
@@ -32,6 +33,38 @@ pub struct FigureElem {
     /// The figure's caption.
     pub caption: Option<Content>,
 
+    /// The figure's placement on the page.
+    ///
+    /// - `{none}`: The figure stays in-flow exactly where it was specified.
+    /// - `{auto}`: The figure floats to the top or bottom of the page,
+    ///   whichever is closer to its position in the flow.
+    /// - `{top}` or `{bottom}`: The figure floats to the top or bottom of the
+    ///   page.
+    ///
+    /// A floating figure that doesn't fit into the remaining space is moved to
+    /// the next page or column.
+    ///
+    /// ```example
+    /// #set page(height: 200pt)
+    ///
+    /// = Introduction
+    /// #figure(
+    ///   placement: bottom,
+    ///   caption: [A glacier],
+    ///   image("glacier.jpg", width: 60%),
+    /// )
+    /// #lorem(60)
+    /// ```
+    pub placement: Option<Smart<GenAlign>>,
+
+    /// Relative to which container a floating figure is placed.
+    ///
+    /// Set this to `{"parent"}` to make the figure span all columns of a
+    /// multi-column layout. See the [place]($func/place.scope) function for
+    /// details.
+    #[default(PlacementScope::Column)]
+    pub scope: PlacementScope,
+
     /// How to number the figure. Accepts a
     /// [numbering pattern or function]($func/numbering).
     #[default(Some(NumberingPattern::from_str("1").unwrap().into()))]
@@ -40,6 +73,11 @@ pub struct FigureElem {
     /// The vertical gap between the body and caption.
     #[default(Em::new(0.65).into())]
     pub gap: Length,
+
+    /// A description of the figure for readers who can't see it, for example
+    /// because they use a screen reader. Defaults to the description of the
+    /// figure's body if it is an [image]($func/image).
+    pub alt: Option<EcoString>,
 }
 
 impl Synthesize for FigureElem {
@@ -49,7 +87,7 @@ impl Synthesize for FigureElem {
 }
 
 impl Show for FigureElem {
-    fn show(&self, _: &mut Vt, styles: StyleChain) -> SourceResult<Content> {
+    fn show(&self, vt: &mut Vt, styles: StyleChain) -> SourceResult<Content> {
         let mut realized = self.body();
 
         if let Some(mut caption) = self.caption(styles) {
@@ -64,14 +102,34 @@ impl Show for FigureElem {
             }
 
             realized += VElem::weak(self.gap(styles).into()).pack();
-            realized += caption;
+            realized += caption.tagged(Tag::new(vt, Role::Caption, self));
         }
 
-        Ok(BlockElem::new()
+        let alt = self.alt(styles).or_else(|| {
+            self.body().to::<ImageElem>().and_then(|image| image.alt(styles))
+        });
+
+        realized = BlockElem::new()
             .with_body(Some(realized))
             .with_breakable(false)
             .pack()
-            .aligned(Axes::with_x(Some(Align::Center.into()))))
+            .tagged(Tag::new(vt, Role::Figure(alt), self))
+            .aligned(Axes::with_x(Some(Align::Center.into())));
+
+        // Wrap the figure in a floating place.
+        if let Some(placement) = self.placement(styles) {
+            realized = PlaceElem::new(realized)
+                .with_alignment(Axes::new(
+                    Some(Align::Center.into()),
+                    placement.map_or(None, Some),
+                ))
+                .with_float(true)
+                .with_scope(self.scope(styles))
+                .pack()
+                .spanned(self.span());
+        }
+
+        Ok(realized)
     }
 }
 
@@ -0,0 +1,271 @@
+use std::str::FromStr;
+
+use super::{Count, Counter, CounterUpdate, Numbering, NumberingPattern};
+use crate::layout::{HElem, ParElem};
+use crate::prelude::*;
+use crate::text::{SuperElem, TextElem, TextSize};
+use crate::visualize::LineElem;
+
+/// A footnote.
+///
+/// Include additional remarks and references on the same page with footnotes.
+/// A footnote will insert a superscript number that links to the note at the
+/// bottom of the page. Notes are numbered sequentially throughout your
+/// document and can break across multiple pages.
+///
+/// To customize the appearance of the entry in the footnote listing, see
+/// [`footnote.entry`]($func/footnote.entry). The footnote itself is realized
+/// as a normal superscript, so you can use a set rule on the
+/// [`super`]($func/super) function to customize it.
+///
+/// ## Example
+/// ```example
+/// Check the docs for more details.
+/// #footnote[https://typst.app/docs]
+/// ```
+///
+/// The footnote automatically attaches itself to the preceding word, even if
+/// there is a space before it in the markup. To force space, you can use the
+/// string `[#" "]` or explicit [horizontal spacing]($func/h).
+///
+/// By giving a label to a footnote, you can have multiple references to it.
+///
+/// ```example
+/// You can edit Typst documents online.
+/// #footnote[https://typst.app/app] <fn>
+/// Checkout Typst's website. @fn
+/// And the online app. #footnote(<fn>)
+/// ```
+///
+/// Display: Footnote
+/// Category: meta
+#[element(Locatable, Synthesize, Show, Count)]
+#[scope(
+    scope.define("entry", FootnoteEntry::func());
+    scope
+)]
+pub struct FootnoteElem {
+    /// How to number footnotes.
+    ///
+    /// By default, the footnote numbering continues throughout your document.
+    /// If you prefer per-page footnote numbering, you can reset the footnote
+    /// [counter]($func/counter) in the page [header]($func/page.header).
+    ///
+    /// ```example
+    /// #set footnote(numbering: "*")
+    ///
+    /// Footnotes:
+    /// #footnote[Star],
+    /// #footnote[Dagger]
+    /// ```
+    #[default(Numbering::Pattern(NumberingPattern::from_str("1").unwrap()))]
+    pub numbering: Numbering,
+
+    /// The content to put into the footnote. Can also be the label of another
+    /// footnote this one should point to.
+    #[required]
+    pub body: FootnoteBody,
+}
+
+impl FootnoteElem {
+    /// Whether this footnote is a reference to another footnote.
+    pub fn is_ref(&self) -> bool {
+        matches!(self.body(), FootnoteBody::Reference(_))
+    }
+
+    /// The content of this footnote if it is not a reference.
+    pub fn body_content(&self) -> Option<Content> {
+        match self.body() {
+            FootnoteBody::Content(content) => Some(content),
+            FootnoteBody::Reference(_) => None,
+        }
+    }
+
+    /// The location of the footnote that holds the content, i.e. this one or
+    /// the one it refers to.
+    pub fn declaration_location(&self, vt: &Vt) -> StrResult<Location> {
+        match self.body() {
+            FootnoteBody::Content(_) => Ok(self.0.location().unwrap()),
+            FootnoteBody::Reference(label) => {
+                let elem = vt.introspector.query_label(&label)?;
+                let Some(footnote) = elem.to::<FootnoteElem>() else {
+                    return Err("referenced element should be a footnote".into());
+                };
+                if footnote.is_ref() {
+                    return Err("cannot refer to a footnote that is a reference".into());
+                }
+                Ok(footnote.0.location().unwrap())
+            }
+        }
+    }
+}
+
+impl Synthesize for FootnoteElem {
+    fn synthesize(&mut self, styles: StyleChain) {
+        self.push_numbering(self.numbering(styles));
+    }
+}
+
+impl Show for FootnoteElem {
+    fn show(&self, vt: &mut Vt, styles: StyleChain) -> SourceResult<Content> {
+        if self.is_ref() && !vt.introspector.init() {
+            return Ok(Content::empty());
+        }
+
+        let loc = self.declaration_location(vt).at(self.span())?;
+        let numbering = self.numbering(styles);
+        let counter = Counter::of(Self::func());
+        let num = counter.at(vt, loc)?.display(vt, &numbering)?;
+        let sup = SuperElem::new(num).pack();
+        let hole = HElem::new(Abs::zero().into()).with_weak(true).pack();
+        let tag = Tag::new(vt, Role::Link, self);
+        Ok(hole + sup.linked(Destination::Location(loc.variant(1))).tagged(tag))
+    }
+}
+
+impl Count for FootnoteElem {
+    fn update(&self) -> Option<CounterUpdate> {
+        (!self.is_ref()).then(|| CounterUpdate::Step(NonZeroUsize::ONE))
+    }
+}
+
+/// The body of a footnote: Either content or the label of another footnote.
+#[derive(Debug, Clone, Hash)]
+pub enum FootnoteBody {
+    Content(Content),
+    Reference(Label),
+}
+
+cast_from_value! {
+    FootnoteBody,
+    v: Label => Self::Reference(v),
+    v: Content => Self::Content(v),
+}
+
+cast_to_value! {
+    v: FootnoteBody => match v {
+        FootnoteBody::Content(v) => v.into(),
+        FootnoteBody::Reference(v) => v.into(),
+    }
+}
+
+/// An entry in a footnote listing.
+///
+/// This function is not intended to be called directly. Instead, it is used
+/// in set and show rules to customize footnote listings.
+///
+/// ## Example
+/// ```example
+/// #show footnote.entry: set text(red)
+///
+/// My footnote listing
+/// #footnote[It's down here]
+/// has red text!
+/// ```
+///
+/// Display: Footnote Entry
+/// Category: meta
+#[element(Show, Finalize)]
+pub struct FootnoteEntry {
+    /// The footnote for this entry. Its location can be used to determine
+    /// the footnote counter state.
+    ///
+    /// ```example
+    /// #show footnote.entry: it => {
+    ///   let loc = it.note.location()
+    ///   numbering(
+    ///     "1: ",
+    ///     ..counter(footnote).at(loc),
+    ///   )
+    ///   it.note.body
+    /// }
+    ///
+    /// Customized #footnote[Hello]
+    /// listing #footnote[World!]
+    /// ```
+    #[required]
+    pub note: FootnoteElem,
+
+    /// The separator between the document body and the footnote listing.
+    ///
+    /// ```example
+    /// #set footnote.entry(
+    ///   separator: repeat[.]
+    /// )
+    ///
+    /// Testing a different separator.
+    /// #footnote[
+    ///   Unconventional, but maybe
+    ///   not that bad?
+    /// ]
+    /// ```
+    #[default(
+        LineElem::new()
+            .with_length(Ratio::new(0.3).into())
+            .with_stroke(PartialStroke {
+                thickness: Smart::Custom(Abs::pt(0.5).into()),
+                ..Default::default()
+            })
+            .pack()
+    )]
+    pub separator: Content,
+
+    /// The amount of clearance between the document body and the separator.
+    #[default(Em::new(1.0).into())]
+    #[resolve]
+    pub clearance: Length,
+
+    /// The gap between footnote entries.
+    #[default(Em::new(0.5).into())]
+    #[resolve]
+    pub gap: Length,
+
+    /// The indent of each footnote entry.
+    #[default(Em::new(1.0).into())]
+    pub indent: Length,
+}
+
+impl Show for FootnoteEntry {
+    fn show(&self, vt: &mut Vt, styles: StyleChain) -> SourceResult<Content> {
+        let note = self.note();
+        let number_gap = Em::new(0.05);
+        let numbering = note.numbering(StyleChain::default());
+        let counter = Counter::of(FootnoteElem::func());
+        let loc = note.0.location().unwrap();
+        let num = counter.at(vt, loc)?.display(vt, &numbering)?;
+
+        // Make the link from the footnote to its entry work.
+        let backlink = {
+            let mut content = Content::empty();
+            content.set_location(loc.variant(1));
+            MetaElem::set_data(vec![Meta::Elem(content)])
+        };
+
+        let sup = SuperElem::new(num)
+            .pack()
+            .linked(Destination::Location(loc))
+            .styled(backlink);
+
+        Ok(Content::sequence([
+            HElem::new(self.indent(styles).into()).pack(),
+            sup,
+            HElem::new(number_gap.into()).with_weak(true).pack(),
+            note.body_content().unwrap_or_default(),
+        ]))
+    }
+}
+
+impl Finalize for FootnoteEntry {
+    fn finalize(&self, realized: Content, _: StyleChain) -> Content {
+        let text_size = Em::new(0.85);
+        let leading = Em::new(0.5);
+        realized
+            .styled(ParElem::set_leading(leading.into()))
+            .styled(TextElem::set_size(TextSize(text_size.into())))
+    }
+}
+
+cast_from_value! {
+    FootnoteElem,
+    v: Content => v.to::<Self>().cloned().ok_or("expected footnote")?,
+}
@@ -88,7 +88,8 @@ impl Synthesize for HeadingElem {
 }
 
 impl Show for HeadingElem {
-    fn show(&self, _: &mut Vt, styles: StyleChain) -> SourceResult<Content> {
+    fn show(&self, vt: &mut Vt, styles: StyleChain) -> SourceResult<Content> {
+        let tag = Tag::new(vt, Role::Heading(self.level(styles)), self);
         let mut realized = self.body();
         if let Some(numbering) = self.numbering(styles) {
             realized = Counter::of(Self::func())
@@ -97,7 +98,7 @@ impl Show for HeadingElem {
                 + HElem::new(Em::new(0.3).into()).with_weak(true).pack()
                 + realized;
         }
-        Ok(BlockElem::new().with_body(Some(realized)).pack())
+        Ok(BlockElem::new().with_body(Some(realized)).pack().tagged(tag))
     }
 }
 
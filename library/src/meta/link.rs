@@ -104,6 +104,7 @@ impl Show for LinkElem {
 
         Ok(body
             .linked(dest)
+            .tagged(Tag::new(vt, Role::Link, self))
             .styled(TextElem::set_hyphenate(Hyphenate(Smart::Custom(false)))))
     }
 }
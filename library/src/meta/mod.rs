@@ -5,8 +5,10 @@ mod context;
 mod counter;
 mod document;
 mod figure;
+mod footnote;
 mod heading;
 mod link;
+mod metadata;
 mod numbering;
 mod outline;
 mod query;
@@ -18,8 +20,10 @@ pub use self::context::*;
 pub use self::counter::*;
 pub use self::document::*;
 pub use self::figure::*;
+pub use self::footnote::*;
 pub use self::heading::*;
 pub use self::link::*;
+pub use self::metadata::*;
 pub use self::numbering::*;
 pub use self::outline::*;
 pub use self::query::*;
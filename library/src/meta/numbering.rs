@@ -99,6 +99,21 @@ impl Numbering {
         })
     }
 
+    /// Determine the label of a page with the given logical number.
+    pub fn page_label(
+        &self,
+        vt: &mut Vt,
+        number: usize,
+    ) -> SourceResult<Option<PageLabel>> {
+        Ok(match self {
+            Self::Pattern(pattern) => Some(pattern.page_label(number)),
+            Self::Func(_) => match self.apply_vt(vt, &[number])? {
+                Value::Str(text) => Some(PageLabel::text(text)),
+                _ => None,
+            },
+        })
+    }
+
     /// Trim the prefix suffix if this is a pattern.
     pub fn trimmed(mut self) -> Self {
         if let Self::Pattern(pattern) = &mut self {
@@ -194,6 +209,38 @@ impl NumberingPattern {
         fmt
     }
 
+    /// Express the first counting symbol applied to the given number as a page
+    /// label.
+    ///
+    /// If the result can't be expressed through a prefix and one of the
+    /// standard numbering styles, the label consists of the formatted text.
+    pub fn page_label(&self, number: usize) -> PageLabel {
+        let style = match self.pieces.first() {
+            Some((_, NumberingKind::Arabic, _)) => Some(PageLabelStyle::Arabic),
+            Some((_, NumberingKind::Roman, case)) if number < 4000 => Some(match case {
+                Case::Lower => PageLabelStyle::LowerRoman,
+                Case::Upper => PageLabelStyle::UpperRoman,
+            }),
+            Some((_, NumberingKind::Letter, case)) if number <= 26 => Some(match case {
+                Case::Lower => PageLabelStyle::LowerAlpha,
+                Case::Upper => PageLabelStyle::UpperAlpha,
+            }),
+            _ => None,
+        };
+
+        match (style, NonZeroUsize::new(number)) {
+            (Some(style), Some(number)) if self.suffix.is_empty() => {
+                let prefix = &self.pieces[0].0;
+                PageLabel {
+                    prefix: (!prefix.is_empty()).then(|| prefix.clone()),
+                    style: Some(style),
+                    number: Some(number),
+                }
+            }
+            _ => PageLabel::text(self.apply_kth(0, number)),
+        }
+    }
+
     /// How many counting symbols this pattern has.
     pub fn pieces(&self) -> usize {
         self.pieces.len()
@@ -364,3 +411,41 @@ impl NumberingKind {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[track_caller]
+    fn test(pattern: &str, number: usize, exp: PageLabel) {
+        let pattern: NumberingPattern = pattern.parse().unwrap();
+        assert_eq!(pattern.page_label(number), exp);
+    }
+
+    fn label(prefix: Option<&str>, style: PageLabelStyle, number: usize) -> PageLabel {
+        PageLabel {
+            prefix: prefix.map(Into::into),
+            style: Some(style),
+            number: NonZeroUsize::new(number),
+        }
+    }
+
+    #[test]
+    fn test_numbering_page_label() {
+        // Standard styles.
+        test("1", 3, label(None, PageLabelStyle::Arabic, 3));
+        test("i", 4, label(None, PageLabelStyle::LowerRoman, 4));
+        test("I", 9, label(None, PageLabelStyle::UpperRoman, 9));
+        test("a", 2, label(None, PageLabelStyle::LowerAlpha, 2));
+        test("A", 26, label(None, PageLabelStyle::UpperAlpha, 26));
+
+        // Prefixes.
+        test("S-1", 5, label(Some("S-"), PageLabelStyle::Arabic, 5));
+        test("p. i", 2, label(Some("p. "), PageLabelStyle::LowerRoman, 2));
+
+        // Labels that can't be expressed through a style.
+        test("1.", 3, PageLabel::text("3."));
+        test("(a)", 1, PageLabel::text("(a)"));
+        test("*", 2, PageLabel::text("†"));
+    }
+}
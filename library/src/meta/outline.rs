@@ -143,7 +143,8 @@ impl Show for OutlineElem {
             };
 
             // Add the numbering and section name.
-            seq.push(start.linked(Destination::Location(location)));
+            let tag = Tag::new(vt, Role::Link, &start);
+            seq.push(start.linked(Destination::Location(location)).tagged(tag));
 
             // Add filler symbols between the section name and page number.
             if let Some(filler) = self.fill(styles) {
@@ -165,7 +166,8 @@ impl Show for OutlineElem {
                 .at(vt, location)?
                 .first();
             let end = TextElem::packed(eco_format!("{page}"));
-            seq.push(end.linked(Destination::Location(location)));
+            let tag = Tag::new(vt, Role::Link, &end);
+            seq.push(end.linked(Destination::Location(location)).tagged(tag));
             seq.push(LinebreakElem::new().pack());
             ancestors.push(heading);
         }
@@ -94,8 +94,15 @@ use crate::prelude::*;
 /// Returns: content
 #[func]
 pub fn query(
-    /// Can be an element function like a `heading` or `figure` or a
-    /// `{<label>}`.
+    /// Can be an element function like a `heading` or `figure`, a `{<label>}`
+    /// or a more complex selector like `{heading.where(level: 1)}`.
+    ///
+    /// Selectors can be refined with their
+    /// [`before`]($type/selector.before), [`after`]($type/selector.after) and
+    /// [`within`]($type/selector.within) methods to only find elements in a
+    /// certain part of the document. For instance,
+    /// `{figure.where(kind: table).within(<chapter>)}` finds all tables in the
+    /// element labelled `<chapter>`.
     ///
     /// Currently, only a subset of element functions is supported. Aside from
     /// headings and figures, this includes equations, references and all
@@ -152,17 +159,70 @@ pub fn query(
     elements.into()
 }
 
+/// Turns a value into a [selector]($type/selector).
+///
+/// If you want to refine an element function or a label with the
+/// [selector methods]($type/selector), you first need to turn it into a
+/// selector with this function.
+///
+/// ## Example
+/// ```example
+/// #locate(loc => {
+///   let figures = query(
+///     selector(figure).within(<appendix>),
+///     loc,
+///   )
+///   [Figures in the appendix: #figures.len()]
+/// })
+///
+/// #figure(rect[A], caption: [Main])
+/// #block[
+///   #figure(rect[B], caption: [Extra])
+/// ] <appendix>
+/// ```
+///
+/// Display: Selector
+/// Category: meta
+/// Returns: selector
+#[func]
+pub fn selector(
+    /// Can be an element function like a `heading` or `figure`, a `{<label>}`
+    /// or a more complex selector like `{heading.where(level: 1)}`.
+    target: Selector,
+) -> Value {
+    Value::dynamic(target)
+}
+
 /// A query target.
 struct Target(Selector);
 
 cast_from_value! {
     Target,
-    label: Label => Self(Selector::Label(label)),
-    element: ElemFunc => {
-        if !Content::new(element).can::<dyn Locatable>() {
-            Err(eco_format!("cannot query for {}s", element.name()))?;
-        }
+    selector: Selector => {
+        queryable(&selector)?;
+        Self(selector)
+    }
+}
 
-        Self(Selector::Elem(element, None))
+/// Ensure that all elements matched by a selector can be found by queries.
+pub fn queryable(selector: &Selector) -> StrResult<()> {
+    match selector {
+        Selector::Elem(element, _) => {
+            if !Content::new(*element).can::<dyn Locatable>() {
+                Err(eco_format!("cannot query for {}s", element.name()))?;
+            }
+        }
+        Selector::Regex(_) => return Err("cannot query for text".into()),
+        Selector::Any(selectors) | Selector::And(selectors) => {
+            selectors.iter().try_for_each(queryable)?
+        }
+        Selector::Before { selector, end: other, .. }
+        | Selector::After { selector, start: other, .. }
+        | Selector::Within { selector, ancestor: other } => {
+            queryable(selector)?;
+            queryable(other)?;
+        }
+        Selector::Location(_) | Selector::Label(_) => {}
     }
+    Ok(())
 }
@@ -1,4 +1,6 @@
-use super::{BibliographyElem, CiteElem, Counter, LocalName, Numbering};
+use super::{
+    BibliographyElem, CiteElem, Counter, FootnoteBody, FootnoteElem, LocalName, Numbering,
+};
 use crate::prelude::*;
 use crate::text::TextElem;
 
@@ -111,6 +113,12 @@ impl Show for RefElem {
             bail!(self.span(), "cannot reference {}", elem.func().name());
         }
 
+        if elem.is::<FootnoteElem>() {
+            return Ok(FootnoteElem::new(FootnoteBody::Reference(target))
+                .pack()
+                .spanned(self.span()));
+        }
+
         let supplement = self.supplement(styles);
         let mut supplement = match supplement {
             Smart::Auto => elem
@@ -137,7 +145,9 @@ impl Show for RefElem {
             .at(vt, elem.location().unwrap())?
             .display(vt, &numbering.trimmed())?;
 
-        Ok((supplement + numbers).linked(Destination::Location(elem.location().unwrap())))
+        Ok((supplement + numbers)
+            .linked(Destination::Location(elem.location().unwrap()))
+            .tagged(Tag::new(vt, Role::Link, self)))
     }
 }
 
@@ -18,6 +18,10 @@ pub trait ContentExt {
     /// Link the content somewhere.
     fn linked(self, dest: Destination) -> Self;
 
+    /// Mark this content as part of an element in the document's logical
+    /// structure.
+    fn tagged(self, tag: Tag) -> Self;
+
     /// Set alignments for this content.
     fn aligned(self, aligns: Axes<Option<GenAlign>>) -> Self;
 
@@ -45,6 +49,10 @@ impl ContentExt for Content {
         self.styled(MetaElem::set_data(vec![Meta::Link(dest)]))
     }
 
+    fn tagged(self, tag: Tag) -> Self {
+        self.styled(MetaElem::set_data(vec![Meta::Tag(tag)]))
+    }
+
     fn aligned(self, aligns: Axes<Option<GenAlign>>) -> Self {
         self.styled(AlignElem::set_alignment(aligns))
     }
@@ -150,7 +150,7 @@ impl Show for RawElem {
                 vec![],
                 &highlighter,
                 &mut |node, style| {
-                    seq.push(styled(&text[node.range()], foreground, style));
+                    seq.push(styled(&text[node.range()], &foreground, style));
                 },
             );
 
@@ -168,7 +168,7 @@ impl Show for RawElem {
                 for (style, piece) in
                     highlighter.highlight_line(line, &SYNTAXES).into_iter().flatten()
                 {
-                    seq.push(styled(piece, foreground, style));
+                    seq.push(styled(piece, &foreground, style));
                 }
             }
 
@@ -224,11 +224,11 @@ fn highlight_themed<F>(
 }
 
 /// Style a piece of text with a syntect style.
-fn styled(piece: &str, foreground: Paint, style: synt::Style) -> Content {
+fn styled(piece: &str, foreground: &Paint, style: synt::Style) -> Content {
     let mut body = TextElem::packed(piece);
 
     let paint = to_typst(style.foreground).into();
-    if paint != foreground {
+    if paint != *foreground {
         body = body.styled(TextElem::set_fill(paint));
     }
 
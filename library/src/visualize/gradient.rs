@@ -0,0 +1,152 @@
+//! Color gradients.
+
+use typst::eval::{Module, Scope};
+
+use crate::prelude::*;
+
+/// A module with gradient constructors.
+pub fn module() -> Module {
+    let mut scope = Scope::new();
+    scope.define("linear", linear);
+    scope.define("radial", radial);
+    scope.define("conic", conic);
+    Module::new("gradient").with_scope(scope)
+}
+
+/// Create a linear gradient.
+///
+/// The colors are interpolated along a straight line through the center of
+/// the bounding box. A gradient can be used wherever a color is accepted as
+/// paint, e.g. for the `fill` of shapes and text or for strokes.
+///
+/// ## Example
+/// ```example
+/// #rect(width: 100%, fill: gradient.linear(red, blue))
+/// #rect(width: 100%, fill: gradient.linear(
+///   (red, 0%), (yellow, 20%), (blue, 100%),
+///   angle: 45deg,
+/// ))
+/// ```
+///
+/// Display: Linear Gradient
+/// Category: visualize
+/// Returns: gradient
+#[func]
+pub fn linear(
+    /// The color stops of the gradient.
+    ///
+    /// Each stop is either a color or an array of a color and an offset
+    /// between `{0%}` and `{100%}`. Either all or none of the stops must have
+    /// an offset. Stops without offsets are distributed evenly.
+    #[variadic]
+    stops: Vec<GradientStop>,
+    /// The direction of the gradient. An angle of `{0deg}` goes from left to
+    /// right and the angle increases clockwise.
+    #[named]
+    #[default(Angle::zero())]
+    angle: Angle,
+    /// The color space in which to interpolate between the stops.
+    #[named]
+    #[default(ColorSpace::Oklab)]
+    space: ColorSpace,
+    /// The bounding box the gradient is relative to.
+    ///
+    /// With `{"self"}`, the gradient spans the painted shape or text itself.
+    /// With `{"parent"}`, it spans the enclosing container, so that several
+    /// shapes or text runs share one continuous gradient. Defaults to
+    /// `{"self"}` for shapes and `{"parent"}` for text.
+    #[named]
+    #[default]
+    relative: Smart<RelativeTo>,
+) -> Value {
+    Gradient::linear(stops, angle, space, relative).at(args.span)?.into()
+}
+
+/// Create a radial gradient.
+///
+/// The colors are interpolated outwards from a center point. The center and
+/// radius are relative to the bounding box, so that the gradient becomes
+/// elliptical on non-square shapes.
+///
+/// ## Example
+/// ```example
+/// #circle(fill: gradient.radial(white, eastern))
+/// #square(fill: gradient.radial(
+///   yellow, red,
+///   center: (30%, 30%),
+///   radius: 70%,
+/// ))
+/// ```
+///
+/// Display: Radial Gradient
+/// Category: visualize
+/// Returns: gradient
+#[func]
+pub fn radial(
+    /// The color stops of the gradient. See the [gradient type]($type/gradient)
+    /// for details.
+    #[variadic]
+    stops: Vec<GradientStop>,
+    /// The center of the gradient, relative to the bounding box.
+    #[named]
+    #[default(Axes::splat(Ratio::new(0.5)))]
+    center: Axes<Ratio>,
+    /// The radius at which the last stop is reached, relative to the bounding
+    /// box.
+    #[named]
+    #[default(Ratio::new(0.5))]
+    radius: Ratio,
+    /// The color space in which to interpolate between the stops.
+    #[named]
+    #[default(ColorSpace::Oklab)]
+    space: ColorSpace,
+    /// The bounding box the gradient is relative to.
+    #[named]
+    #[default]
+    relative: Smart<RelativeTo>,
+) -> Value {
+    Gradient::radial(stops, center, radius, space, relative)
+        .at(args.span)?
+        .into()
+}
+
+/// Create a conic gradient.
+///
+/// The colors are interpolated around a center point, starting at the given
+/// angle and going around clockwise.
+///
+/// ## Example
+/// ```example
+/// #circle(fill: gradient.conic(red, yellow, green, blue, red))
+/// ```
+///
+/// Display: Conic Gradient
+/// Category: visualize
+/// Returns: gradient
+#[func]
+pub fn conic(
+    /// The color stops of the gradient. See the [gradient type]($type/gradient)
+    /// for details.
+    #[variadic]
+    stops: Vec<GradientStop>,
+    /// The angle at which the first stop is placed.
+    #[named]
+    #[default(Angle::zero())]
+    angle: Angle,
+    /// The center of the gradient, relative to the bounding box.
+    #[named]
+    #[default(Axes::splat(Ratio::new(0.5)))]
+    center: Axes<Ratio>,
+    /// The color space in which to interpolate between the stops.
+    #[named]
+    #[default(ColorSpace::Oklab)]
+    space: ColorSpace,
+    /// The bounding box the gradient is relative to.
+    #[named]
+    #[default]
+    relative: Smart<RelativeTo>,
+) -> Value {
+    Gradient::conic(stops, angle, center, space, relative)
+        .at(args.span)?
+        .into()
+}
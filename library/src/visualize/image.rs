@@ -1,6 +1,7 @@
 use std::ffi::OsStr;
 use std::path::Path;
 
+use typst::eval::Bytes;
 use typst::image::{Image, ImageFormat, RasterFormat, VectorFormat};
 
 use crate::prelude::*;
@@ -23,6 +24,10 @@ use crate::prelude::*;
 /// Display: Image
 /// Category: visualize
 #[element(Layout)]
+#[scope(
+    scope.define("decode", decode);
+    scope
+)]
 pub struct ImageElem {
     /// Path to an image file.
     #[required]
@@ -30,11 +35,20 @@ pub struct ImageElem {
         let Spanned { v: path, span } =
             args.expect::<Spanned<EcoString>>("path to image file")?;
         let path: EcoString = vm.locate(&path).at(span)?.to_string_lossy().into();
-        let _ = load(vm.world(), &path).at(span)?;
+        let data = vm.world().file(Path::new(path.as_str())).at(span)?;
         path
     )]
     pub path: EcoString,
 
+    /// The raw file data.
+    #[internal]
+    #[required]
+    #[parse(Bytes::from(data))]
+    pub data: Bytes,
+
+    /// The image's format. Detected automatically by default.
+    pub format: Smart<ImageFormat>,
+
     /// The width of the image.
     pub width: Smart<Rel<Length>>,
 
@@ -44,6 +58,77 @@ pub struct ImageElem {
     /// How the image should adjust itself to a given area.
     #[default(ImageFit::Cover)]
     pub fit: ImageFit,
+
+    /// A text describing the image for readers who can't see it, for example
+    /// because they use a screen reader.
+    pub alt: Option<EcoString>,
+}
+
+/// Decode a raster or vector graphic from bytes or a string.
+///
+/// ## Example
+/// ```example
+/// #let original = read("tetrahedron.svg")
+/// #let changed = original.replace("#a3a7b7", "#3c8dbc")
+///
+/// #image.decode(original, width: 30%)
+/// #image.decode(changed, width: 30%)
+/// ```
+///
+/// Display: Decode Image
+/// Category: visualize
+/// Returns: content
+#[func]
+pub fn decode(
+    /// The data to decode as an image. Can be a string for SVGs.
+    data: ImageData,
+    /// The image's format. Detected automatically by default.
+    #[named]
+    #[default]
+    format: Option<Smart<ImageFormat>>,
+    /// The width of the image.
+    #[named]
+    #[default]
+    width: Option<Smart<Rel<Length>>>,
+    /// The height of the image.
+    #[named]
+    #[default]
+    height: Option<Smart<Rel<Length>>>,
+    /// How the image should adjust itself to a given area.
+    #[named]
+    #[default]
+    fit: Option<ImageFit>,
+    /// A text describing the image.
+    #[named]
+    #[default]
+    alt: Option<Option<EcoString>>,
+) -> Value {
+    let mut elem = ImageElem::new(EcoString::new(), data.0);
+    if let Some(format) = format {
+        elem.push_format(format);
+    }
+    if let Some(width) = width {
+        elem.push_width(width);
+    }
+    if let Some(height) = height {
+        elem.push_height(height);
+    }
+    if let Some(fit) = fit {
+        elem.push_fit(fit);
+    }
+    if let Some(alt) = alt {
+        elem.push_alt(alt);
+    }
+    elem.pack().into()
+}
+
+/// The data of an image to decode.
+struct ImageData(Bytes);
+
+cast_from_value! {
+    ImageData,
+    v: Str => Self(v.as_bytes().into()),
+    v: Bytes => Self(v),
 }
 
 impl Layout for ImageElem {
@@ -53,7 +138,8 @@ impl Layout for ImageElem {
         styles: StyleChain,
         regions: Regions,
     ) -> SourceResult<Fragment> {
-        let image = load(vt.world, &self.path()).unwrap();
+        let image =
+            load(self.data(), self.format(styles), &self.path()).at(self.span())?;
         let sizing = Axes::new(self.width(styles), self.height(styles));
         let region = sizing
             .zip(regions.base())
@@ -105,6 +191,15 @@ impl Layout for ImageElem {
             frame.clip();
         }
 
+        // Mark the image as a figure in the document's structure, unless it is
+        // already the body of one.
+        let role = Role::Figure(self.alt(styles));
+        let tag = MetaElem::set_data(vec![Meta::Tag(Tag::new(vt, role, self))]);
+        let styles = match MetaElem::role_in(styles) {
+            Some(Role::Figure(_)) => styles,
+            _ => styles.chain_one(&tag),
+        };
+
         // Apply metadata.
         frame.meta(styles, false);
 
@@ -124,18 +219,44 @@ pub enum ImageFit {
     Stretch,
 }
 
-/// Load an image from a path.
+/// Load an image from its data.
+///
+/// If no format is given, it is determined from the path's extension or, if
+/// that fails, from the data itself.
 #[comemo::memoize]
-fn load(world: Tracked<dyn World>, full: &str) -> StrResult<Image> {
-    let full = Path::new(full);
-    let buffer = world.file(full)?;
-    let ext = full.extension().and_then(OsStr::to_str).unwrap_or_default();
-    let format = match ext.to_lowercase().as_str() {
+fn load(data: Bytes, format: Smart<ImageFormat>, path: &str) -> StrResult<Image> {
+    let format = match format {
+        Smart::Custom(format) => format,
+        Smart::Auto => determine_format(Path::new(path), &data)?,
+    };
+    Image::new(data.into(), format)
+}
+
+/// Try to determine the image format from a path or, failing that, the data.
+fn determine_format(path: &Path, data: &[u8]) -> StrResult<ImageFormat> {
+    let ext = path.extension().and_then(OsStr::to_str).unwrap_or_default();
+    Ok(match ext.to_lowercase().as_str() {
         "png" => ImageFormat::Raster(RasterFormat::Png),
         "jpg" | "jpeg" => ImageFormat::Raster(RasterFormat::Jpg),
         "gif" => ImageFormat::Raster(RasterFormat::Gif),
         "svg" | "svgz" => ImageFormat::Vector(VectorFormat::Svg),
+        _ if data.starts_with(b"\x89PNG\r\n\x1a\n") => {
+            ImageFormat::Raster(RasterFormat::Png)
+        }
+        _ if data.starts_with(&[0xFF, 0xD8, 0xFF]) => {
+            ImageFormat::Raster(RasterFormat::Jpg)
+        }
+        _ if data.starts_with(b"GIF87a") || data.starts_with(b"GIF89a") => {
+            ImageFormat::Raster(RasterFormat::Gif)
+        }
+        _ if is_svg(data) => ImageFormat::Vector(VectorFormat::Svg),
         _ => return Err("unknown image format".into()),
-    };
-    Image::new(buffer, format)
+    })
+}
+
+/// Whether the data looks like an uncompressed SVG document.
+fn is_svg(data: &[u8]) -> bool {
+    let Ok(text) = std::str::from_utf8(data) else { return false };
+    let text = text.trim_start();
+    (text.starts_with("<?xml") || text.starts_with("<svg")) && text.contains("<svg")
 }
@@ -1,11 +1,14 @@
 //! Drawing and visualization.
 
+pub mod gradient;
 mod image;
 mod line;
+mod pattern;
 mod polygon;
 mod shape;
 
 pub use self::image::*;
 pub use self::line::*;
+pub use self::pattern::*;
 pub use self::polygon::*;
 pub use self::shape::*;
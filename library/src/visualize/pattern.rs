@@ -0,0 +1,75 @@
+use crate::prelude::*;
+
+/// Create a repeating pattern fill from content.
+///
+/// The content is laid out into a tile, which is then repeated to fill the
+/// painted area. Like a [gradient]($type/gradient), a pattern can be used
+/// wherever a color is accepted as paint, e.g. for the `fill` of shapes,
+/// table cells and text or for strokes.
+///
+/// ## Example
+/// ```example
+/// #let hatched = pattern(size: (8pt, 8pt))[
+///   #place(line(start: (0%, 100%), end: (100%, 0%), stroke: 0.5pt))
+/// ]
+///
+/// #rect(width: 100%, height: 40pt, fill: hatched)
+/// #table(
+///   columns: 2,
+///   fill: (col, row) => if row == 0 { hatched },
+///   [Name], [Value],
+///   [A], [1],
+/// )
+/// ```
+///
+/// Display: Pattern
+/// Category: visualize
+/// Returns: pattern
+#[func]
+pub fn pattern(
+    /// The size of a single tile. If set to `{auto}`, the tile takes on the
+    /// size of its laid out content.
+    #[named]
+    #[default]
+    size: Smart<Axes<Length>>,
+    /// The gap between adjacent tiles. A negative gap makes the tiles
+    /// overlap, but it must not exceed the size of the tile.
+    #[named]
+    #[default(Axes::splat(Length::zero()))]
+    spacing: Axes<Length>,
+    /// The bounding box the pattern is relative to.
+    ///
+    /// With `{"self"}`, the tiles start at the top-left corner of the painted
+    /// shape or text itself. With `{"parent"}`, they start at the corner of
+    /// the enclosing container, so that adjacent shapes line up seamlessly.
+    /// Defaults to `{"self"}` for shapes and `{"parent"}` for text.
+    #[named]
+    #[default]
+    relative: Smart<RelativeTo>,
+    /// The content of a single tile.
+    body: Content,
+) -> Value {
+    let world = vm.world();
+    let styles = StyleChain::new(&world.library().styles);
+
+    let pod = match size {
+        Smart::Auto => Regions::one(Axes::splat(Abs::inf()), Axes::splat(false)),
+        Smart::Custom(size) => Regions::one(size.resolve(styles), Axes::splat(true)),
+    };
+
+    let frame = body.layout(&mut vm.vt, styles, pod)?.into_frame();
+    let size = frame.size();
+    let valid = |length: Abs| length.is_finite() && length > Abs::zero();
+    if !valid(size.x) || !valid(size.y) {
+        bail!(args.span, "pattern tile size must be non-zero");
+    }
+
+    // Negative spacing may overlap tiles, but the distance from one tile to
+    // the next must stay positive.
+    let spacing = spacing.resolve(styles);
+    if !valid(size.x + spacing.x) || !valid(size.y + spacing.y) {
+        bail!(args.span, "spacing must not make the tile size negative");
+    }
+
+    Pattern::new(frame, spacing, relative).into()
+}
@@ -15,6 +15,7 @@ struct Elem {
     ident: Ident,
     capable: Vec<Ident>,
     fields: Vec<Field>,
+    scope: Option<FieldParser>,
 }
 
 struct Field {
@@ -50,9 +51,9 @@ impl Field {
     }
 }
 
-struct FieldParser {
-    prefix: Vec<syn::Stmt>,
-    expr: syn::Stmt,
+pub(super) struct FieldParser {
+    pub(super) prefix: Vec<syn::Stmt>,
+    pub(super) expr: syn::Stmt,
 }
 
 impl Parse for FieldParser {
@@ -137,7 +138,8 @@ fn prepare(stream: TokenStream, body: &syn::ItemStruct) -> Result<Elem> {
         .into_iter()
         .collect();
 
-    let docs = documentation(&body.attrs);
+    let mut attrs = body.attrs.clone();
+    let docs = documentation(&attrs);
     let mut lines = docs.split('\n').collect();
     let category = meta_line(&mut lines, "Category")?.into();
     let display = meta_line(&mut lines, "Display")?.into();
@@ -152,9 +154,10 @@ fn prepare(stream: TokenStream, body: &syn::ItemStruct) -> Result<Elem> {
         ident: body.ident.clone(),
         capable,
         fields,
+        scope: parse_attr(&mut attrs, "scope")?.flatten(),
     };
 
-    validate_attrs(&body.attrs)?;
+    validate_attrs(&attrs)?;
     Ok(element)
 }
 
@@ -346,6 +349,14 @@ fn create_set_field_method(field: &Field) -> TokenStream {
 fn create_pack_impl(element: &Elem) -> TokenStream {
     let Elem { ident, name, display, category, docs, .. } = element;
     let vtable_func = create_vtable_func(element);
+    let scope = match &element.scope {
+        Some(FieldParser { prefix, expr }) => quote! {{
+            let mut scope = ::typst::eval::Scope::deduplicating();
+            #(#prefix)*
+            #expr
+        }},
+        None => quote! { ::typst::eval::Scope::new() },
+    };
     let infos = element
         .fields
         .iter()
@@ -377,6 +388,7 @@ fn create_pack_impl(element: &Elem) -> TokenStream {
                         params: ::std::vec![#(#infos),*],
                         returns: ::std::vec!["content"],
                         category: #category,
+                        scope: #scope,
                     }),
                 };
                 (&NATIVE).into()
@@ -507,6 +519,7 @@ fn create_set_impl(element: &Elem) -> TokenStream {
     quote! {
         impl ::typst::model::Set for #ident {
             fn set(
+                vm: &mut ::typst::eval::Vm,
                 args: &mut ::typst::eval::Args,
             ) -> ::typst::diag::SourceResult<::typst::model::Styles> {
                 let mut styles = ::typst::model::Styles::new();
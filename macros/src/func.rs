@@ -1,5 +1,6 @@
 use quote::ToTokens;
 
+use super::element::FieldParser;
 use super::*;
 
 /// Expand the `#[func]` macro.
@@ -18,6 +19,7 @@ struct Func {
     params: Vec<Param>,
     returns: Vec<String>,
     body: syn::Block,
+    scope: Option<FieldParser>,
 }
 
 struct Param {
@@ -72,7 +74,8 @@ fn prepare(item: &syn::ItemFn) -> Result<Func> {
         validate_attrs(&attrs)?;
     }
 
-    let docs = documentation(&item.attrs);
+    let mut attrs = item.attrs.clone();
+    let docs = documentation(&attrs);
     let mut lines = docs.split('\n').collect();
     let returns = meta_line(&mut lines, "Returns")?
         .split(" or ")
@@ -92,9 +95,10 @@ fn prepare(item: &syn::ItemFn) -> Result<Func> {
         params,
         returns,
         body: (*item.block).clone(),
+        scope: parse_attr(&mut attrs, "scope")?.flatten(),
     };
 
-    validate_attrs(&item.attrs)?;
+    validate_attrs(&attrs)?;
     Ok(func)
 }
 
@@ -109,8 +113,16 @@ fn create(func: &Func) -> TokenStream {
         params,
         returns,
         body,
-        ..
+        scope,
     } = func;
+    let scope = match scope {
+        Some(FieldParser { prefix, expr }) => quote! {{
+            let mut scope = ::typst::eval::Scope::deduplicating();
+            #(#prefix)*
+            #expr
+        }},
+        None => quote! { ::typst::eval::Scope::new() },
+    };
     let handlers = params.iter().filter(|param| !param.external).map(create_param_parser);
     let params = params.iter().map(create_param_info);
     quote! {
@@ -129,6 +141,7 @@ fn create(func: &Func) -> TokenStream {
                     params: ::std::vec![#(#params),*],
                     returns: ::std::vec![#(#returns),*],
                     category: #category,
+                    scope: #scope,
                 }),
             };
             &FUNC
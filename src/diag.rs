@@ -10,10 +10,11 @@ use std::string::FromUtf8Error;
 use comemo::Tracked;
 use ecow::EcoString;
 
+use crate::package::PackageSpec;
 use crate::syntax::{ErrorPos, Span, Spanned};
 use crate::World;
 
-/// Early-return with a [`SourceError`].
+/// Early-return with a [`SourceDiagnostic`].
 #[macro_export]
 #[doc(hidden)]
 macro_rules! __bail {
@@ -29,12 +30,12 @@ macro_rules! __bail {
 #[doc(inline)]
 pub use crate::__bail as bail;
 
-/// Construct a [`SourceError`].
+/// Construct an error [`SourceDiagnostic`].
 #[macro_export]
 #[doc(hidden)]
 macro_rules! __error {
     ($span:expr, $message:expr $(,)?) => {
-        $crate::diag::SourceError::new($span, $message)
+        $crate::diag::SourceDiagnostic::error($span, $message)
     };
 
     ($span:expr, $fmt:expr, $($arg:expr),+ $(,)?) => {
@@ -44,48 +45,99 @@ macro_rules! __error {
 
 #[doc(inline)]
 pub use crate::__error as error;
+
+/// Construct a warning [`SourceDiagnostic`].
+#[macro_export]
+#[doc(hidden)]
+macro_rules! __warning {
+    ($span:expr, $message:expr $(,)?) => {
+        $crate::diag::SourceDiagnostic::warning($span, $message)
+    };
+
+    ($span:expr, $fmt:expr, $($arg:expr),+ $(,)?) => {
+        $crate::diag::warning!($span, $crate::diag::eco_format!($fmt, $($arg),+))
+    };
+}
+
+#[doc(inline)]
+pub use crate::__warning as warning;
 #[doc(hidden)]
 pub use ecow::eco_format;
 
 /// A result that can carry multiple source errors.
-pub type SourceResult<T> = Result<T, Box<Vec<SourceError>>>;
+pub type SourceResult<T> = Result<T, Box<Vec<SourceDiagnostic>>>;
 
-/// An error in a source file.
+/// An error or warning in a source file.
 ///
 /// The contained spans will only be detached if any of the input source files
 /// were detached.
 #[derive(Debug, Clone, Eq, PartialEq, Hash)]
-pub struct SourceError {
-    /// The span of the erroneous node in the source code.
+pub struct SourceDiagnostic {
+    /// Whether the diagnostic is an error or a warning.
+    pub severity: Severity,
+    /// The span of the relevant node in the source code.
     pub span: Span,
-    /// The position in the node where the error should be annotated.
+    /// The position in the node where the diagnostic should be annotated.
     pub pos: ErrorPos,
     /// A diagnostic message describing the problem.
     pub message: EcoString,
-    /// The trace of function calls leading to the error.
+    /// The trace of function calls leading to the problem.
     pub trace: Vec<Spanned<Tracepoint>>,
+    /// Additional hints to the user, indicating how this problem could be
+    /// avoided or worked around.
+    pub hints: Vec<EcoString>,
+}
+
+/// The severity of a [`SourceDiagnostic`].
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
+pub enum Severity {
+    /// A fatal error.
+    Error,
+    /// A non-fatal warning.
+    Warning,
 }
 
-impl SourceError {
+impl SourceDiagnostic {
     /// Create a new, bare error.
     #[track_caller]
-    pub fn new(span: Span, message: impl Into<EcoString>) -> Self {
+    pub fn error(span: Span, message: impl Into<EcoString>) -> Self {
         Self {
+            severity: Severity::Error,
             span,
             pos: ErrorPos::Full,
             trace: vec![],
             message: message.into(),
+            hints: vec![],
         }
     }
 
-    /// Adjust the position in the node where the error should be annotated.
+    /// Create a new, bare warning.
+    pub fn warning(span: Span, message: impl Into<EcoString>) -> Self {
+        Self {
+            severity: Severity::Warning,
+            span,
+            pos: ErrorPos::Full,
+            trace: vec![],
+            message: message.into(),
+            hints: vec![],
+        }
+    }
+
+    /// Adjust the position in the node where the diagnostic should be
+    /// annotated.
     pub fn with_pos(mut self, pos: ErrorPos) -> Self {
         self.pos = pos;
         self
     }
 
+    /// Add a user-facing hint to the diagnostic.
+    pub fn with_hint(mut self, hint: impl Into<EcoString>) -> Self {
+        self.hints.push(hint.into());
+        self
+    }
+
     /// The range in the source file identified by
-    /// [`self.span.source()`](Span::source) where the error should be
+    /// [`self.span.source()`](Span::source) where the diagnostic should be
     /// annotated.
     pub fn range(&self, world: &dyn World) -> Range<usize> {
         let full = world.source(self.span.source()).range(self.span);
@@ -97,7 +149,7 @@ impl SourceError {
     }
 }
 
-/// A part of an error's [trace](SourceError::trace).
+/// A part of a diagnostic's [trace](SourceDiagnostic::trace).
 #[derive(Debug, Clone, Eq, PartialEq, Ord, PartialOrd, Hash)]
 pub enum Tracepoint {
     /// A function call.
@@ -193,6 +245,8 @@ pub enum FileError {
     NotSource,
     /// The file was not valid UTF-8, but should have been.
     InvalidUtf8,
+    /// A package with this specification could not be found.
+    PackageNotFound(PackageSpec),
     /// Another error.
     Other,
 }
@@ -225,6 +279,9 @@ impl Display for FileError {
             Self::IsDirectory => f.pad("failed to load file (is a directory)"),
             Self::NotSource => f.pad("not a typst source file"),
             Self::InvalidUtf8 => f.pad("file is not valid utf-8"),
+            Self::PackageNotFound(spec) => {
+                write!(f, "package not found (searched for {spec})")
+            }
             Self::Other => f.pad("failed to load file"),
         }
     }
@@ -1,6 +1,7 @@
 //! Finished documents.
 
 use std::fmt::{self, Debug, Formatter, Write};
+use std::hash::Hash;
 use std::num::NonZeroUsize;
 use std::str::FromStr;
 use std::sync::Arc;
@@ -14,8 +15,9 @@ use crate::geom::{
     Numeric, Paint, Point, Rel, RgbaColor, Shape, Sides, Size, Stroke, Transform,
 };
 use crate::image::Image;
-use crate::model::{Content, Location, MetaElem, StyleChain};
+use crate::model::{Content, Location, MetaElem, StyleChain, Vt};
 use crate::syntax::Span;
+use crate::util::hash128;
 
 /// A finished document with metadata and page frames.
 #[derive(Debug, Default, Clone, Hash)]
@@ -26,6 +28,69 @@ pub struct Document {
     pub title: Option<EcoString>,
     /// The document's author.
     pub author: Vec<EcoString>,
+    /// The labels of the pages, as derived from the active page numbering.
+    ///
+    /// Has one entry per page, which is `None` if the page is not numbered.
+    pub page_labels: Vec<Option<PageLabel>>,
+}
+
+/// A logical page label, as displayed by document viewers instead of the
+/// physical page index.
+#[derive(Debug, Clone, Eq, PartialEq, Hash)]
+pub struct PageLabel {
+    /// Text displayed before the number, for example `Appendix-`.
+    pub prefix: Option<EcoString>,
+    /// How the number is displayed. If this is `None`, the label consists only
+    /// of the prefix.
+    pub style: Option<PageLabelStyle>,
+    /// The value of the number.
+    pub number: Option<NonZeroUsize>,
+}
+
+impl PageLabel {
+    /// A label that displays the given text verbatim.
+    pub fn text(text: impl Into<EcoString>) -> Self {
+        Self {
+            prefix: Some(text.into()),
+            style: None,
+            number: None,
+        }
+    }
+
+    /// A label that displays the given number in arabic numerals.
+    pub fn arabic(number: NonZeroUsize) -> Self {
+        Self {
+            prefix: None,
+            style: Some(PageLabelStyle::Arabic),
+            number: Some(number),
+        }
+    }
+
+    /// Whether this label continues the numbering of the previous one.
+    pub fn continues(&self, prev: &Self) -> bool {
+        self.style.is_some()
+            && self.prefix == prev.prefix
+            && self.style == prev.style
+            && matches!(
+                (prev.number, self.number),
+                (Some(prev), Some(next)) if prev.get() + 1 == next.get()
+            )
+    }
+}
+
+/// How the number in a page label is displayed.
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
+pub enum PageLabelStyle {
+    /// Arabic numerals: 1, 2, 3.
+    Arabic,
+    /// Lowercase roman numerals: i, ii, iii.
+    LowerRoman,
+    /// Uppercase roman numerals: I, II, III.
+    UpperRoman,
+    /// Lowercase letters: a, b, c.
+    LowerAlpha,
+    /// Uppercase letters: A, B, C.
+    UpperAlpha,
 }
 
 /// A finished layout with items at fixed positions.
@@ -192,8 +257,19 @@ impl Frame {
     }
 
     /// Whether the given frame should be inlined.
+    ///
+    /// Frames with structure tags are never inlined because their tags apply
+    /// to all of their items.
     fn should_inline(&self, frame: &Frame) -> bool {
-        self.items.is_empty() || frame.items.len() <= 5
+        (self.items.is_empty() || frame.items.len() <= 5) && !frame.is_tagged()
+    }
+
+    /// Whether the frame is marked as part of an element in the document's
+    /// logical structure.
+    fn is_tagged(&self) -> bool {
+        self.items
+            .iter()
+            .any(|(_, item)| matches!(item, FrameItem::Meta(Meta::Tag(_), _)))
     }
 
     /// Inline a frame at the given layer.
@@ -283,6 +359,20 @@ impl Frame {
         }
     }
 
+    /// Attach only the structure tags from the style chain to the frame.
+    pub fn tag(&mut self, styles: StyleChain) {
+        if !self.is_empty() {
+            for meta in MetaElem::data_in(styles) {
+                if let Meta::Tag(tag) = meta {
+                    self.prepend(
+                        Point::zero(),
+                        FrameItem::Meta(Meta::Tag(tag), self.size),
+                    );
+                }
+            }
+        }
+    }
+
     /// Add a background fill.
     pub fn fill(&mut self, fill: Paint) {
         self.prepend(
@@ -359,6 +449,7 @@ impl Frame {
                 Geometry::Line(Point::with_x(self.size.x)).stroked(Stroke {
                     paint: Color::RED.into(),
                     thickness: Abs::pt(1.0),
+                    ..Stroke::default()
                 }),
                 Span::detached(),
             ),
@@ -386,6 +477,7 @@ impl Frame {
                 Geometry::Line(Point::with_x(self.size.x)).stroked(Stroke {
                     paint: Color::GREEN.into(),
                     thickness: Abs::pt(1.0),
+                    ..Stroke::default()
                 }),
                 Span::detached(),
             ),
@@ -603,6 +695,9 @@ pub enum Meta {
     /// An identifiable element that produces something within the area this
     /// metadata is attached to.
     Elem(Content),
+    /// Marks the content in the area as part of an element in the document's
+    /// logical structure.
+    Tag(Tag),
     /// Indicates that content should be hidden. This variant doesn't appear
     /// in the final frames as it is removed alongside the content that should
     /// be hidden.
@@ -613,6 +708,69 @@ cast_from_value! {
     Meta: "meta",
 }
 
+/// Identifies an element in the document's logical structure, which exporters
+/// use to make documents accessible.
+#[derive(Debug, Clone, Eq, PartialEq, Hash)]
+pub struct Tag {
+    /// What kind of structure element this is.
+    pub role: Role,
+    /// Identifies the structure element. Content that is marked with tags of
+    /// the same location belongs to the same element, even if it was split up
+    /// across lines or regions.
+    pub location: Location,
+}
+
+impl Tag {
+    /// Create a tag for a new structure element with the given role.
+    ///
+    /// The value keeps the element's location stable across layout
+    /// iterations. Elements with equal values are told apart by the order in
+    /// which they are tagged, so that identical paragraphs still become
+    /// separate structure elements.
+    pub fn new<T: Hash + ?Sized>(vt: &mut Vt, role: Role, value: &T) -> Self {
+        let location = vt.provider.locate(hash128(&(&role, value)));
+        Self { role, location }
+    }
+}
+
+/// The role of an element in the document's logical structure.
+#[derive(Debug, Clone, Eq, PartialEq, Hash)]
+pub enum Role {
+    /// A heading of the given level.
+    Heading(NonZeroUsize),
+    /// A paragraph.
+    Paragraph,
+    /// A bullet or numbered list.
+    List,
+    /// An item in a list, containing a label and a body.
+    ListItem,
+    /// The bullet or number of a list item.
+    ListLabel,
+    /// The content of a list item.
+    ListBody,
+    /// A table.
+    Table,
+    /// The header rows of a table.
+    TableHeader,
+    /// The footer rows of a table.
+    TableFooter,
+    /// A row in a table.
+    TableRow,
+    /// A cell in a table.
+    TableCell,
+    /// A cell in the header of a table.
+    TableHeaderCell,
+    /// An illustration with an optional alternative description.
+    Figure(Option<EcoString>),
+    /// The caption of a figure.
+    Caption,
+    /// A link.
+    Link,
+    /// Content that isn't part of the logical structure, like page headers
+    /// and footers.
+    Artifact,
+}
+
 /// A link destination.
 #[derive(Debug, Clone, Eq, PartialEq, Hash)]
 pub enum Destination {
@@ -0,0 +1,152 @@
+use std::fmt::{self, Debug, Formatter};
+use std::ops::{Add, AddAssign, Deref};
+
+use ecow::{eco_format, EcoString};
+
+use super::{Array, Value};
+use crate::diag::StrResult;
+use crate::util::Buffer;
+
+/// A sequence of bytes.
+///
+/// This is conceptually similar to an array of integers between `{0}` and
+/// `{255}`, but represented much more efficiently.
+#[derive(Clone, Hash, Eq, PartialEq)]
+pub struct Bytes(Buffer);
+
+impl Bytes {
+    /// Create a buffer from a static byte slice.
+    pub fn from_static(slice: &'static [u8]) -> Self {
+        Self(Buffer::from_static(slice))
+    }
+
+    /// The number of bytes.
+    pub fn len(&self) -> i64 {
+        self.0.len() as i64
+    }
+
+    /// Whether there are no bytes.
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    /// The underlying buffer.
+    pub fn buffer(&self) -> &Buffer {
+        &self.0
+    }
+
+    /// Return a view into the bytes.
+    pub fn as_slice(&self) -> &[u8] {
+        &self.0
+    }
+
+    /// Extract the byte at the given index.
+    pub fn at(&self, index: i64) -> StrResult<i64> {
+        self.locate(index)
+            .and_then(|i| self.0.get(i))
+            .map(|&b| b as i64)
+            .ok_or_else(|| out_of_bounds(index, self.len()))
+    }
+
+    /// Extract a contiguous subregion of the bytes.
+    pub fn slice(&self, start: i64, end: Option<i64>) -> StrResult<Self> {
+        let len = self.len();
+        let start = self
+            .locate(start)
+            .filter(|&start| start <= self.0.len())
+            .ok_or_else(|| out_of_bounds(start, len))?;
+
+        let end = end.unwrap_or(self.len());
+        let end = self
+            .locate(end)
+            .filter(|&end| end <= self.0.len())
+            .ok_or_else(|| out_of_bounds(end, len))?
+            .max(start);
+
+        Ok(self.0[start..end].into())
+    }
+
+    /// Convert the bytes into an array of integers.
+    pub fn to_array(&self) -> Array {
+        self.0.iter().map(|&b| Value::Int(b as i64)).collect()
+    }
+
+    /// Resolve an index, if it is within bounds.
+    fn locate(&self, index: i64) -> Option<usize> {
+        usize::try_from(if index >= 0 { index } else { self.len().checked_add(index)? })
+            .ok()
+    }
+}
+
+impl From<&[u8]> for Bytes {
+    fn from(slice: &[u8]) -> Self {
+        Self(slice.into())
+    }
+}
+
+impl From<Vec<u8>> for Bytes {
+    fn from(vec: Vec<u8>) -> Self {
+        Self(vec.into())
+    }
+}
+
+impl From<Buffer> for Bytes {
+    fn from(buffer: Buffer) -> Self {
+        Self(buffer)
+    }
+}
+
+impl From<Bytes> for Buffer {
+    fn from(bytes: Bytes) -> Self {
+        bytes.0
+    }
+}
+
+impl Deref for Bytes {
+    type Target = [u8];
+
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+impl AsRef<[u8]> for Bytes {
+    fn as_ref(&self) -> &[u8] {
+        self
+    }
+}
+
+impl Debug for Bytes {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        write!(f, "bytes({})", self.len())
+    }
+}
+
+impl Add for Bytes {
+    type Output = Self;
+
+    fn add(mut self, rhs: Self) -> Self::Output {
+        self += rhs;
+        self
+    }
+}
+
+impl AddAssign for Bytes {
+    fn add_assign(&mut self, rhs: Self) {
+        if rhs.is_empty() {
+            // Nothing to do
+        } else if self.is_empty() {
+            *self = rhs;
+        } else {
+            let mut vec = self.0.to_vec();
+            vec.extend_from_slice(&rhs);
+            *self = vec.into();
+        }
+    }
+}
+
+/// The out of bounds access error message.
+#[cold]
+fn out_of_bounds(index: i64, len: i64) -> EcoString {
+    eco_format!("byte index out of bounds (index: {}, len: {})", index, len)
+}
@@ -267,7 +267,12 @@ impl CastInfo {
                         *matching_type = true;
                     }
                 }
-                CastInfo::Type(ty) => parts.push((*ty).into()),
+                CastInfo::Type(ty) => {
+                    // The same type can be reachable through multiple options.
+                    if !parts.iter().any(|part| part == ty) {
+                        parts.push((*ty).into());
+                    }
+                }
                 CastInfo::Union(options) => {
                     for option in options {
                         accumulate(option, found, parts, matching_type);
@@ -0,0 +1,357 @@
+use std::cmp::Ordering;
+use std::fmt::{self, Debug, Formatter};
+
+use ecow::{eco_format, EcoString};
+use time::error::{Format, InvalidFormatDescription};
+use time::{format_description, PrimitiveDateTime};
+
+use super::cast_from_value;
+use crate::diag::StrResult;
+
+/// A date, a time, or a combination of both.
+#[derive(Copy, Clone, Eq, PartialEq, Hash)]
+pub enum Datetime {
+    /// Representation as a date.
+    Date(time::Date),
+    /// Representation as a time.
+    Time(time::Time),
+    /// Representation as a combination of date and time.
+    Datetime(time::PrimitiveDateTime),
+}
+
+impl Datetime {
+    /// Create a datetime from year, month, and day.
+    pub fn from_ymd(year: i32, month: u8, day: u8) -> Option<Self> {
+        let month = time::Month::try_from(month).ok()?;
+        time::Date::from_calendar_date(year, month, day).ok().map(Self::Date)
+    }
+
+    /// Create a datetime from hour, minute, and second.
+    pub fn from_hms(hour: u8, minute: u8, second: u8) -> Option<Self> {
+        time::Time::from_hms(hour, minute, second).ok().map(Self::Time)
+    }
+
+    /// Create a datetime from day and time.
+    pub fn from_ymd_hms(
+        year: i32,
+        month: u8,
+        day: u8,
+        hour: u8,
+        minute: u8,
+        second: u8,
+    ) -> Option<Self> {
+        let Some(Self::Date(date)) = Self::from_ymd(year, month, day) else {
+            return None;
+        };
+        let Some(Self::Time(time)) = Self::from_hms(hour, minute, second) else {
+            return None;
+        };
+        Some(Self::Datetime(PrimitiveDateTime::new(date, time)))
+    }
+
+    /// Display the datetime in a given format. If no format is given, a
+    /// default format based on the kind of the datetime is used.
+    pub fn display(&self, pattern: Option<EcoString>) -> StrResult<EcoString> {
+        let pattern = pattern.as_deref().unwrap_or(match self {
+            Self::Date(_) => "[year]-[month]-[day]",
+            Self::Time(_) => "[hour]:[minute]:[second]",
+            Self::Datetime(_) => "[year]-[month]-[day] [hour]:[minute]:[second]",
+        });
+
+        let format = format_description::parse_borrowed::<1>(pattern)
+            .map_err(format_parse_error)?;
+        if let Some(name) = self.missing_component(pattern) {
+            return Err(eco_format!(
+                "cannot display component `{name}` of this datetime"
+            ));
+        }
+
+        let formatted = match self {
+            Self::Date(date) => date.format(&format),
+            Self::Time(time) => time.format(&format),
+            Self::Datetime(datetime) => datetime.format(&format),
+        };
+
+        formatted.map(EcoString::from).map_err(format_time_error)
+    }
+
+    /// The first component in the pattern that this datetime lacks, if any.
+    fn missing_component<'a>(&self, pattern: &'a str) -> Option<&'a str> {
+        let mut rest = pattern;
+        while let Some(i) = rest.find('[') {
+            rest = &rest[i + 1..];
+
+            // Two opening brackets are an escaped literal bracket.
+            if let Some(after) = rest.strip_prefix('[') {
+                rest = after;
+                continue;
+            }
+
+            let name = rest
+                .split(|c: char| c.is_whitespace() || c == ']')
+                .next()
+                .unwrap_or_default();
+
+            let missing = match name {
+                "day" | "month" | "ordinal" | "weekday" | "week_number" | "year" => {
+                    self.date().is_none()
+                }
+                "hour" | "minute" | "period" | "second" | "subsecond" => {
+                    self.time().is_none()
+                }
+                _ => false,
+            };
+
+            if missing {
+                return Some(name);
+            }
+        }
+        None
+    }
+
+    /// The date, if there is one.
+    fn date(&self) -> Option<time::Date> {
+        match self {
+            Self::Date(date) => Some(*date),
+            Self::Time(_) => None,
+            Self::Datetime(datetime) => Some(datetime.date()),
+        }
+    }
+
+    /// The time, if there is one.
+    fn time(&self) -> Option<time::Time> {
+        match self {
+            Self::Date(_) => None,
+            Self::Time(time) => Some(*time),
+            Self::Datetime(datetime) => Some(datetime.time()),
+        }
+    }
+
+    /// Return the year of the datetime, if existing.
+    pub fn year(&self) -> Option<i32> {
+        self.date().map(|date| date.year())
+    }
+
+    /// Return the month of the datetime, if existing.
+    pub fn month(&self) -> Option<u8> {
+        self.date().map(|date| date.month().into())
+    }
+
+    /// Return the weekday of the datetime, if existing. Monday is `1`.
+    pub fn weekday(&self) -> Option<u8> {
+        self.date().map(|date| date.weekday().number_from_monday())
+    }
+
+    /// Return the day of the datetime, if existing.
+    pub fn day(&self) -> Option<u8> {
+        self.date().map(|date| date.day())
+    }
+
+    /// Return the ordinal (day of the year) of the datetime, if existing.
+    pub fn ordinal(&self) -> Option<u16> {
+        self.date().map(|date| date.ordinal())
+    }
+
+    /// Return the hour of the datetime, if existing.
+    pub fn hour(&self) -> Option<u8> {
+        self.time().map(|time| time.hour())
+    }
+
+    /// Return the minute of the datetime, if existing.
+    pub fn minute(&self) -> Option<u8> {
+        self.time().map(|time| time.minute())
+    }
+
+    /// Return the second of the datetime, if existing.
+    pub fn second(&self) -> Option<u8> {
+        self.time().map(|time| time.second())
+    }
+
+    /// Add a duration to the datetime.
+    ///
+    /// Dates move by whole days and times wrap around at midnight.
+    pub fn checked_add(self, duration: Duration) -> Option<Self> {
+        Some(match self {
+            Self::Date(date) => Self::Date(date.checked_add(duration.0)?),
+            Self::Time(time) => Self::Time(time + duration.0),
+            Self::Datetime(datetime) => Self::Datetime(datetime.checked_add(duration.0)?),
+        })
+    }
+
+    /// Subtract a duration from the datetime.
+    pub fn checked_sub(self, duration: Duration) -> Option<Self> {
+        self.checked_add(duration.checked_neg()?)
+    }
+
+    /// The duration between this and another datetime of the same kind.
+    pub fn since(self, other: Self) -> Option<Duration> {
+        Some(Duration(match (self, other) {
+            (Self::Date(a), Self::Date(b)) => a - b,
+            (Self::Time(a), Self::Time(b)) => a - b,
+            (Self::Datetime(a), Self::Datetime(b)) => a - b,
+            _ => return None,
+        }))
+    }
+}
+
+impl PartialOrd for Datetime {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        match (self, other) {
+            (Self::Date(a), Self::Date(b)) => a.partial_cmp(b),
+            (Self::Time(a), Self::Time(b)) => a.partial_cmp(b),
+            (Self::Datetime(a), Self::Datetime(b)) => a.partial_cmp(b),
+            _ => None,
+        }
+    }
+}
+
+impl Debug for Datetime {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        let year = self.year().map(|y| format!("year: {y}"));
+        let month = self.month().map(|m| format!("month: {m}"));
+        let day = self.day().map(|d| format!("day: {d}"));
+        let hour = self.hour().map(|h| format!("hour: {h}"));
+        let minute = self.minute().map(|m| format!("minute: {m}"));
+        let second = self.second().map(|s| format!("second: {s}"));
+        let filtered = [year, month, day, hour, minute, second]
+            .into_iter()
+            .flatten()
+            .collect::<Vec<_>>();
+
+        write!(f, "datetime({})", filtered.join(", "))
+    }
+}
+
+cast_from_value! {
+    Datetime: "datetime",
+}
+
+/// Format the `InvalidFormatDescription` error into a nicer message.
+fn format_parse_error(error: InvalidFormatDescription) -> EcoString {
+    eco_format!("invalid datetime format ({error})")
+}
+
+/// Format the `Format` error of the time crate into an appropriate message.
+fn format_time_error(error: Format) -> EcoString {
+    match error {
+        Format::InvalidComponent(name) => {
+            eco_format!("cannot display component `{name}` of this datetime")
+        }
+        _ => "failed to format datetime in the requested format".into(),
+    }
+}
+
+/// A span of time.
+#[derive(Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Hash)]
+pub struct Duration(time::Duration);
+
+impl Duration {
+    /// Create a duration from a number of weeks, days, hours, minutes and
+    /// seconds.
+    pub fn new(
+        weeks: i64,
+        days: i64,
+        hours: i64,
+        minutes: i64,
+        seconds: i64,
+    ) -> StrResult<Self> {
+        let total = weeks
+            .checked_mul(7)
+            .and_then(|v| v.checked_add(days))
+            .and_then(|v| v.checked_mul(24))
+            .and_then(|v| v.checked_add(hours))
+            .and_then(|v| v.checked_mul(60))
+            .and_then(|v| v.checked_add(minutes))
+            .and_then(|v| v.checked_mul(60))
+            .and_then(|v| v.checked_add(seconds))
+            .ok_or("duration is too large")?;
+        Ok(Self(time::Duration::seconds(total)))
+    }
+
+    /// Whether the duration is zero.
+    pub fn is_zero(&self) -> bool {
+        self.0.is_zero()
+    }
+
+    /// The duration in seconds.
+    pub fn seconds(&self) -> f64 {
+        self.0.as_seconds_f64()
+    }
+
+    /// The duration in minutes.
+    pub fn minutes(&self) -> f64 {
+        self.seconds() / 60.0
+    }
+
+    /// The duration in hours.
+    pub fn hours(&self) -> f64 {
+        self.seconds() / 3_600.0
+    }
+
+    /// The duration in days.
+    pub fn days(&self) -> f64 {
+        self.seconds() / 86_400.0
+    }
+
+    /// The duration in weeks.
+    pub fn weeks(&self) -> f64 {
+        self.seconds() / 604_800.0
+    }
+
+    /// Scale the duration by a factor.
+    pub fn checked_mul(self, factor: f64) -> Option<Self> {
+        let seconds = self.seconds() * factor;
+        (seconds.is_finite() && seconds.abs() < i64::MAX as f64)
+            .then(|| Self(time::Duration::seconds_f64(seconds)))
+    }
+
+    /// Divide the duration by another one.
+    pub fn ratio(self, other: Self) -> f64 {
+        self.seconds() / other.seconds()
+    }
+
+    /// Negate the duration.
+    pub fn checked_neg(self) -> Option<Self> {
+        self.0.checked_neg().map(Self)
+    }
+
+    /// Add another duration to this one.
+    pub fn checked_add(self, other: Self) -> Option<Self> {
+        self.0.checked_add(other.0).map(Self)
+    }
+
+    /// Subtract another duration from this one.
+    pub fn checked_sub(self, other: Self) -> Option<Self> {
+        self.0.checked_sub(other.0).map(Self)
+    }
+}
+
+impl Debug for Duration {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        let mut rest = self.0.whole_seconds();
+        let mut parts = vec![];
+        for (unit, size) in
+            [("weeks", 604_800), ("days", 86_400), ("hours", 3_600), ("minutes", 60)]
+        {
+            let amount = rest / size;
+            if amount != 0 {
+                parts.push(format!("{unit}: {amount}"));
+            }
+            rest %= size;
+        }
+
+        let nanos = self.0.subsec_nanoseconds();
+        if nanos != 0 {
+            let seconds = rest as f64 + nanos as f64 / 1e9;
+            parts.push(format!("seconds: {seconds}"));
+        } else if rest != 0 || parts.is_empty() {
+            parts.push(format!("seconds: {rest}"));
+        }
+
+        write!(f, "duration({})", parts.join(", "))
+    }
+}
+
+cast_from_value! {
+    Duration: "duration",
+}
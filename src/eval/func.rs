@@ -5,12 +5,14 @@ use std::hash::{Hash, Hasher};
 use std::sync::Arc;
 
 use comemo::{Prehashed, Track, Tracked, TrackedMut};
+use ecow::eco_format;
 use once_cell::sync::Lazy;
 
 use super::{
-    cast_to_value, Args, CastInfo, Eval, Flow, Route, Scope, Scopes, Tracer, Value, Vm,
+    cast_to_value, destructure, Args, CastInfo, Eval, Flow, Route, Scope, Scopes, Tracer,
+    Value, Vm,
 };
-use crate::diag::{bail, SourceResult};
+use crate::diag::{bail, SourceResult, StrResult};
 use crate::model::{ElemFunc, Introspector, StabilityProvider, Vt};
 use crate::syntax::ast::{self, AstNode, Expr, Ident};
 use crate::syntax::{SourceId, Span, SyntaxNode};
@@ -59,6 +61,14 @@ impl Func {
         }
     }
 
+    /// Get a field from this function's scope, if possible.
+    pub fn get(&self, field: &str) -> StrResult<&Value> {
+        self.info().and_then(|info| info.scope.get(field)).ok_or_else(|| {
+            let name = self.name().unwrap_or("anonymous");
+            eco_format!("function `{name}` does not contain `{field}`")
+        })
+    }
+
     /// The function's span.
     pub fn span(&self) -> Span {
         self.span
@@ -228,6 +238,8 @@ pub struct FuncInfo {
     pub returns: Vec<&'static str>,
     /// Which category the function is part of.
     pub category: &'static str,
+    /// The function's own scope of fields and sub-functions.
+    pub scope: Scope,
 }
 
 impl FuncInfo {
@@ -270,9 +282,8 @@ pub(super) struct Closure {
     pub name: Option<Ident>,
     /// Captured values from outer scopes.
     pub captured: Scope,
-    /// The parameter names and default values. Parameters with default value
-    /// are named parameters.
-    pub params: Vec<(Ident, Option<Value>)>,
+    /// The closure's parameters.
+    pub params: Vec<Param>,
     /// The name of an argument sink where remaining arguments are placed.
     pub sink: Option<Ident>,
     /// The expression the closure should evaluate to.
@@ -313,16 +324,21 @@ impl Closure {
         }
 
         // Parse the arguments according to the parameter list.
-        for (param, default) in &closure.params {
-            vm.define(
-                param.clone(),
-                match default {
-                    Some(default) => {
-                        args.named::<Value>(param)?.unwrap_or_else(|| default.clone())
-                    }
-                    None => args.expect::<Value>(param)?,
-                },
-            );
+        for param in &closure.params {
+            match param {
+                Param::Pos(ast::Pattern::Normal(ident)) => {
+                    vm.define(ident.clone(), args.expect::<Value>(ident)?);
+                }
+                Param::Pos(pattern) => {
+                    let value = args.expect::<Value>("pattern parameter")?;
+                    destructure(&mut vm, pattern, value)?;
+                }
+                Param::Named(ident, default) => {
+                    let value =
+                        args.named::<Value>(ident)?.unwrap_or_else(|| default.clone());
+                    vm.define(ident.clone(), value);
+                }
+            }
         }
 
         // Put the remaining arguments into the sink.
@@ -351,10 +367,24 @@ impl Closure {
             return None;
         }
 
-        Some(self.params.iter().filter(|(_, default)| default.is_none()).count())
+        Some(
+            self.params
+                .iter()
+                .filter(|param| matches!(param, Param::Pos(_)))
+                .count(),
+        )
     }
 }
 
+/// A parameter of a closure.
+#[derive(Hash)]
+pub(super) enum Param {
+    /// A positional parameter: `x` or `(x, y)`.
+    Pos(ast::Pattern),
+    /// A named parameter with a default value: `draw: false`.
+    Named(Ident, Value),
+}
+
 impl From<Closure> for Func {
     fn from(closure: Closure) -> Self {
         Repr::Closure(Arc::new(Prehashed::new(closure))).into()
@@ -423,7 +453,11 @@ impl<'a> CapturesVisitor<'a> {
 
                 for param in expr.params().children() {
                     match param {
-                        ast::Param::Pos(ident) => self.bind(ident),
+                        ast::Param::Pos(pattern) => {
+                            for ident in pattern.idents() {
+                                self.bind(ident);
+                            }
+                        }
                         ast::Param::Named(named) => self.bind(named.name()),
                         ast::Param::Sink(ident) => self.bind(ident),
                     }
@@ -439,12 +473,14 @@ impl<'a> CapturesVisitor<'a> {
                 if let Some(init) = expr.init() {
                     self.visit(init.as_untyped());
                 }
-                self.bind(expr.binding());
+                for ident in expr.kind().idents() {
+                    self.bind(ident);
+                }
             }
 
-            // A for loop contains one or two bindings in its pattern. These are
-            // active after the iterable is evaluated but before the body is
-            // evaluated.
+            // A for loop contains one or more bindings in its pattern. These
+            // are active after the iterable is evaluated but before the body
+            // is evaluated.
             Some(ast::Expr::For(expr)) => {
                 self.visit(expr.iter().as_untyped());
                 self.internal.enter();
@@ -452,7 +488,9 @@ impl<'a> CapturesVisitor<'a> {
                 if let Some(key) = pattern.key() {
                     self.bind(key);
                 }
-                self.bind(pattern.value());
+                for ident in pattern.value().idents() {
+                    self.bind(ident);
+                }
                 self.visit(expr.body().as_untyped());
                 self.internal.exit();
             }
@@ -1,10 +1,13 @@
 //! Methods on values.
 
-use ecow::EcoString;
+use std::sync::Arc;
 
-use super::{Args, Str, Value, Vm};
+use ecow::{EcoString, EcoVec};
+
+use super::{array, Args, Datetime, Duration, Str, Value, Vm};
 use crate::diag::{At, SourceResult};
-use crate::model::Location;
+use crate::geom::{Gradient, Pattern};
+use crate::model::{Location, Selector};
 use crate::syntax::Span;
 
 /// Call a method on a value.
@@ -70,6 +73,20 @@ pub fn call(
             _ => return missing(),
         },
 
+        Value::Bytes(bytes) => match method {
+            "len" => Value::Int(bytes.len()),
+            "at" => Value::Int(bytes.at(args.expect("index")?).at(span)?),
+            "slice" => {
+                let start = args.expect("start")?;
+                let mut end = args.eat()?;
+                if end.is_none() {
+                    end = args.named("count")?.map(|c: i64| start + c);
+                }
+                Value::Bytes(bytes.slice(start, end).at(span)?)
+            }
+            _ => return missing(),
+        },
+
         Value::Content(content) => match method {
             "func" => content.func().into(),
             "has" => Value::Bool(content.has(&args.expect::<EcoString>("field")?)),
@@ -156,6 +173,86 @@ pub fn call(
                     "position" => vm.vt.introspector.position(location).into(),
                     _ => return missing(),
                 }
+            } else if let Some(gradient) = dynamic.downcast::<Gradient>() {
+                match method {
+                    "kind" => Value::Str(gradient.kind().into()),
+                    "stops" => Value::Array(
+                        gradient
+                            .stops()
+                            .iter()
+                            .map(|&(color, offset)| Value::Array(array![color, offset]))
+                            .collect(),
+                    ),
+                    "space" => gradient.space().into(),
+                    "relative" => gradient.relative().into(),
+                    "angle" => gradient.angle().into(),
+                    "sample" => gradient.sample(args.expect("t")?).into(),
+                    _ => return missing(),
+                }
+            } else if let Some(pattern) = dynamic.downcast::<Pattern>() {
+                match method {
+                    "size" => {
+                        let size = pattern.size();
+                        Value::Array(array![size.x, size.y])
+                    }
+                    "spacing" => {
+                        let spacing = pattern.spacing();
+                        Value::Array(array![spacing.x, spacing.y])
+                    }
+                    "relative" => pattern.relative().into(),
+                    _ => return missing(),
+                }
+            } else if let Some(datetime) = dynamic.downcast::<Datetime>() {
+                match method {
+                    "display" => datetime.display(args.eat()?).at(span)?.into(),
+                    "year" => datetime.year().into(),
+                    "month" => datetime.month().into(),
+                    "weekday" => datetime.weekday().into(),
+                    "day" => datetime.day().into(),
+                    "hour" => datetime.hour().into(),
+                    "minute" => datetime.minute().into(),
+                    "second" => datetime.second().into(),
+                    "ordinal" => datetime.ordinal().into(),
+                    _ => return missing(),
+                }
+            } else if let Some(duration) = dynamic.downcast::<Duration>() {
+                match method {
+                    "seconds" => duration.seconds().into(),
+                    "minutes" => duration.minutes().into(),
+                    "hours" => duration.hours().into(),
+                    "days" => duration.days().into(),
+                    "weeks" => duration.weeks().into(),
+                    _ => return missing(),
+                }
+            } else if let Some(selector) = dynamic.downcast::<Selector>() {
+                let selector = selector.clone();
+                match method {
+                    "or" | "and" => {
+                        let selectors: EcoVec<_> = std::iter::once(selector)
+                            .chain(args.all::<Selector>()?)
+                            .collect();
+                        Value::dynamic(if method == "or" {
+                            Selector::Any(selectors)
+                        } else {
+                            Selector::And(selectors)
+                        })
+                    }
+                    "before" => Value::dynamic(Selector::Before {
+                        selector: Arc::new(selector),
+                        end: Arc::new(args.expect("end")?),
+                        inclusive: args.named("inclusive")?.unwrap_or(true),
+                    }),
+                    "after" => Value::dynamic(Selector::After {
+                        selector: Arc::new(selector),
+                        start: Arc::new(args.expect("start")?),
+                        inclusive: args.named("inclusive")?.unwrap_or(true),
+                    }),
+                    "within" => Value::dynamic(Selector::Within {
+                        selector: Arc::new(selector),
+                        ancestor: Arc::new(args.expect("ancestor")?),
+                    }),
+                    _ => return missing(),
+                }
             } else {
                 return (vm.items.library_method)(vm, &dynamic, method, args, span);
             }
@@ -272,6 +369,7 @@ pub fn methods_on(type_name: &str) -> &[(&'static str, bool)] {
             ("starts-with", true),
             ("trim", true),
         ],
+        "bytes" => &[("len", false), ("at", true), ("slice", true)],
         "content" => &[("func", false), ("has", true), ("at", true), ("location", false)],
         "array" => &[
             ("all", true),
@@ -309,6 +407,40 @@ pub fn methods_on(type_name: &str) -> &[(&'static str, bool)] {
         "function" => &[("where", true), ("with", true)],
         "arguments" => &[("named", false), ("pos", false)],
         "location" => &[("page", false), ("position", false)],
+        "gradient" => &[
+            ("angle", false),
+            ("kind", false),
+            ("relative", false),
+            ("sample", true),
+            ("space", false),
+            ("stops", false),
+        ],
+        "pattern" => &[("relative", false), ("size", false), ("spacing", false)],
+        "datetime" => &[
+            ("display", true),
+            ("year", false),
+            ("month", false),
+            ("weekday", false),
+            ("day", false),
+            ("hour", false),
+            ("minute", false),
+            ("second", false),
+            ("ordinal", false),
+        ],
+        "duration" => &[
+            ("seconds", false),
+            ("minutes", false),
+            ("hours", false),
+            ("days", false),
+            ("weeks", false),
+        ],
+        "selector" => &[
+            ("or", true),
+            ("and", true),
+            ("before", true),
+            ("after", true),
+            ("within", true),
+        ],
         "counter" => &[
             ("display", true),
             ("at", true),
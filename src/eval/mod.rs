@@ -13,6 +13,8 @@ mod str;
 #[macro_use]
 mod value;
 mod args;
+mod bytes;
+mod datetime;
 mod func;
 mod methods;
 mod module;
@@ -25,7 +27,9 @@ pub use once_cell::sync::Lazy;
 
 pub use self::args::*;
 pub use self::array::*;
+pub use self::bytes::*;
 pub use self::cast::*;
+pub use self::datetime::*;
 pub use self::dict::*;
 pub use self::func::*;
 pub use self::library::*;
@@ -37,7 +41,7 @@ pub use self::value::*;
 
 pub(crate) use self::methods::methods_on;
 
-use std::collections::BTreeMap;
+use std::collections::{BTreeMap, HashSet};
 use std::mem;
 use std::path::{Path, PathBuf};
 
@@ -46,17 +50,19 @@ use ecow::EcoVec;
 use unicode_segmentation::UnicodeSegmentation;
 
 use crate::diag::{
-    bail, error, At, SourceError, SourceResult, StrResult, Trace, Tracepoint,
+    bail, error, warning, At, SourceDiagnostic, SourceResult, StrResult, Trace,
+    Tracepoint,
 };
 use crate::model::{
     Content, Introspector, Label, Recipe, Selector, StabilityProvider, Styles, Transform,
     Unlabellable, Vt,
 };
+use crate::package::{PackageManifest, PackageSpec, Version, MANIFEST};
 use crate::syntax::ast::AstNode;
 use crate::syntax::{
     ast, parse_code, Source, SourceId, Span, Spanned, SyntaxKind, SyntaxNode,
 };
-use crate::util::PathExt;
+use crate::util::{hash128, PathExt};
 use crate::World;
 
 const MAX_ITERATIONS: usize = 10_000;
@@ -213,13 +219,30 @@ impl<'a> Vm<'a> {
 
     /// Resolve a user-entered path to be relative to the compilation
     /// environment's root.
+    ///
+    /// Within a package, absolute paths are relative to the package's root
+    /// and no files outside of the package can be accessed.
     pub fn locate(&self, path: &str) -> StrResult<PathBuf> {
         if !self.location.is_detached() {
+            let world = self.world();
+            let source = world.source(self.location);
+            if let Some(spec) = source.package() {
+                let root = world.package(spec)?.normalize();
+                let full = match path.strip_prefix('/') {
+                    Some(path) => root.join(path).normalize(),
+                    None => source.path().with_file_name(path).normalize(),
+                };
+                if !full.starts_with(&root) {
+                    return Err("cannot access files outside of the package".into());
+                }
+                return Ok(full);
+            }
+
             if let Some(path) = path.strip_prefix('/') {
                 return Ok(self.world().root().join(path).normalize());
             }
 
-            if let Some(dir) = self.world().source(self.location).path().parent() {
+            if let Some(dir) = source.path().parent() {
                 return Ok(dir.join(path).normalize());
             }
         }
@@ -242,7 +265,7 @@ pub enum Flow {
 
 impl Flow {
     /// Return an error stating that this control flow is forbidden.
-    pub fn forbidden(&self) -> SourceError {
+    pub fn forbidden(&self) -> SourceDiagnostic {
         match *self {
             Self::Break(span) => {
                 error!(span, "cannot break outside of loop")
@@ -290,11 +313,13 @@ impl Route {
     }
 }
 
-/// Traces which values existed for the expression at a span.
+/// Traces warnings and which values existed for the expression at a span.
 #[derive(Default, Clone)]
 pub struct Tracer {
     span: Option<Span>,
     values: Vec<Value>,
+    warnings: Vec<SourceDiagnostic>,
+    warned: HashSet<u128>,
 }
 
 impl Tracer {
@@ -303,13 +328,18 @@ impl Tracer {
 
     /// Create a new tracer, possibly with a span under inspection.
     pub fn new(span: Option<Span>) -> Self {
-        Self { span, values: vec![] }
+        Self { span, ..Self::default() }
     }
 
     /// Get the traced values.
     pub fn finish(self) -> Vec<Value> {
         self.values
     }
+
+    /// The warnings that were emitted so far.
+    pub fn warnings(&self) -> &[SourceDiagnostic] {
+        &self.warnings
+    }
 }
 
 #[comemo::track]
@@ -329,6 +359,17 @@ impl Tracer {
             self.values.push(v);
         }
     }
+
+    /// Emit a warning.
+    ///
+    /// Warnings with the same span and message as an earlier one are dropped
+    /// since repeated layout attempts would otherwise report them many times.
+    pub fn warn(&mut self, warning: SourceDiagnostic) {
+        let hash = hash128(&(&warning.span, &warning.message));
+        if self.warned.insert(hash) {
+            self.warnings.push(warning);
+        }
+    }
 }
 
 /// Evaluate an expression.
@@ -381,6 +422,12 @@ fn eval_markup(
                         seq.iter_mut().rev().find(|node| !node.can::<dyn Unlabellable>())
                     {
                         *elem = mem::take(elem).labelled(label);
+                    } else {
+                        vm.vt.tracer.warn(warning!(
+                            expr.span(),
+                            "label `<{}>` is not attached to anything",
+                            label.0,
+                        ));
                     }
                 }
                 value => seq.push(value.display().spanned(expr.span())),
@@ -829,6 +876,20 @@ impl Eval for ast::ContentBlock {
     type Output = Content;
 
     fn eval(&self, vm: &mut Vm) -> SourceResult<Self::Output> {
+        // A set rule at the very end of a content block has nothing to apply
+        // to since its styles don't escape the block. Spaces and comments
+        // after it don't count as content.
+        let last =
+            self.body().exprs().rev().find(|expr| {
+                !matches!(expr, ast::Expr::Space(_) | ast::Expr::Parbreak(_))
+            });
+        if let Some(ast::Expr::Set(set)) = last {
+            vm.vt.tracer.warn(
+                warning!(set.span(), "set rule has no effect")
+                    .with_hint("set rules only apply to content that follows them"),
+            );
+        }
+
         vm.scopes.enter();
         let content = self.body().eval(vm)?;
         vm.scopes.exit();
@@ -1009,7 +1070,8 @@ impl Eval for ast::FuncCall {
         let args = self.args();
 
         // Try to evaluate as a method call. This is possible if the callee is a
-        // field access and does not evaluate to a module.
+        // field access and does not evaluate to a module or a function that
+        // has the field in its scope.
         let (callee, mut args) = if let ast::Expr::FieldAccess(access) = callee {
             let target = access.target();
             let field = access.field();
@@ -1030,7 +1092,9 @@ impl Eval for ast::FuncCall {
             } else {
                 let target = target.eval(vm)?;
                 let args = args.eval(vm)?;
-                if !matches!(target, Value::Symbol(_) | Value::Module(_)) {
+                if !matches!(target, Value::Symbol(_) | Value::Module(_))
+                    && !matches!(&target, Value::Func(func) if func.get(&field).is_ok())
+                {
                     return methods::call(vm, target, &field, args, span).trace(
                         vm.world(),
                         point,
@@ -1155,11 +1219,11 @@ impl Eval for ast::Closure {
         // Collect parameters and an optional sink parameter.
         for param in self.params().children() {
             match param {
-                ast::Param::Pos(name) => {
-                    params.push((name, None));
+                ast::Param::Pos(pattern) => {
+                    params.push(Param::Pos(pattern));
                 }
                 ast::Param::Named(named) => {
-                    params.push((named.name(), Some(named.expr().eval(vm)?)));
+                    params.push(Param::Named(named.name(), named.expr().eval(vm)?));
                 }
                 ast::Param::Sink(name) => {
                     if sink.is_some() {
@@ -1192,11 +1256,124 @@ impl Eval for ast::LetBinding {
             Some(expr) => expr.eval(vm)?,
             None => Value::None,
         };
-        vm.define(self.binding(), value);
+
+        match self.kind() {
+            ast::LetBindingKind::Normal(pattern) => destructure(vm, &pattern, value)?,
+            ast::LetBindingKind::Closure(ident) => vm.define(ident, value),
+        }
+
         Ok(Value::None)
     }
 }
 
+/// Bind a value to a pattern, destructuring it if necessary.
+fn destructure(vm: &mut Vm, pattern: &ast::Pattern, value: Value) -> SourceResult<()> {
+    match pattern {
+        ast::Pattern::Normal(ident) => vm.define(ident.clone(), value),
+        ast::Pattern::Placeholder(_) => {}
+        ast::Pattern::Destructuring(destructuring) => match value {
+            Value::Array(array) => destructure_array(vm, destructuring, array)?,
+            Value::Dict(dict) => destructure_dict(vm, destructuring, dict)?,
+            v => bail!(pattern.span(), "cannot destructure {}", v.type_name()),
+        },
+    }
+    Ok(())
+}
+
+/// Destructure an array into the items of a pattern.
+fn destructure_array(
+    vm: &mut Vm,
+    destructuring: &ast::Destructuring,
+    array: Array,
+) -> SourceResult<()> {
+    let items: Vec<_> = destructuring.items().collect();
+    let sink = items
+        .iter()
+        .any(|item| matches!(item, ast::DestructuringItem::Sink(_)));
+
+    let len = array.as_slice().len();
+    let expected = items.len() - usize::from(sink);
+    if len < expected {
+        let at_least = if sink { "at least " } else { "" };
+        bail!(
+            destructuring.span(),
+            "not enough elements to destructure (expected {}{}, found {})",
+            at_least,
+            expected,
+            len,
+        );
+    } else if len > expected && !sink {
+        bail!(
+            destructuring.span(),
+            "too many elements to destructure (expected {}, found {})",
+            expected,
+            len,
+        );
+    }
+
+    let mut values = array.into_iter();
+    for item in items {
+        match item {
+            ast::DestructuringItem::Pattern(pattern) => {
+                destructure(vm, &pattern, values.next().unwrap_or_default())?;
+            }
+            ast::DestructuringItem::Named(named) => {
+                bail!(named.span(), "cannot destructure named elements from an array");
+            }
+            ast::DestructuringItem::Sink(ident) => {
+                let rest: Array = values.by_ref().take(len - expected).collect();
+                if let Some(ident) = ident {
+                    vm.define(ident, rest);
+                }
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/// Destructure a dictionary into the items of a pattern.
+fn destructure_dict(
+    vm: &mut Vm,
+    destructuring: &ast::Destructuring,
+    dict: Dict,
+) -> SourceResult<()> {
+    let mut used = vec![];
+    let mut sink = None;
+    for item in destructuring.items() {
+        match item {
+            ast::DestructuringItem::Pattern(ast::Pattern::Normal(ident)) => {
+                let value = dict.at(&ident).at(ident.span())?.clone();
+                used.push(ident.get().clone());
+                vm.define(ident, value);
+            }
+            ast::DestructuringItem::Pattern(pattern) => {
+                bail!(
+                    pattern.span(),
+                    "cannot destructure unnamed pattern from dictionary"
+                );
+            }
+            ast::DestructuringItem::Named(named) => {
+                let name = named.name();
+                let value = dict.at(&name).at(name.span())?.clone();
+                used.push(name.take());
+                destructure(vm, &named.pattern(), value)?;
+            }
+            ast::DestructuringItem::Sink(ident) => sink = ident,
+        }
+    }
+
+    if let Some(ident) = sink {
+        let rest: Dict = dict
+            .into_iter()
+            .filter(|(key, _)| !used.iter().any(|name| name == key.as_str()))
+            .collect();
+        vm.define(ident, rest);
+    }
+
+    Ok(())
+}
+
 impl Eval for ast::SetRule {
     type Output = Styles;
 
@@ -1218,7 +1395,7 @@ impl Eval for ast::SetRule {
             })
             .at(target.span())?;
         let args = self.args().eval(vm)?;
-        Ok(target.set(args)?.spanned(self.span()))
+        Ok(target.set(vm, args)?.spanned(self.span()))
     }
 }
 
@@ -1228,7 +1405,13 @@ impl Eval for ast::ShowRule {
     fn eval(&self, vm: &mut Vm) -> SourceResult<Self::Output> {
         let selector = self
             .selector()
-            .map(|sel| sel.eval(vm)?.cast::<Selector>().at(sel.span()))
+            .map(|sel| {
+                let selector = sel.eval(vm)?.cast::<Selector>().at(sel.span())?;
+                if !selector.is_local() {
+                    bail!(sel.span(), "this selector cannot be used in show rules");
+                }
+                Ok(selector)
+            })
             .transpose()?;
 
         let transform = self.transform();
@@ -1333,12 +1516,23 @@ impl Eval for ast::ForLoop {
         let mut output = Value::None;
 
         macro_rules! iter {
-            (for ($($binding:ident => $value:ident),*) in $iter:expr) => {{
+            (for ($key:ident => $k:ident, $pat:ident => $v:ident) in $iter:expr) => {
+                iter!(@loop ($k, $v) in $iter => {
+                    vm.define($key.clone(), $k);
+                    destructure(vm, &$pat, $v.into())?;
+                })
+            };
+            (for ($pat:ident => $v:ident) in $iter:expr) => {
+                iter!(@loop ($v) in $iter => {
+                    destructure(vm, &$pat, $v.into())?;
+                })
+            };
+            (@loop $binding:pat in $iter:expr => $bind:block) => {{
                 vm.scopes.enter();
 
                 #[allow(unused_parens)]
-                for ($($value),*) in $iter {
-                    $(vm.define($binding.clone(), $value);)*
+                for $binding in $iter {
+                    $bind
 
                     let body = self.body();
                     let value = body.eval(vm)?;
@@ -1364,6 +1558,9 @@ impl Eval for ast::ForLoop {
         let key = pattern.key();
         let value = pattern.value();
 
+        // With a destructuring pattern, dictionaries and arguments yield pairs.
+        let pairs = matches!(value, ast::Pattern::Destructuring(_));
+
         match (key, value, iter) {
             (None, v, Value::Str(string)) => {
                 iter!(for (v => value) in string.as_str().graphemes(true));
@@ -1374,12 +1571,23 @@ impl Eval for ast::ForLoop {
             (Some(i), v, Value::Array(array)) => {
                 iter!(for (i => idx, v => value) in array.into_iter().enumerate());
             }
+            (None, v, Value::Dict(dict)) if pairs => {
+                iter!(for (v => value) in dict.into_iter()
+                    .map(|(k, v)| Value::Array(array![k, v])));
+            }
             (None, v, Value::Dict(dict)) => {
                 iter!(for (v => value) in dict.into_iter().map(|p| p.1));
             }
             (Some(k), v, Value::Dict(dict)) => {
                 iter!(for (k => key, v => value) in dict.into_iter());
             }
+            (None, v, Value::Args(args)) if pairs => {
+                let items = args.items.into_iter().map(|arg| {
+                    let name = arg.name.map_or(Value::None, Value::Str);
+                    Value::Array(array![name, arg.value.v])
+                });
+                iter!(for (v => value) in items);
+            }
             (None, v, Value::Args(args)) => {
                 iter!(for (v => value) in args.items.into_iter()
                     .filter(|arg| arg.name.is_none())
@@ -1460,10 +1668,39 @@ fn import(vm: &mut Vm, source: Value, span: Span) -> SourceResult<Module> {
         v => bail!(span, "expected path or module, found {}", v.type_name()),
     };
 
+    if path.as_str().starts_with('@') {
+        let spec = path.parse::<PackageSpec>().at(span)?;
+        return import_package(vm, spec, span);
+    }
+
     // Load the source file.
-    let world = vm.world();
     let full = vm.locate(&path).at(span)?;
-    let id = world.resolve(&full).at(span)?;
+    import_file(vm, &full, span)
+}
+
+/// Import the entrypoint of a package.
+fn import_package(vm: &mut Vm, spec: PackageSpec, span: Span) -> SourceResult<Module> {
+    // Load and validate the package's manifest.
+    let world = vm.world();
+    let root = world.package(&spec).at(span)?.normalize();
+    let data = world.file(&root.join(MANIFEST)).at(span)?;
+    let manifest = PackageManifest::parse(&data).at(span)?;
+    manifest.validate(&spec, Version::compiler()).at(span)?;
+
+    // Find the entrypoint, which must lie within the package.
+    let full = root.join(&manifest.package.entrypoint).normalize();
+    if !full.starts_with(&root) {
+        bail!(span, "package entrypoint must lie within the package");
+    }
+
+    let module = import_file(vm, &full, span)?;
+    Ok(module.with_name(spec.name))
+}
+
+/// Import a source file at a resolved path.
+fn import_file(vm: &mut Vm, full: &Path, span: Span) -> SourceResult<Module> {
+    let world = vm.world();
+    let id = world.resolve(full).at(span)?;
 
     // Prevent cyclic importing.
     if vm.route.contains(id) {
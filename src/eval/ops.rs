@@ -4,9 +4,12 @@ use std::cmp::Ordering;
 
 use ecow::eco_format;
 
-use super::{format_str, Regex, Value};
+use super::{format_str, Datetime, Duration, Regex, Value};
 use crate::diag::StrResult;
-use crate::geom::{Axes, Axis, GenAlign, Length, Numeric, PartialStroke, Rel, Smart};
+use crate::geom::{
+    Axes, Axis, GenAlign, Gradient, Length, Numeric, Paint, PartialStroke, Pattern, Rel,
+    Smart,
+};
 use Value::*;
 
 /// Bail with a type mismatch error.
@@ -25,6 +28,7 @@ pub fn join(lhs: Value, rhs: Value) -> StrResult<Value> {
         (Str(a), Str(b)) => Str(a + b),
         (Str(a), Symbol(b)) => Str(format_str!("{a}{b}")),
         (Symbol(a), Str(b)) => Str(format_str!("{a}{b}")),
+        (Bytes(a), Bytes(b)) => Bytes(a + b),
         (Content(a), Content(b)) => Content(a + b),
         (Content(a), Symbol(b)) => Content(a + item!(text)(b.get().into())),
         (Content(a), Str(b)) => Content(a + item!(text)(b.into())),
@@ -60,6 +64,10 @@ pub fn neg(value: Value) -> StrResult<Value> {
         Ratio(v) => Ratio(-v),
         Relative(v) => Relative(-v),
         Fraction(v) => Fraction(-v),
+        Dyn(v) if v.is::<Duration>() => {
+            let v = v.downcast::<Duration>().unwrap();
+            Value::dynamic(v.checked_neg().ok_or("duration is too large")?)
+        }
         v => mismatch!("cannot apply '-' to {}", v),
     })
 }
@@ -95,6 +103,7 @@ pub fn add(lhs: Value, rhs: Value) -> StrResult<Value> {
         (Str(a), Str(b)) => Str(a + b),
         (Str(a), Symbol(b)) => Str(format_str!("{a}{b}")),
         (Symbol(a), Str(b)) => Str(format_str!("{a}{b}")),
+        (Bytes(a), Bytes(b)) => Bytes(a + b),
         (Content(a), Content(b)) => Content(a + b),
         (Content(a), Symbol(b)) => Content(a + item!(text)(b.get().into())),
         (Content(a), Str(b)) => Content(a + item!(text)(b.into())),
@@ -108,10 +117,40 @@ pub fn add(lhs: Value, rhs: Value) -> StrResult<Value> {
             Value::dynamic(PartialStroke {
                 paint: Smart::Custom(color.into()),
                 thickness: Smart::Custom(thickness),
+                ..PartialStroke::default()
+            })
+        }
+
+        (Dyn(paint), Length(thickness)) | (Length(thickness), Dyn(paint))
+            if paint.is::<Gradient>() || paint.is::<Pattern>() =>
+        {
+            Value::dynamic(PartialStroke {
+                paint: Smart::Custom(Value::Dyn(paint).cast::<Paint>()?),
+                thickness: Smart::Custom(thickness),
+                ..PartialStroke::default()
             })
         }
 
         (Dyn(a), Dyn(b)) => {
+            // Durations can be added to each other and to datetimes.
+            if let Some(&b) = b.downcast::<Duration>() {
+                if let Some(&a) = a.downcast::<Duration>() {
+                    return Ok(Value::dynamic(
+                        a.checked_add(b).ok_or("duration is too large")?,
+                    ));
+                } else if let Some(&a) = a.downcast::<Datetime>() {
+                    return Ok(Value::dynamic(
+                        a.checked_add(b).ok_or("resulting datetime is out of range")?,
+                    ));
+                }
+            } else if let (Some(&a), Some(&b)) =
+                (a.downcast::<Duration>(), b.downcast::<Datetime>())
+            {
+                return Ok(Value::dynamic(
+                    b.checked_add(a).ok_or("resulting datetime is out of range")?,
+                ));
+            }
+
             // 1D alignments can be summed into 2D alignments.
             if let (Some(&a), Some(&b)) =
                 (a.downcast::<GenAlign>(), b.downcast::<GenAlign>())
@@ -157,6 +196,29 @@ pub fn sub(lhs: Value, rhs: Value) -> StrResult<Value> {
 
         (Fraction(a), Fraction(b)) => Fraction(a - b),
 
+        (Dyn(a), Dyn(b)) => {
+            if let (Some(&a), Some(&b)) =
+                (a.downcast::<Duration>(), b.downcast::<Duration>())
+            {
+                Value::dynamic(a.checked_sub(b).ok_or("duration is too large")?)
+            } else if let (Some(&a), Some(&b)) =
+                (a.downcast::<Datetime>(), b.downcast::<Duration>())
+            {
+                Value::dynamic(
+                    a.checked_sub(b).ok_or("resulting datetime is out of range")?,
+                )
+            } else if let (Some(&a), Some(&b)) =
+                (a.downcast::<Datetime>(), b.downcast::<Datetime>())
+            {
+                match a.since(b) {
+                    Some(duration) => Value::dynamic(duration),
+                    Option::None => Err("cannot subtract datetimes of different kinds")?,
+                }
+            } else {
+                mismatch!("cannot subtract {1} from {0}", a, b);
+            }
+        }
+
         (a, b) => mismatch!("cannot subtract {1} from {0}", a, b),
     })
 }
@@ -201,6 +263,11 @@ pub fn mul(lhs: Value, rhs: Value) -> StrResult<Value> {
         (Content(a), Int(b)) => Content(a.repeat(b)?),
         (Int(a), Content(b)) => Content(b.repeat(a)?),
 
+        (Dyn(a), Int(b)) if a.is::<Duration>() => scale_duration(&a, b as f64)?,
+        (Dyn(a), Float(b)) if a.is::<Duration>() => scale_duration(&a, b)?,
+        (Int(a), Dyn(b)) if b.is::<Duration>() => scale_duration(&b, a as f64)?,
+        (Float(a), Dyn(b)) if b.is::<Duration>() => scale_duration(&b, a)?,
+
         (a, b) => mismatch!("cannot multiply {} with {}", a, b),
     })
 }
@@ -241,6 +308,14 @@ pub fn div(lhs: Value, rhs: Value) -> StrResult<Value> {
         (Fraction(a), Float(b)) => Fraction(a / b),
         (Fraction(a), Fraction(b)) => Float(a / b),
 
+        (Dyn(a), Int(b)) if a.is::<Duration>() => scale_duration(&a, 1.0 / b as f64)?,
+        (Dyn(a), Float(b)) if a.is::<Duration>() => scale_duration(&a, 1.0 / b)?,
+        (Dyn(a), Dyn(b)) if a.is::<Duration>() && b.is::<Duration>() => {
+            let a = a.downcast::<Duration>().unwrap();
+            let b = b.downcast::<Duration>().unwrap();
+            Float(a.ratio(*b))
+        }
+
         (a, b) => mismatch!("cannot divide {} by {}", a, b),
     })
 }
@@ -255,10 +330,20 @@ fn is_zero(v: &Value) -> bool {
         Ratio(v) => v.is_zero(),
         Relative(v) => v.is_zero(),
         Fraction(v) => v.is_zero(),
+        Dyn(ref v) => v.downcast::<Duration>().map_or(false, Duration::is_zero),
         _ => false,
     }
 }
 
+/// Scale a dynamic value that holds a duration.
+fn scale_duration(duration: &super::Dynamic, factor: f64) -> StrResult<Value> {
+    let duration = duration.downcast::<Duration>().unwrap();
+    duration
+        .checked_mul(factor)
+        .map(Value::dynamic)
+        .ok_or_else(|| "resulting duration is out of range".into())
+}
+
 /// Try to divide two lengths.
 fn try_div_length(a: Length, b: Length) -> StrResult<f64> {
     a.try_div(b).ok_or_else(|| "cannot divide these two lengths".into())
@@ -339,6 +424,7 @@ pub fn equal(lhs: &Value, rhs: &Value) -> bool {
         (Color(a), Color(b)) => a == b,
         (Symbol(a), Symbol(b)) => a == b,
         (Str(a), Str(b)) => a == b,
+        (Bytes(a), Bytes(b)) => a == b,
         (Label(a), Label(b)) => a == b,
         (Content(a), Content(b)) => a == b,
         (Array(a), Array(b)) => a == b,
@@ -372,6 +458,19 @@ pub fn compare(lhs: &Value, rhs: &Value) -> Option<Ordering> {
         (Relative(a), Relative(b)) => a.partial_cmp(b),
         (Fraction(a), Fraction(b)) => a.partial_cmp(b),
         (Str(a), Str(b)) => a.partial_cmp(b),
+        (Dyn(a), Dyn(b)) => {
+            if let (Some(a), Some(b)) =
+                (a.downcast::<Datetime>(), b.downcast::<Datetime>())
+            {
+                a.partial_cmp(b)
+            } else if let (Some(a), Some(b)) =
+                (a.downcast::<Duration>(), b.downcast::<Duration>())
+            {
+                a.partial_cmp(b)
+            } else {
+                Option::None
+            }
+        }
 
         // Some technically different things should be comparable.
         (&Int(a), &Float(b)) => (a as f64).partial_cmp(&b),
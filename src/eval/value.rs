@@ -5,11 +5,12 @@ use std::hash::{Hash, Hasher};
 use std::sync::Arc;
 
 use ecow::eco_format;
+use serde::{Serialize, Serializer};
 use siphasher::sip128::{Hasher128, SipHasher};
 
 use super::{
-    cast_to_value, format_str, ops, Args, Array, Cast, CastInfo, Content, Dict, Func,
-    Label, Module, Str, Symbol,
+    cast_to_value, format_str, ops, Args, Array, Bytes, Cast, CastInfo, Content, Dict,
+    Func, Label, Module, Str, Symbol,
 };
 use crate::diag::StrResult;
 use crate::geom::{Abs, Angle, Color, Em, Fr, Length, Ratio, Rel};
@@ -46,6 +47,8 @@ pub enum Value {
     Symbol(Symbol),
     /// A string: `"string"`.
     Str(Str),
+    /// Raw bytes.
+    Bytes(Bytes),
     /// A label: `<intro>`.
     Label(Label),
     /// A content value: `[*Hi* there]`.
@@ -103,6 +106,7 @@ impl Value {
             Self::Color(_) => Color::TYPE_NAME,
             Self::Symbol(_) => Symbol::TYPE_NAME,
             Self::Str(_) => Str::TYPE_NAME,
+            Self::Bytes(_) => Bytes::TYPE_NAME,
             Self::Label(_) => Label::TYPE_NAME,
             Self::Content(_) => Content::TYPE_NAME,
             Self::Styles(_) => Styles::TYPE_NAME,
@@ -127,6 +131,7 @@ impl Value {
             Self::Dict(dict) => dict.at(field).cloned(),
             Self::Content(content) => content.at(field),
             Self::Module(module) => module.get(field).cloned(),
+            Self::Func(func) => func.get(field).cloned(),
             v => Err(eco_format!("cannot access fields on type {}", v.type_name())),
         }
     }
@@ -185,6 +190,7 @@ impl Debug for Value {
             Self::Color(v) => Debug::fmt(v, f),
             Self::Symbol(v) => Debug::fmt(v, f),
             Self::Str(v) => Debug::fmt(v, f),
+            Self::Bytes(v) => Debug::fmt(v, f),
             Self::Label(v) => Debug::fmt(v, f),
             Self::Content(v) => Debug::fmt(v, f),
             Self::Styles(v) => Debug::fmt(v, f),
@@ -227,6 +233,7 @@ impl Hash for Value {
             Self::Color(v) => v.hash(state),
             Self::Symbol(v) => v.hash(state),
             Self::Str(v) => v.hash(state),
+            Self::Bytes(v) => v.hash(state),
             Self::Label(v) => v.hash(state),
             Self::Content(v) => v.hash(state),
             Self::Styles(v) => v.hash(state),
@@ -240,6 +247,28 @@ impl Hash for Value {
     }
 }
 
+impl Serialize for Value {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        match self {
+            Self::None => serializer.serialize_none(),
+            Self::Bool(v) => serializer.serialize_bool(*v),
+            Self::Int(v) => serializer.serialize_i64(*v),
+            Self::Float(v) => serializer.serialize_f64(*v),
+            Self::Str(v) => serializer.serialize_str(v),
+            Self::Bytes(v) => serializer.serialize_bytes(v),
+            Self::Content(v) => v.serialize(serializer),
+            Self::Array(v) => serializer.collect_seq(v.iter()),
+            Self::Dict(v) => {
+                serializer.collect_map(v.iter().map(|(key, value)| (key.as_str(), value)))
+            }
+            v => serializer.serialize_str(&v.repr()),
+        }
+    }
+}
+
 /// A dynamic value.
 #[derive(Clone, Hash)]
 pub struct Dynamic(Arc<dyn Bounds>);
@@ -394,6 +423,7 @@ primitive! {
     Str,
     Symbol(symbol) => symbol.get().into()
 }
+primitive! { Bytes: "bytes", Bytes }
 primitive! { Label: "label", Label }
 primitive! { Content: "content",
     Content,
@@ -445,4 +475,28 @@ mod tests {
         test(dict!["one" => 1], "(one: 1)");
         test(dict!["two" => false, "one" => 1], "(one: 1, two: false)");
     }
+
+    #[track_caller]
+    fn test_json(value: impl Into<Value>, exp: &str) {
+        assert_eq!(serde_json::to_string(&value.into()).unwrap(), exp);
+    }
+
+    #[test]
+    fn test_value_serialize() {
+        // Primitives.
+        test_json(Value::None, "null");
+        test_json(Value::Auto, r#""auto""#);
+        test_json(true, "true");
+        test_json(12i64, "12");
+        test_json(3.5, "3.5");
+        test_json("hello", r#""hello""#);
+        test_json(Abs::pt(5.5), r#""5.5pt""#);
+
+        // Collections.
+        test_json(array![1, "a", Value::None], r#"[1,"a",null]"#);
+        test_json(dict!["two" => false, "one" => 1], r#"{"one":1,"two":false}"#);
+
+        // Content.
+        test_json(Content::empty(), r#"{"func":"sequence","children":[]}"#);
+    }
 }
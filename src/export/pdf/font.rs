@@ -69,7 +69,7 @@ pub fn write_fonts(ctx: &mut PdfContext) {
         // Extract the widths of all glyphs.
         let num_glyphs = ttf.number_of_glyphs();
         let mut widths = vec![0.0; num_glyphs as usize];
-        for &g in glyphs {
+        for &g in glyphs.keys() {
             let x = ttf.glyph_hor_advance(GlyphId(g)).unwrap_or(0);
             widths[g as usize] = font.to_em(x).to_font_units();
         }
@@ -131,25 +131,8 @@ pub fn write_fonts(ctx: &mut PdfContext) {
 
         // Compute a reverse mapping from glyphs to unicode.
         let cmap = {
-            let mut mapping = BTreeMap::new();
-            for subtable in
-                ttf.tables().cmap.into_iter().flat_map(|table| table.subtables)
-            {
-                if subtable.is_unicode() {
-                    subtable.codepoints(|n| {
-                        if let Some(c) = std::char::from_u32(n) {
-                            if let Some(GlyphId(g)) = ttf.glyph_index(c) {
-                                if glyphs.contains(&g) {
-                                    mapping.insert(g, c);
-                                }
-                            }
-                        }
-                    });
-                }
-            }
-
             let mut cmap = UnicodeCmap::new(cmap_name, system_info);
-            for (g, c) in mapping {
+            for (g, c) in unicode_mapping(ttf, glyphs) {
                 cmap.pair(g, c);
             }
             cmap
@@ -164,7 +147,7 @@ pub fn write_fonts(ctx: &mut PdfContext) {
         // Subset and write the font's bytes.
         let data = font.data();
         let subsetted = {
-            let glyphs: Vec<_> = glyphs.iter().copied().collect();
+            let glyphs: Vec<_> = glyphs.keys().copied().collect();
             let profile = subsetter::Profile::pdf(&glyphs);
             subsetter::subset(data, font.index(), profile)
         };
@@ -182,3 +165,58 @@ pub fn write_fonts(ctx: &mut PdfContext) {
         stream.finish();
     }
 }
+
+/// Map the used glyphs back to the unicode codepoints they represent.
+fn unicode_mapping(
+    ttf: &ttf_parser::Face,
+    glyphs: &BTreeMap<u16, char>,
+) -> BTreeMap<u16, char> {
+    let mut mapping = BTreeMap::new();
+    for subtable in ttf.tables().cmap.into_iter().flat_map(|table| table.subtables) {
+        if subtable.is_unicode() {
+            subtable.codepoints(|n| {
+                if let Some(c) = std::char::from_u32(n) {
+                    if let Some(GlyphId(g)) = ttf.glyph_index(c) {
+                        if glyphs.contains_key(&g) {
+                            mapping.insert(g, c);
+                        }
+                    }
+                }
+            });
+        }
+    }
+
+    // Glyphs that aren't reachable through the font's own mapping (like
+    // ligatures) map to the first character of the cluster they were shaped
+    // from, so that every used glyph has an entry.
+    for (&g, &c) in glyphs {
+        mapping.entry(g).or_insert(c);
+    }
+
+    mapping
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const FONT: &[u8] = include_bytes!("../../../assets/fonts/InriaSerif-Regular.ttf");
+
+    #[test]
+    fn test_unicode_mapping_ligatures() {
+        let ttf = ttf_parser::Face::parse(FONT, 0).unwrap();
+
+        // The font has an "ft" ligature without a codepoint of its own.
+        let face = rustybuzz::Face::from_slice(FONT, 0).unwrap();
+        let mut buffer = rustybuzz::UnicodeBuffer::new();
+        buffer.push_str("ft");
+        let shaped = rustybuzz::shape(&face, &[], buffer);
+        let infos = shaped.glyph_infos();
+        assert_eq!(infos.len(), 1);
+        let ligature = infos[0].glyph_id as u16;
+
+        let GlyphId(x) = ttf.glyph_index('x').unwrap();
+        let glyphs = BTreeMap::from([(x, 'x'), (ligature, 'f')]);
+        assert_eq!(unicode_mapping(&ttf, &glyphs), glyphs);
+    }
+}
@@ -0,0 +1,240 @@
+use std::f64::consts::TAU;
+
+use ecow::{eco_format, EcoString};
+use pdf_writer::types::ShadingType;
+use pdf_writer::writers::ColorSpace;
+use pdf_writer::{Filter, Finish, Name, Ref};
+
+use super::{deflate, transform_to_array, PdfContext, RefExt};
+use crate::geom::{Color, ConicGradient, Gradient, Ratio, RelativeTo, Size, Transform};
+
+/// How many triangles to use for the mesh of a conic gradient.
+const CONIC_SEGMENTS: usize = 256;
+
+/// A gradient with the transform and bounding box it is painted with.
+#[derive(Debug, Clone, Eq, PartialEq, Hash)]
+pub struct PdfGradient {
+    /// The transform from the gradient's space into the page's default space.
+    pub transform: Transform,
+    /// The size of the bounding box the gradient is relative to.
+    pub size: Size,
+    /// The gradient itself.
+    pub gradient: Gradient,
+}
+
+/// The transforms and sizes relevant for painting an item with a gradient.
+#[derive(Debug, Copy, Clone)]
+pub struct Transforms {
+    /// The transform of the painted item's bounding box.
+    pub transform: Transform,
+    /// The size of the painted item's bounding box.
+    pub size: Size,
+    /// The transform of the item's container.
+    pub container_transform: Transform,
+    /// The size of the item's container.
+    pub container_size: Size,
+}
+
+impl Transforms {
+    /// The transform and size of the area that a paint with the given
+    /// relativity spans.
+    pub fn area(&self, relative: RelativeTo) -> (Transform, Size) {
+        match relative {
+            RelativeTo::Self_ => (self.transform, self.size),
+            RelativeTo::Parent => (self.container_transform, self.container_size),
+        }
+    }
+}
+
+/// Register a gradient for painting with the given transforms and return its
+/// resource name.
+pub fn register_gradient(
+    ctx: &mut PdfContext,
+    gradient: &Gradient,
+    on_text: bool,
+    transforms: Transforms,
+) -> EcoString {
+    let (transform, size) = transforms.area(gradient.unwrap_relative(on_text));
+
+    // Radial and conic gradients are defined in the unit square, which is
+    // stretched to the bounding box.
+    let transform = match gradient {
+        Gradient::Linear(_) => transform,
+        Gradient::Radial(_) | Gradient::Conic(_) => {
+            transform.pre_concat(Transform::scale(
+                Ratio::new(size.x.to_pt().max(f64::EPSILON)),
+                Ratio::new(size.y.to_pt().max(f64::EPSILON)),
+            ))
+        }
+    };
+
+    let pdf_gradient = PdfGradient { transform, size, gradient: gradient.clone() };
+    ctx.gradient_map.insert(pdf_gradient.clone());
+    eco_format!("Gr{}", ctx.gradient_map.map(pdf_gradient))
+}
+
+/// Write all gradients into shading patterns.
+pub fn write_gradients(ctx: &mut PdfContext) {
+    let gradients: Vec<PdfGradient> = ctx.gradient_map.items().cloned().collect();
+    for PdfGradient { transform, size, gradient } in gradients {
+        let pattern_ref = ctx.alloc.bump();
+        ctx.gradient_refs.push(pattern_ref);
+
+        match &gradient {
+            Gradient::Linear(_) | Gradient::Radial(_) => {
+                let function_ref = write_function(ctx, &gradient);
+                let mut pattern = ctx.writer.shading_pattern(pattern_ref);
+                let mut shading = pattern.shading();
+                shading.color_space().srgb();
+                shading.function(function_ref);
+                shading.extend([true, true]);
+
+                if let Gradient::Linear(linear) = &gradient {
+                    let (sin, cos) = (linear.angle.sin(), linear.angle.cos());
+                    let (w, h) = (size.x.to_pt(), size.y.to_pt());
+                    let length = (w * cos).abs() + (h * sin).abs();
+                    let (dx, dy) = (cos * length / 2.0, sin * length / 2.0);
+                    shading.shading_type(ShadingType::Axial);
+                    shading.coords(
+                        [w / 2.0 - dx, h / 2.0 - dy, w / 2.0 + dx, h / 2.0 + dy]
+                            .map(|v| v as f32),
+                    );
+                } else if let Gradient::Radial(radial) = &gradient {
+                    let (x, y) = (radial.center.x.get(), radial.center.y.get());
+                    shading.shading_type(ShadingType::Radial);
+                    shading
+                        .coords([x, y, 0.0, x, y, radial.radius.get()].map(|v| v as f32));
+                }
+
+                shading.finish();
+                pattern.matrix(transform_to_array(transform));
+            }
+            Gradient::Conic(conic) => {
+                let shading_ref = ctx.alloc.bump();
+                write_conic_mesh(ctx, shading_ref, &gradient, conic);
+                let mut pattern = ctx.writer.shading_pattern(pattern_ref);
+                pattern.pair(Name(b"Shading"), shading_ref);
+                pattern.matrix(transform_to_array(transform));
+            }
+        }
+    }
+}
+
+/// Write a stitching function that interpolates between the gradient's stops.
+fn write_function(ctx: &mut PdfContext, gradient: &Gradient) -> Ref {
+    let stops = gradient.srgb_stops();
+    let function_ref = ctx.alloc.bump();
+
+    let mut functions = vec![];
+    let mut bounds = vec![];
+    let mut encode = vec![];
+    for window in stops.windows(2) {
+        let (c0, o0) = window[0];
+        let (c1, o1) = window[1];
+        if o1 <= o0 {
+            continue;
+        }
+
+        let id = ctx.alloc.bump();
+        ctx.writer
+            .exponential_function(id)
+            .domain([0.0, 1.0])
+            .range([0.0, 1.0, 0.0, 1.0, 0.0, 1.0])
+            .c0(to_rgb(c0))
+            .c1(to_rgb(c1))
+            .n(1.0);
+
+        functions.push(id);
+        bounds.push(o1.get() as f32);
+        encode.extend([0.0, 1.0]);
+    }
+
+    // All stops are at the same offset, so we just need a single color.
+    if functions.is_empty() {
+        let id = ctx.alloc.bump();
+        let color = stops[stops.len() - 1].0;
+        ctx.writer
+            .exponential_function(id)
+            .domain([0.0, 1.0])
+            .range([0.0, 1.0, 0.0, 1.0, 0.0, 1.0])
+            .c0(to_rgb(color))
+            .c1(to_rgb(color))
+            .n(1.0);
+        functions.push(id);
+        bounds.push(1.0);
+        encode.extend([0.0, 1.0]);
+    }
+
+    // The bounds only separate the functions.
+    bounds.pop();
+
+    ctx.writer
+        .stitching_function(function_ref)
+        .domain([0.0, 1.0])
+        .range([0.0, 1.0, 0.0, 1.0, 0.0, 1.0])
+        .functions(functions)
+        .bounds(bounds)
+        .encode(encode);
+
+    function_ref
+}
+
+/// Write a conic gradient as a free-form triangle mesh around its center.
+fn write_conic_mesh(
+    ctx: &mut PdfContext,
+    shading_ref: Ref,
+    gradient: &Gradient,
+    conic: &ConicGradient,
+) {
+    // The mesh is defined in the unit square. A radius of two is enough to
+    // cover the whole square from any center within it.
+    const RADIUS: f64 = 2.0;
+    const MIN: f64 = -RADIUS;
+    const MAX: f64 = 1.0 + RADIUS;
+
+    let (cx, cy) = (conic.center.x.get(), conic.center.y.get());
+    let point = |t: f64| {
+        let angle = conic.angle.to_rad() + t * TAU;
+        (cx + RADIUS * angle.cos(), cy + RADIUS * angle.sin())
+    };
+
+    let mut data = vec![];
+    let mut vertex = |(x, y): (f64, f64), color: Color| {
+        let encode =
+            |v: f64| (((v - MIN) / (MAX - MIN)).clamp(0.0, 1.0) * 65535.0) as u16;
+        let c = color.to_rgba();
+        data.push(0);
+        data.extend(encode(x).to_be_bytes());
+        data.extend(encode(y).to_be_bytes());
+        data.extend([c.r, c.g, c.b]);
+    };
+
+    for i in 0..CONIC_SEGMENTS {
+        let t0 = i as f64 / CONIC_SEGMENTS as f64;
+        let t1 = (i + 1) as f64 / CONIC_SEGMENTS as f64;
+        let mid = gradient.sample(Ratio::new((t0 + t1) / 2.0));
+        vertex((cx, cy), mid);
+        vertex(point(t0), gradient.sample(Ratio::new(t0)));
+        vertex(point(t1), gradient.sample(Ratio::new(t1)));
+    }
+
+    let data = deflate(&data);
+    let mut shading = ctx.writer.stream(shading_ref, &data);
+    shading.filter(Filter::FlateDecode);
+    shading.pair(Name(b"ShadingType"), 4);
+    shading.insert(Name(b"ColorSpace")).start::<ColorSpace>().srgb();
+    shading.pair(Name(b"BitsPerCoordinate"), 16);
+    shading.pair(Name(b"BitsPerComponent"), 8);
+    shading.pair(Name(b"BitsPerFlag"), 8);
+    shading
+        .insert(Name(b"Decode"))
+        .array()
+        .items([MIN, MAX, MIN, MAX, 0.0, 1.0, 0.0, 1.0, 0.0, 1.0].map(|v| v as f32));
+    shading.finish();
+}
+
+/// Convert a color into sRGB components.
+fn to_rgb(color: Color) -> [f32; 3] {
+    let c = color.to_rgba();
+    [c.r, c.g, c.b].map(|v| v as f32 / 255.0)
+}
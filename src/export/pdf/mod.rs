@@ -1,37 +1,73 @@
 //! Exporting into PDF documents.
 
 mod font;
+mod gradient;
 mod image;
 mod outline;
 mod page;
+mod pattern;
+mod standard;
+mod structure;
 
 use std::cmp::Eq;
-use std::collections::{HashMap, HashSet};
+use std::collections::{BTreeMap, HashMap};
 use std::hash::Hash;
+use std::num::NonZeroUsize;
 
 use pdf_writer::types::Direction;
 use pdf_writer::{Finish, Name, PdfWriter, Ref, TextStr};
 use xmp_writer::{LangId, RenditionClass, XmpWriter};
 
+use self::gradient::PdfGradient;
 use self::outline::HeadingNode;
 use self::page::Page;
-use crate::doc::{Document, Lang};
+use self::pattern::PdfPattern;
+use self::structure::StructTree;
+use crate::diag::SourceResult;
+use crate::doc::{Document, Lang, PageLabel, PageLabelStyle};
 use crate::font::Font;
-use crate::geom::{Abs, Dir, Em};
+use crate::geom::{Abs, Dir, Em, Transform};
 use crate::image::Image;
 use crate::model::Introspector;
 
 /// Export a document into a PDF file.
 ///
-/// Returns the raw bytes making up the PDF file.
-pub fn pdf(document: &Document) -> Vec<u8> {
-    let mut ctx = PdfContext::new(document);
+/// Returns the raw bytes making up the PDF file or errors if the document
+/// cannot conform to the requested standard.
+pub fn pdf(document: &Document, options: &PdfOptions) -> SourceResult<Vec<u8>> {
+    if let Some(standard) = options.standard {
+        standard::validate(document, standard)?;
+    }
+
+    let mut ctx = PdfContext::new(document, options);
     page::construct_pages(&mut ctx, &document.pages);
+    pattern::write_patterns(&mut ctx);
     font::write_fonts(&mut ctx);
     image::write_images(&mut ctx);
+    gradient::write_gradients(&mut ctx);
     page::write_page_tree(&mut ctx);
     write_catalog(&mut ctx);
-    ctx.writer.finish()
+
+    let mut buf = ctx.writer.finish();
+    if options.standard.is_some() {
+        standard::insert_file_id(&mut buf);
+    }
+
+    Ok(buf)
+}
+
+/// Settings for PDF export.
+#[derive(Debug, Default, Clone, Eq, PartialEq, Hash)]
+pub struct PdfOptions {
+    /// The standard the file should conform to, if any.
+    pub standard: Option<PdfStandard>,
+}
+
+/// A standard that restricts which PDF features a file may use.
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
+pub enum PdfStandard {
+    /// PDF/A-2b, for long-term archival with reliable visual reproduction.
+    A2b,
 }
 
 /// Identifies the color space definitions.
@@ -41,42 +77,57 @@ const D65_GRAY: Name<'static> = Name(b"d65gray");
 /// Context for exporting a whole PDF document.
 pub struct PdfContext<'a> {
     document: &'a Document,
+    options: &'a PdfOptions,
     introspector: Introspector,
     writer: PdfWriter,
     pages: Vec<Page>,
     page_heights: Vec<f32>,
     alloc: Ref,
     page_tree_ref: Ref,
+    global_resources_ref: Ref,
     font_refs: Vec<Ref>,
     image_refs: Vec<Ref>,
+    gradient_refs: Vec<Ref>,
+    pattern_refs: Vec<Ref>,
     page_refs: Vec<Ref>,
     font_map: Remapper<Font>,
     image_map: Remapper<Image>,
-    glyph_sets: HashMap<Font, HashSet<u16>>,
+    gradient_map: Remapper<PdfGradient>,
+    pattern_map: Remapper<PdfPattern>,
+    glyph_sets: HashMap<Font, BTreeMap<u16, char>>,
     languages: HashMap<Lang, usize>,
     heading_tree: Vec<HeadingNode>,
+    struct_tree: StructTree,
 }
 
 impl<'a> PdfContext<'a> {
-    fn new(document: &'a Document) -> Self {
+    fn new(document: &'a Document, options: &'a PdfOptions) -> Self {
         let mut alloc = Ref::new(1);
         let page_tree_ref = alloc.bump();
+        let global_resources_ref = alloc.bump();
         Self {
             document,
+            options,
             introspector: Introspector::new(&document.pages),
             writer: PdfWriter::new(),
             pages: vec![],
             page_heights: vec![],
             alloc,
             page_tree_ref,
+            global_resources_ref,
             page_refs: vec![],
             font_refs: vec![],
             image_refs: vec![],
+            gradient_refs: vec![],
+            pattern_refs: vec![],
             font_map: Remapper::new(),
             image_map: Remapper::new(),
+            gradient_map: Remapper::new(),
+            pattern_map: Remapper::new(),
             glyph_sets: HashMap::new(),
             languages: HashMap::new(),
             heading_tree: vec![],
+            struct_tree: StructTree::default(),
         }
     }
 }
@@ -127,9 +178,16 @@ fn write_catalog(ctx: &mut PdfContext) {
     }
 
     let authors = &ctx.document.author;
+    let joined = authors.join(", ");
     if !authors.is_empty() {
-        info.author(TextStr(&authors.join(", ")));
-        xmp.creator(authors.iter().map(|s| s.as_str()));
+        info.author(TextStr(&joined));
+        // PDF/A requires the author in the document information to match the
+        // XMP metadata.
+        if ctx.options.standard.is_some() {
+            xmp.creator([joined.as_str()]);
+        } else {
+            xmp.creator(authors.iter().map(|s| s.as_str()));
+        }
     }
     info.creator(TextStr("Typst"));
     info.finish();
@@ -140,6 +198,11 @@ fn write_catalog(ctx: &mut PdfContext) {
     xmp.rendition_class(RenditionClass::Proof);
     xmp.pdf_version("1.7");
 
+    if let Some(standard) = ctx.options.standard {
+        xmp.pdfa_part(standard.part());
+        xmp.pdfa_conformance(standard.conformance());
+    }
+
     let xmp_buf = xmp.finish(None);
     let meta_ref = ctx.alloc.bump();
     let mut meta_stream = ctx.writer.stream(meta_ref, xmp_buf.as_bytes());
@@ -147,11 +210,22 @@ fn write_catalog(ctx: &mut PdfContext) {
     meta_stream.pair(Name(b"Subtype"), Name(b"XML"));
     meta_stream.finish();
 
+    let page_labels = write_page_labels(ctx);
+    let output_profile =
+        ctx.options.standard.map(|_| standard::write_output_profile(ctx));
+    let struct_tree_root = structure::write_struct_tree(ctx);
+
     // Write the document catalog.
     let mut catalog = ctx.writer.catalog(ctx.alloc.bump());
     catalog.pages(ctx.page_tree_ref);
     catalog.viewer_preferences().direction(dir);
     catalog.pair(Name(b"Metadata"), meta_ref);
+    catalog.pair(Name(b"StructTreeRoot"), struct_tree_root);
+    catalog.insert(Name(b"MarkInfo")).dict().pair(Name(b"Marked"), true);
+
+    if let Some(page_labels) = page_labels {
+        catalog.pair(Name(b"PageLabels"), page_labels);
+    }
 
     if let Some(outline_root_id) = outline_root_id {
         catalog.outlines(outline_root_id);
@@ -160,6 +234,72 @@ fn write_catalog(ctx: &mut PdfContext) {
     if let Some(lang) = lang {
         catalog.lang(TextStr(lang.as_str()));
     }
+
+    if let Some(profile) = output_profile {
+        let mut intents = catalog.insert(Name(b"OutputIntents")).array();
+        let mut intent = intents.push().dict();
+        intent.pair(Name(b"Type"), Name(b"OutputIntent"));
+        intent.pair(Name(b"S"), Name(b"GTS_PDFA1"));
+        intent.pair(Name(b"OutputConditionIdentifier"), TextStr("sRGB"));
+        intent.pair(Name(b"Info"), TextStr("sRGB IEC61966-2.1"));
+        intent.pair(Name(b"DestOutputProfile"), profile);
+    }
+}
+
+/// Write the page labels as a number tree.
+///
+/// Only the pages that start a new labelling range get an entry. Pages without
+/// a label are labelled with their physical page number.
+fn write_page_labels(ctx: &mut PdfContext) -> Option<Ref> {
+    let labels = &ctx.document.page_labels;
+    if labels.iter().all(Option::is_none) {
+        return None;
+    }
+
+    let mut ranges: Vec<(usize, PageLabel)> = vec![];
+    let mut prev: Option<PageLabel> = None;
+    for i in 0..ctx.document.pages.len() {
+        let label = labels
+            .get(i)
+            .cloned()
+            .flatten()
+            .unwrap_or_else(|| PageLabel::arabic(NonZeroUsize::new(i + 1).unwrap()));
+
+        if !prev.as_ref().map_or(false, |prev| label.continues(prev)) {
+            ranges.push((i, label.clone()));
+        }
+
+        prev = Some(label);
+    }
+
+    let id = ctx.alloc.bump();
+    let mut tree = ctx.writer.indirect(id).dict();
+    let mut nums = tree.insert(Name(b"Nums")).array();
+    for (i, label) in ranges {
+        nums.item(i as i32);
+        let mut entry = nums.push().dict();
+        entry.pair(Name(b"Type"), Name(b"PageLabel"));
+        if let Some(style) = label.style {
+            entry.pair(
+                Name(b"S"),
+                Name(match style {
+                    PageLabelStyle::Arabic => b"D",
+                    PageLabelStyle::LowerRoman => b"r",
+                    PageLabelStyle::UpperRoman => b"R",
+                    PageLabelStyle::LowerAlpha => b"a",
+                    PageLabelStyle::UpperAlpha => b"A",
+                }),
+            );
+        }
+        if let Some(prefix) = &label.prefix {
+            entry.pair(Name(b"P"), TextStr(prefix));
+        }
+        if let Some(number) = label.number.filter(|number| number.get() > 1) {
+            entry.pair(Name(b"St"), number.get() as i32);
+        }
+    }
+
+    Some(id)
 }
 
 /// Compress data with the DEFLATE algorithm.
@@ -207,6 +347,20 @@ where
     fn items(&self) -> impl Iterator<Item = &T> + '_ {
         self.to_items.iter()
     }
+
+    fn len(&self) -> usize {
+        self.to_items.len()
+    }
+
+    fn get(&self, pdf_index: usize) -> &T {
+        &self.to_items[pdf_index]
+    }
+}
+
+/// Convert a transform into a PDF matrix.
+fn transform_to_array(transform: Transform) -> [f32; 6] {
+    let Transform { sx, ky, kx, sy, tx, ty } = transform;
+    [sx.get() as _, ky.get() as _, kx.get() as _, sy.get() as _, tx.to_f32(), ty.to_f32()]
 }
 
 /// Additional methods for [`Abs`].
@@ -246,3 +400,57 @@ impl RefExt for Ref {
         prev
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::doc::Frame;
+    use crate::geom::Size;
+
+    #[test]
+    fn test_pdf_page_labels() {
+        let label = |prefix: Option<&str>, style, number| PageLabel {
+            prefix: prefix.map(Into::into),
+            style: Some(style),
+            number: NonZeroUsize::new(number),
+        };
+
+        let document = Document {
+            pages: vec![Frame::new(Size::zero()); 6],
+            page_labels: vec![
+                Some(label(None, PageLabelStyle::LowerRoman, 1)),
+                Some(label(None, PageLabelStyle::LowerRoman, 2)),
+                Some(label(None, PageLabelStyle::Arabic, 1)),
+                Some(label(Some("A-"), PageLabelStyle::UpperAlpha, 3)),
+                Some(PageLabel::text("Back")),
+                None,
+            ],
+            ..Default::default()
+        };
+
+        let buf = pdf(&document, &PdfOptions::default()).unwrap();
+        let text = String::from_utf8_lossy(&buf);
+        let text = text.split_whitespace().collect::<Vec<_>>().join(" ");
+        assert!(text.contains("/PageLabels"));
+        assert!(text.contains(
+            "/Nums [\
+             0 << /Type /PageLabel /S /r >> \
+             2 << /Type /PageLabel /S /D >> \
+             3 << /Type /PageLabel /S /A /P (A-) /St 3 >> \
+             4 << /Type /PageLabel /P (Back) >> \
+             5 << /Type /PageLabel /S /D /St 6 >>]"
+        ));
+    }
+
+    #[test]
+    fn test_pdf_without_page_labels() {
+        let document = Document {
+            pages: vec![Frame::new(Size::zero()); 2],
+            page_labels: vec![None, None],
+            ..Default::default()
+        };
+
+        let buf = pdf(&document, &PdfOptions::default()).unwrap();
+        assert!(!String::from_utf8_lossy(&buf).contains("/PageLabels"));
+    }
+}
@@ -1,14 +1,20 @@
 use ecow::eco_format;
-use pdf_writer::types::{ActionType, AnnotationType, ColorSpaceOperand};
-use pdf_writer::writers::ColorSpace;
+use pdf_writer::types::{
+    ActionType, AnnotationFlags, AnnotationType, ColorSpaceOperand, LineCapStyle,
+    LineJoinStyle,
+};
+use pdf_writer::writers::{Annotation, ColorSpace, Resources};
 use pdf_writer::{Content, Filter, Finish, Name, Rect, Ref, Str};
 
+use super::gradient::{register_gradient, Transforms};
+use super::pattern::register_pattern;
+use super::structure::role_name;
 use super::{deflate, AbsExt, EmExt, PdfContext, RefExt, D65_GRAY, SRGB};
-use crate::doc::{Destination, Frame, FrameItem, GroupItem, Meta, TextItem};
+use crate::doc::{Destination, Frame, FrameItem, GroupItem, Meta, Role, Tag, TextItem};
 use crate::font::Font;
 use crate::geom::{
-    self, Abs, Color, Em, Geometry, Numeric, Paint, Point, Ratio, Shape, Size, Stroke,
-    Transform,
+    self, Abs, Color, Em, Geometry, LineCap, LineJoin, Numeric, Paint, Point, Ratio,
+    Shape, Size, Stroke, Transform,
 };
 use crate::image::Image;
 
@@ -22,17 +28,19 @@ pub fn construct_pages(ctx: &mut PdfContext, frames: &[Frame]) {
 /// Construct a page object.
 pub fn construct_page(ctx: &mut PdfContext, frame: &Frame) {
     let page_ref = ctx.alloc.bump();
+    let index = ctx.page_refs.len();
     ctx.page_refs.push(page_ref);
     ctx.page_heights.push(frame.height().to_f32());
 
     let mut ctx = PageContext {
         parent: ctx,
-        page_ref,
         content: Content::new(),
         state: State::default(),
         saves: vec![],
         bottom: 0.0,
         links: vec![],
+        page: Some(index),
+        tags: vec![],
     };
 
     let size = frame.size();
@@ -48,31 +56,62 @@ pub fn construct_page(ctx: &mut PdfContext, frame: &Frame) {
         ty: size.y,
     });
 
+    // The page is the container of its top-level items.
+    ctx.state.size = size;
+    ctx.state.container_transform = ctx.state.transform;
+
     // Encode the page into the content stream.
     write_frame(&mut ctx, frame);
 
     let page = Page {
         size,
         content: ctx.content,
-        id: ctx.page_ref,
+        id: page_ref,
         links: ctx.links,
     };
 
     ctx.parent.pages.push(page);
 }
 
+/// Encode a frame into a standalone content stream, e.g. for the tile of a
+/// pattern. The frame's own coordinate system is used as is.
+pub fn write_frame_content(ctx: &mut PdfContext, frame: &Frame) -> Vec<u8> {
+    let mut ctx = PageContext {
+        parent: ctx,
+        content: Content::new(),
+        state: State::default(),
+        saves: vec![],
+        bottom: frame.height().to_f32(),
+        links: vec![],
+        page: None,
+        tags: vec![],
+    };
+
+    ctx.state.size = frame.size();
+    write_frame(&mut ctx, frame);
+    ctx.content.finish()
+}
+
 /// Write the page tree.
 pub fn write_page_tree(ctx: &mut PdfContext) {
-    for page in std::mem::take(&mut ctx.pages).into_iter() {
-        write_page(ctx, page);
+    for (i, page) in std::mem::take(&mut ctx.pages).into_iter().enumerate() {
+        write_page(ctx, i, page);
     }
 
     let mut pages = ctx.writer.pages(ctx.page_tree_ref);
     pages
         .count(ctx.page_refs.len() as i32)
-        .kids(ctx.page_refs.iter().copied());
+        .kids(ctx.page_refs.iter().copied())
+        .pair(Name(b"Resources"), ctx.global_resources_ref);
+    pages.finish();
 
-    let mut resources = pages.resources();
+    write_global_resources(ctx);
+}
+
+/// Write the resource dictionary that is shared by all pages and patterns.
+fn write_global_resources(ctx: &mut PdfContext) {
+    let mut resources =
+        ctx.writer.indirect(ctx.global_resources_ref).start::<Resources>();
     let mut spaces = resources.color_spaces();
     spaces.insert(SRGB).start::<ColorSpace>().srgb();
     spaces.insert(D65_GRAY).start::<ColorSpace>().d65_gray();
@@ -93,13 +132,26 @@ pub fn write_page_tree(ctx: &mut PdfContext) {
     }
 
     images.finish();
+
+    let mut patterns = resources.patterns();
+    for (gradient_ref, gr) in ctx.gradient_map.pdf_indices(&ctx.gradient_refs) {
+        let name = eco_format!("Gr{}", gr);
+        patterns.pair(Name(name.as_bytes()), gradient_ref);
+    }
+
+    for (pattern_ref, p) in ctx.pattern_map.pdf_indices(&ctx.pattern_refs) {
+        let name = eco_format!("P{}", p);
+        patterns.pair(Name(name.as_bytes()), pattern_ref);
+    }
+
+    patterns.finish();
     resources.finish();
-    pages.finish();
 }
 
 /// Write a page tree node.
-fn write_page(ctx: &mut PdfContext, page: Page) {
+fn write_page(ctx: &mut PdfContext, index: usize, page: Page) {
     let content_id = ctx.alloc.bump();
+    let annotation_refs: Vec<_> = page.links.iter().map(|_| ctx.alloc.bump()).collect();
 
     let mut page_writer = ctx.writer.page(page.id);
     page_writer.parent(ctx.page_tree_ref);
@@ -108,12 +160,29 @@ fn write_page(ctx: &mut PdfContext, page: Page) {
     let h = page.size.y.to_f32();
     page_writer.media_box(Rect::new(0.0, 0.0, w, h));
     page_writer.contents(content_id);
+    page_writer.pair(Name(b"StructParents"), index as i32);
+
+    // Let assistive technology visit the links in the order of the structure.
+    if !annotation_refs.is_empty() {
+        page_writer.pair(Name(b"Tabs"), Name(b"S"));
+        page_writer
+            .insert(Name(b"Annots"))
+            .array()
+            .items(annotation_refs.iter().copied());
+    }
+
+    page_writer.finish();
 
-    let mut annotations = page_writer.annotations();
-    for (dest, rect) in page.links {
-        let mut annotation = annotations.push();
+    for ((dest, rect, elem), annotation_ref) in
+        page.links.into_iter().zip(annotation_refs)
+    {
+        let key =
+            ctx.page_refs.len() + ctx.struct_tree.annotate(index, annotation_ref, elem);
+        let mut annotation = ctx.writer.indirect(annotation_ref).start::<Annotation>();
         annotation.subtype(AnnotationType::Link).rect(rect);
         annotation.border(0.0, 0.0, 0.0, None);
+        annotation.flags(AnnotationFlags::PRINT);
+        annotation.pair(Name(b"StructParent"), key as i32);
 
         let pos = match dest {
             Destination::Url(uri) => {
@@ -139,9 +208,6 @@ fn write_page(ctx: &mut PdfContext, page: Page) {
         }
     }
 
-    annotations.finish();
-    page_writer.finish();
-
     let data = page.content.finish();
     let data = deflate(&data);
     ctx.writer.stream(content_id, &data).filter(Filter::FlateDecode);
@@ -155,19 +221,25 @@ pub struct Page {
     pub size: Size,
     /// The page's content stream.
     pub content: Content,
-    /// Links in the PDF coordinate system.
-    pub links: Vec<(Destination, Rect)>,
+    /// Links in the PDF coordinate system and the structure elements they
+    /// belong to.
+    pub links: Vec<(Destination, Rect, Option<usize>)>,
 }
 
 /// An exporter for the contents of a single PDF page.
 struct PageContext<'a, 'b> {
     parent: &'a mut PdfContext<'b>,
-    page_ref: Ref,
     content: Content,
     state: State,
     saves: Vec<State>,
     bottom: f32,
-    links: Vec<(Destination, Rect)>,
+    links: Vec<(Destination, Rect, Option<usize>)>,
+    /// The index of the page, or `None` if the content doesn't belong to a
+    /// page and can't be part of the document's structure.
+    page: Option<usize>,
+    /// The structure tags of the innermost tagged frame, from the outermost
+    /// to the innermost element.
+    tags: Vec<Tag>,
 }
 
 /// A simulated graphics state used to deduplicate graphics state changes and
@@ -175,6 +247,8 @@ struct PageContext<'a, 'b> {
 #[derive(Debug, Default, Clone)]
 struct State {
     transform: Transform,
+    container_transform: Transform,
+    size: Size,
     font: Option<(Font, Abs)>,
     fill: Option<Paint>,
     fill_space: Option<Name<'static>>,
@@ -182,6 +256,18 @@ struct State {
     stroke_space: Option<Name<'static>>,
 }
 
+impl State {
+    /// The transforms for painting an item with the given bounding box.
+    fn transforms(&self, pos: Point, size: Size) -> Transforms {
+        Transforms {
+            transform: self.transform.pre_concat(Transform::translate(pos.x, pos.y)),
+            size,
+            container_transform: self.container_transform,
+            container_size: self.size,
+        }
+    }
+}
+
 impl PageContext<'_, '_> {
     fn save_state(&mut self) {
         self.saves.push(self.state.clone());
@@ -215,25 +301,41 @@ impl PageContext<'_, '_> {
         }
     }
 
-    fn set_fill(&mut self, fill: Paint) {
-        if self.state.fill != Some(fill) {
+    fn set_fill(&mut self, fill: &Paint, on_text: bool, transforms: Transforms) {
+        // Gradients and patterns depend on the transforms, so they are always
+        // written.
+        let is_solid = matches!(fill, Paint::Solid(_));
+        if self.state.fill.as_ref() != Some(fill) || !is_solid {
             let f = |c| c as f32 / 255.0;
-            let Paint::Solid(color) = fill;
-            match color {
-                Color::Luma(c) => {
+            match fill {
+                Paint::Solid(Color::Luma(c)) => {
                     self.set_fill_color_space(D65_GRAY);
                     self.content.set_fill_gray(f(c.0));
                 }
-                Color::Rgba(c) => {
+                Paint::Solid(Color::Rgba(c)) => {
                     self.set_fill_color_space(SRGB);
                     self.content.set_fill_color([f(c.r), f(c.g), f(c.b)]);
                 }
-                Color::Cmyk(c) => {
+                Paint::Solid(Color::Cmyk(c)) => {
                     self.reset_fill_color_space();
                     self.content.set_fill_cmyk(f(c.c), f(c.m), f(c.y), f(c.k));
                 }
+                Paint::Gradient(gradient) => {
+                    let name =
+                        register_gradient(self.parent, gradient, on_text, transforms);
+                    self.content.set_fill_color_space(ColorSpaceOperand::Pattern);
+                    self.content.set_fill_pattern(None, Name(name.as_bytes()));
+                    self.reset_fill_color_space();
+                }
+                Paint::Pattern(pattern) => {
+                    let name =
+                        register_pattern(self.parent, pattern, on_text, transforms);
+                    self.content.set_fill_color_space(ColorSpaceOperand::Pattern);
+                    self.content.set_fill_pattern(None, Name(name.as_bytes()));
+                    self.reset_fill_color_space();
+                }
             }
-            self.state.fill = Some(fill);
+            self.state.fill = Some(fill.clone());
         }
     }
 
@@ -248,27 +350,62 @@ impl PageContext<'_, '_> {
         self.state.fill_space = None;
     }
 
-    fn set_stroke(&mut self, stroke: Stroke) {
-        if self.state.stroke != Some(stroke) {
+    fn set_stroke(&mut self, stroke: &Stroke, transforms: Transforms) {
+        // Gradients and patterns depend on the transforms, so they are always
+        // written.
+        let is_solid = matches!(stroke.paint, Paint::Solid(_));
+        if self.state.stroke.as_ref() != Some(stroke) || !is_solid {
             let f = |c| c as f32 / 255.0;
-            let Paint::Solid(color) = stroke.paint;
-            match color {
-                Color::Luma(c) => {
+            match &stroke.paint {
+                Paint::Solid(Color::Luma(c)) => {
                     self.set_stroke_color_space(D65_GRAY);
                     self.content.set_stroke_gray(f(c.0));
                 }
-                Color::Rgba(c) => {
+                Paint::Solid(Color::Rgba(c)) => {
                     self.set_stroke_color_space(SRGB);
                     self.content.set_stroke_color([f(c.r), f(c.g), f(c.b)]);
                 }
-                Color::Cmyk(c) => {
+                Paint::Solid(Color::Cmyk(c)) => {
                     self.reset_stroke_color_space();
                     self.content.set_stroke_cmyk(f(c.c), f(c.m), f(c.y), f(c.k));
                 }
+                Paint::Gradient(gradient) => {
+                    let name =
+                        register_gradient(self.parent, gradient, false, transforms);
+                    self.content.set_stroke_color_space(ColorSpaceOperand::Pattern);
+                    self.content.set_stroke_pattern(None, Name(name.as_bytes()));
+                    self.reset_stroke_color_space();
+                }
+                Paint::Pattern(pattern) => {
+                    let name = register_pattern(self.parent, pattern, false, transforms);
+                    self.content.set_stroke_color_space(ColorSpaceOperand::Pattern);
+                    self.content.set_stroke_pattern(None, Name(name.as_bytes()));
+                    self.reset_stroke_color_space();
+                }
             }
 
+            let prev = self.state.stroke.as_ref();
             self.content.set_line_width(stroke.thickness.to_f32());
-            self.state.stroke = Some(stroke);
+            if prev.map(|s| s.line_cap) != Some(stroke.line_cap) {
+                self.content.set_line_cap(stroke.line_cap.into());
+            }
+            if prev.map(|s| s.line_join) != Some(stroke.line_join) {
+                self.content.set_line_join(stroke.line_join.into());
+            }
+            if prev.map(|s| &s.dash_pattern) != Some(&stroke.dash_pattern) {
+                if let Some(pattern) = &stroke.dash_pattern {
+                    self.content.set_dash_pattern(
+                        pattern.array.iter().map(|l| l.to_f32()),
+                        pattern.phase.to_f32(),
+                    );
+                } else {
+                    self.content.set_dash_pattern([], 0.0);
+                }
+            }
+            if prev.map(|s| s.miter_limit) != Some(stroke.miter_limit) {
+                self.content.set_miter_limit(stroke.miter_limit.0 as f32);
+            }
+            self.state.stroke = Some(stroke.clone());
         }
     }
 
@@ -286,21 +423,84 @@ impl PageContext<'_, '_> {
 
 /// Encode a frame into the content stream.
 fn write_frame(ctx: &mut PageContext, frame: &Frame) {
+    // The tags of a frame apply to all of its items, so they replace the tags
+    // of the enclosing frames. Since tags are inherited through styles, they
+    // already include those of the enclosing elements.
+    let mut tags: Vec<Tag> = vec![];
+    for (_, item) in frame.items() {
+        if let FrameItem::Meta(Meta::Tag(tag), _) = item {
+            if !tags.iter().any(|prev| prev.location == tag.location) {
+                tags.push(tag.clone());
+            }
+        }
+    }
+
+    let outer = (!tags.is_empty()).then(|| std::mem::replace(&mut ctx.tags, tags));
+
     for &(pos, ref item) in frame.items() {
         let x = pos.x.to_f32();
         let y = pos.y.to_f32();
         match item {
             FrameItem::Group(group) => write_group(ctx, pos, group),
-            FrameItem::Text(text) => write_text(ctx, x, y, text),
-            FrameItem::Shape(shape, _) => write_shape(ctx, x, y, shape),
-            FrameItem::Image(image, size, _) => write_image(ctx, x, y, image, *size),
+            FrameItem::Text(text) => {
+                write_marked(ctx, false, |ctx| write_text(ctx, pos, text))
+            }
+            FrameItem::Shape(shape, _) => {
+                if shape.fill.is_some() || shape.stroke.is_some() {
+                    write_marked(ctx, true, |ctx| write_shape(ctx, pos, shape))
+                }
+            }
+            FrameItem::Image(image, size, _) => {
+                write_marked(ctx, false, |ctx| write_image(ctx, x, y, image, *size))
+            }
             FrameItem::Meta(meta, size) => match meta {
                 Meta::Link(dest) => write_link(ctx, pos, dest, *size),
                 Meta::Elem(_) => {}
+                Meta::Tag(_) => {}
                 Meta::Hide => {}
             },
         }
     }
+
+    if let Some(outer) = outer {
+        ctx.tags = outer;
+    }
+}
+
+/// Encode an item into the content stream as a marked-content sequence that
+/// associates it with its element in the document's structure.
+///
+/// Page headers and footers and, unless they are part of a figure, shapes
+/// are marked as artifacts so that assistive technology skips them.
+fn write_marked(
+    ctx: &mut PageContext,
+    decorative: bool,
+    f: impl FnOnce(&mut PageContext),
+) {
+    let Some(page) = ctx.page else {
+        f(ctx);
+        return;
+    };
+
+    let innermost = ctx.tags.last().map(|tag| &tag.role);
+    if ctx.tags.iter().any(|tag| tag.role == Role::Artifact)
+        || (decorative && !matches!(innermost, Some(Role::Figure(_))))
+    {
+        ctx.content.begin_marked_content(Name(b"Artifact"));
+        f(ctx);
+        ctx.content.end_marked_content();
+        return;
+    }
+
+    let name = innermost.map_or(Name(b"Span"), role_name);
+    let elem = ctx.parent.struct_tree.open(&ctx.tags);
+    let mcid = ctx.parent.struct_tree.mark(page, elem);
+    let mut op = ctx.content.op("BDC");
+    op.operand(name);
+    op.obj().dict().pair(Name(b"MCID"), mcid);
+    drop(op);
+    f(ctx);
+    ctx.content.end_marked_content();
 }
 
 /// Encode a group into the content stream.
@@ -309,6 +509,8 @@ fn write_group(ctx: &mut PageContext, pos: Point, group: &GroupItem) {
 
     ctx.save_state();
     ctx.transform(translation.pre_concat(group.transform));
+    ctx.state.size = group.frame.size();
+    ctx.state.container_transform = ctx.state.transform;
 
     if group.clips {
         let size = group.frame.size();
@@ -327,19 +529,23 @@ fn write_group(ctx: &mut PageContext, pos: Point, group: &GroupItem) {
 }
 
 /// Encode a text run into the content stream.
-fn write_text(ctx: &mut PageContext, x: f32, y: f32, text: &TextItem) {
+fn write_text(ctx: &mut PageContext, pos: Point, text: &TextItem) {
     *ctx.parent.languages.entry(text.lang).or_insert(0) += text.glyphs.len();
     ctx.parent
         .glyph_sets
         .entry(text.font.clone())
         .or_default()
-        .extend(text.glyphs.iter().map(|g| g.id));
+        .extend(text.glyphs.iter().map(|g| (g.id, g.c)));
 
-    ctx.set_fill(text.fill);
+    let size = Size::new(text.width(), text.size);
+    let transforms = ctx.state.transforms(Point::new(pos.x, pos.y - text.size), size);
+    ctx.set_fill(&text.fill, true, transforms);
     ctx.set_font(&text.font, text.size);
     ctx.content.begin_text();
 
     // Positiosn the text.
+    let x = pos.x.to_f32();
+    let y = pos.y.to_f32();
     ctx.content.set_text_matrix([1.0, 0.0, 0.0, -1.0, x, y]);
 
     let mut positioned = ctx.content.show_positioned();
@@ -381,19 +587,23 @@ fn write_text(ctx: &mut PageContext, x: f32, y: f32, text: &TextItem) {
 }
 
 /// Encode a geometrical shape into the content stream.
-fn write_shape(ctx: &mut PageContext, x: f32, y: f32, shape: &Shape) {
+fn write_shape(ctx: &mut PageContext, pos: Point, shape: &Shape) {
     if shape.fill.is_none() && shape.stroke.is_none() {
         return;
     }
 
-    if let Some(fill) = shape.fill {
-        ctx.set_fill(fill);
+    let transforms = ctx.state.transforms(pos, shape.geometry.bbox_size());
+    if let Some(fill) = &shape.fill {
+        ctx.set_fill(fill, false, transforms);
     }
 
-    if let Some(stroke) = shape.stroke {
-        ctx.set_stroke(stroke);
+    if let Some(stroke) = &shape.stroke {
+        ctx.set_stroke(stroke, transforms);
     }
 
+    let x = pos.x.to_f32();
+    let y = pos.y.to_f32();
+
     match shape.geometry {
         Geometry::Line(target) => {
             let dx = target.x.to_f32();
@@ -413,7 +623,7 @@ fn write_shape(ctx: &mut PageContext, x: f32, y: f32, shape: &Shape) {
         }
     }
 
-    match (shape.fill, shape.stroke) {
+    match (&shape.fill, &shape.stroke) {
         (None, None) => unreachable!(),
         (Some(_), None) => ctx.content.fill_nonzero(),
         (None, Some(_)) => ctx.content.stroke(),
@@ -483,5 +693,35 @@ fn write_link(ctx: &mut PageContext, pos: Point, dest: &Destination, size: Size)
     let y2 = min_y.to_f32();
     let rect = Rect::new(x1, y1, x2, y2);
 
-    ctx.links.push((dest.clone(), rect));
+    // Associate the link annotation with the innermost link element. Links in
+    // artifacts belong directly to the document.
+    let artifact = ctx.tags.iter().any(|tag| tag.role == Role::Artifact);
+    let elem = match ctx.tags.iter().rposition(|tag| tag.role == Role::Link) {
+        Some(i) if ctx.page.is_some() && !artifact => {
+            ctx.parent.struct_tree.open(&ctx.tags[..=i])
+        }
+        _ => None,
+    };
+
+    ctx.links.push((dest.clone(), rect, elem));
+}
+
+impl From<LineCap> for LineCapStyle {
+    fn from(line_cap: LineCap) -> Self {
+        match line_cap {
+            LineCap::Butt => LineCapStyle::ButtCap,
+            LineCap::Round => LineCapStyle::RoundCap,
+            LineCap::Square => LineCapStyle::ProjectingSquareCap,
+        }
+    }
+}
+
+impl From<LineJoin> for LineJoinStyle {
+    fn from(line_join: LineJoin) -> Self {
+        match line_join {
+            LineJoin::Miter => LineJoinStyle::MiterJoin,
+            LineJoin::Round => LineJoinStyle::RoundJoin,
+            LineJoin::Bevel => LineJoinStyle::BevelJoin,
+        }
+    }
 }
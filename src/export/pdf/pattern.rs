@@ -0,0 +1,75 @@
+use std::collections::HashMap;
+
+use ecow::{eco_format, EcoString};
+use pdf_writer::types::{PaintType, TilingType};
+use pdf_writer::{Filter, Finish, Name, Rect};
+
+use super::gradient::Transforms;
+use super::page::write_frame_content;
+use super::{deflate, transform_to_array, AbsExt, PdfContext, RefExt};
+use crate::geom::{Pattern, Transform};
+
+/// A pattern with the transform it is painted with.
+#[derive(Debug, Clone, Eq, PartialEq, Hash)]
+pub struct PdfPattern {
+    /// The transform from the pattern's tile space into the page's default
+    /// space.
+    pub transform: Transform,
+    /// The pattern itself.
+    pub pattern: Pattern,
+}
+
+/// Register a pattern for painting with the given transforms and return its
+/// resource name.
+pub fn register_pattern(
+    ctx: &mut PdfContext,
+    pattern: &Pattern,
+    on_text: bool,
+    transforms: Transforms,
+) -> EcoString {
+    let (transform, _) = transforms.area(pattern.unwrap_relative(on_text));
+    let pdf_pattern = PdfPattern { transform, pattern: pattern.clone() };
+    ctx.pattern_map.insert(pdf_pattern.clone());
+    eco_format!("P{}", ctx.pattern_map.map(pdf_pattern))
+}
+
+/// Write all patterns into tiling patterns.
+///
+/// This must happen before fonts and images are written because the tiles
+/// may use additional glyphs and images. Tiles can also contain patterns
+/// themselves, which are then written in the same pass.
+pub fn write_patterns(ctx: &mut PdfContext) {
+    let mut contents: HashMap<Pattern, Vec<u8>> = HashMap::new();
+
+    let mut i = 0;
+    while i < ctx.pattern_map.len() {
+        let PdfPattern { transform, pattern } = ctx.pattern_map.get(i).clone();
+        i += 1;
+
+        let content = match contents.get(&pattern) {
+            Some(content) => content.clone(),
+            None => {
+                let content = deflate(&write_frame_content(ctx, pattern.frame()));
+                contents.insert(pattern.clone(), content.clone());
+                content
+            }
+        };
+
+        let pattern_ref = ctx.alloc.bump();
+        ctx.pattern_refs.push(pattern_ref);
+
+        let size = pattern.size();
+        let step = pattern.step();
+        let mut tiling = ctx.writer.tiling_pattern(pattern_ref, &content);
+        tiling
+            .paint_type(PaintType::Colored)
+            .tiling_type(TilingType::ConstantSpacing)
+            .bbox(Rect::new(0.0, 0.0, size.x.to_f32(), size.y.to_f32()))
+            .x_step(step.x.to_f32())
+            .y_step(step.y.to_f32())
+            .matrix(transform_to_array(transform));
+        tiling.pair(Name(b"Resources"), ctx.global_resources_ref);
+        tiling.filter(Filter::FlateDecode);
+        tiling.finish();
+    }
+}
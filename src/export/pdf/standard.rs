@@ -0,0 +1,248 @@
+use ecow::eco_format;
+use pdf_writer::{Filter, Finish, Name, Ref};
+
+use super::{deflate, PdfContext, PdfStandard, RefExt};
+use crate::diag::{SourceDiagnostic, SourceResult};
+use crate::doc::{Document, Frame, FrameItem};
+use crate::geom::{Color, Paint};
+use crate::syntax::Span;
+use crate::util::hash128;
+
+/// The sRGB profile that archival documents declare as their output intent.
+const SRGB_ICC: &[u8] = include_bytes!("icc/sRGB-v4.icc");
+
+impl PdfStandard {
+    /// The `pdfaid:part` of the standard.
+    pub(super) fn part(self) -> &'static str {
+        match self {
+            Self::A2b => "2",
+        }
+    }
+
+    /// The `pdfaid:conformance` level of the standard.
+    pub(super) fn conformance(self) -> &'static str {
+        match self {
+            Self::A2b => "B",
+        }
+    }
+
+    /// The human-readable name of the standard.
+    fn name(self) -> &'static str {
+        match self {
+            Self::A2b => "PDF/A-2b",
+        }
+    }
+}
+
+/// Check that the document's content can be expressed within the standard.
+///
+/// Reports all violations at once instead of stopping at the first one.
+pub fn validate(document: &Document, standard: PdfStandard) -> SourceResult<()> {
+    let mut errors = vec![];
+    for frame in &document.pages {
+        validate_frame(&mut errors, frame, standard);
+    }
+
+    if errors.is_empty() {
+        Ok(())
+    } else {
+        Err(Box::new(errors))
+    }
+}
+
+/// Check the items of a frame and its subframes.
+fn validate_frame(
+    errors: &mut Vec<SourceDiagnostic>,
+    frame: &Frame,
+    standard: PdfStandard,
+) {
+    for (_, item) in frame.items() {
+        match item {
+            FrameItem::Group(group) => validate_frame(errors, &group.frame, standard),
+            FrameItem::Text(text) => {
+                let span = text.glyphs.first().map_or(Span::detached(), |g| g.span);
+                validate_paint(errors, &text.fill, span, standard);
+                for glyph in text.glyphs.iter().filter(|glyph| glyph.id == 0) {
+                    errors.push(
+                        SourceDiagnostic::error(
+                            glyph.span,
+                            eco_format!(
+                                "{} does not allow missing glyphs",
+                                standard.name()
+                            ),
+                        )
+                        .with_hint(eco_format!(
+                            "the font {} does not contain the character {:?}",
+                            text.font.info().family,
+                            glyph.c,
+                        )),
+                    );
+                }
+            }
+            FrameItem::Shape(shape, span) => {
+                if let Some(fill) = &shape.fill {
+                    validate_paint(errors, fill, *span, standard);
+                }
+                if let Some(stroke) = &shape.stroke {
+                    validate_paint(errors, &stroke.paint, *span, standard);
+                }
+            }
+            FrameItem::Image(..) | FrameItem::Meta(..) => {}
+        }
+    }
+}
+
+/// Check a paint, including the content of pattern tiles.
+fn validate_paint(
+    errors: &mut Vec<SourceDiagnostic>,
+    paint: &Paint,
+    span: Span,
+    standard: PdfStandard,
+) {
+    match paint {
+        // The output intent is an RGB profile, so device-dependent CMYK colors
+        // can't be reproduced faithfully.
+        Paint::Solid(Color::Cmyk(_)) => errors.push(
+            SourceDiagnostic::error(
+                span,
+                eco_format!("{} does not allow CMYK colors", standard.name()),
+            )
+            .with_hint("use an RGB or luma color instead"),
+        ),
+        Paint::Pattern(pattern) => validate_frame(errors, pattern.frame(), standard),
+        Paint::Solid(_) | Paint::Gradient(_) => {}
+    }
+}
+
+/// Write the ICC profile of the output intent, which defines the colors of
+/// the document, and return its reference.
+pub fn write_output_profile(ctx: &mut PdfContext) -> Ref {
+    let profile_ref = ctx.alloc.bump();
+    let data = deflate(SRGB_ICC);
+    let mut profile = ctx.writer.stream(profile_ref, &data);
+    profile.filter(Filter::FlateDecode);
+    profile.pair(Name(b"N"), 3);
+    profile.finish();
+    profile_ref
+}
+
+/// Insert a file identifier into the trailer of a finished PDF file.
+///
+/// The trailer comes after the cross-reference table, so inserting into it
+/// does not shift any of the recorded byte offsets.
+pub fn insert_file_id(buf: &mut Vec<u8>) {
+    let id = eco_format!("{:032x}", hash128(buf.as_slice()));
+    let entry = eco_format!("\n  /ID [<{id}> <{id}>]");
+    let trailer = buf
+        .windows(7)
+        .rposition(|window| window == b"trailer")
+        .expect("finished file has a trailer");
+    let start = trailer
+        + buf[trailer..]
+            .windows(2)
+            .position(|window| window == b"<<")
+            .expect("trailer has a dictionary")
+        + 2;
+    let mut rest = buf.split_off(start);
+    buf.extend(entry.bytes());
+    buf.append(&mut rest);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::doc::{Glyph, Lang, TextItem};
+    use crate::export::pdf::{pdf, PdfOptions};
+    use crate::font::Font;
+    use crate::geom::{Abs, CmykColor, Em, Geometry, Point, RgbaColor, Size};
+    use crate::util::Buffer;
+
+    const FONT: &[u8] = include_bytes!("../../../assets/fonts/LinLibertine_R.ttf");
+
+    /// A document with a single page holding the given item.
+    fn document(item: FrameItem) -> Document {
+        let mut frame = Frame::new(Size::splat(Abs::pt(100.0)));
+        frame.push(Point::zero(), item);
+        Document { pages: vec![frame], ..Default::default() }
+    }
+
+    /// A text item with the given glyph ids.
+    fn text(ids: &[u16], fill: Paint) -> FrameItem {
+        let font = Font::new(Buffer::from_static(FONT), 0).unwrap();
+        let glyphs = ids
+            .iter()
+            .map(|&id| Glyph {
+                id,
+                x_advance: Em::new(0.5),
+                x_offset: Em::zero(),
+                c: '☃',
+                span: Span::detached(),
+                offset: 0,
+            })
+            .collect();
+        FrameItem::Text(TextItem {
+            font,
+            size: Abs::pt(10.0),
+            fill,
+            lang: Lang::ENGLISH,
+            glyphs,
+        })
+    }
+
+    #[track_caller]
+    fn test(document: &Document, exp: &[&str]) {
+        let messages: Vec<_> = match validate(document, PdfStandard::A2b) {
+            Ok(()) => vec![],
+            Err(errors) => errors.iter().map(|error| error.message.clone()).collect(),
+        };
+        assert_eq!(messages, exp);
+    }
+
+    #[test]
+    fn test_pdfa_declares_conformance() {
+        let options = PdfOptions { standard: Some(PdfStandard::A2b) };
+        let black = Paint::from(RgbaColor::new(0, 0, 0, 255));
+        let buf = pdf(&document(text(&[3, 4], black)), &options).unwrap();
+        let text = String::from_utf8_lossy(&buf);
+        assert!(text.contains("pdfaid:part"));
+        assert!(text.contains("/OutputIntents"));
+        assert!(text[text.rfind("trailer").unwrap()..].contains("/ID ["));
+    }
+
+    #[test]
+    fn test_pdfa_validates_colors() {
+        let rgb = Paint::from(RgbaColor::new(0, 0, 0, 255));
+        let cmyk = Paint::from(CmykColor::new(0, 255, 255, 0));
+        let rect = Geometry::Rect(Size::splat(Abs::pt(10.0)));
+        test(
+            &document(FrameItem::Shape(rect.clone().filled(rgb), Span::detached())),
+            &[],
+        );
+        test(
+            &document(FrameItem::Shape(rect.filled(cmyk.clone()), Span::detached())),
+            &["PDF/A-2b does not allow CMYK colors"],
+        );
+        test(&document(text(&[3], cmyk)), &["PDF/A-2b does not allow CMYK colors"]);
+    }
+
+    #[test]
+    fn test_pdfa_validates_glyphs() {
+        let black = Paint::from(RgbaColor::new(0, 0, 0, 255));
+        test(&document(text(&[3, 4], black.clone())), &[]);
+        test(
+            &document(text(&[3, 0, 0], black.clone())),
+            &[
+                "PDF/A-2b does not allow missing glyphs",
+                "PDF/A-2b does not allow missing glyphs",
+            ],
+        );
+
+        let document = document(text(&[0], black));
+        let errors =
+            pdf(&document, &PdfOptions { standard: Some(PdfStandard::A2b) }).unwrap_err();
+        assert_eq!(
+            errors[0].hints,
+            ["the font Linux Libertine does not contain the character '☃'"]
+        );
+    }
+}
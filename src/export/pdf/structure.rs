@@ -0,0 +1,244 @@
+use std::collections::HashMap;
+
+use pdf_writer::{Dict, Finish, Name, Ref, TextStr};
+
+use super::{PdfContext, RefExt};
+use crate::doc::{Role, Tag};
+use crate::model::Location;
+
+/// The logical structure of a document, which is built up while writing the
+/// pages' content streams.
+#[derive(Default)]
+pub struct StructTree {
+    /// All structure elements.
+    elems: Vec<StructElem>,
+    /// Maps from tag locations to indices into `elems`.
+    indices: HashMap<Location, usize>,
+    /// The kids of the document element.
+    root: Vec<Kid>,
+    /// For each page and marked-content ID, the element that the marked
+    /// content belongs to.
+    marked: Vec<Vec<Option<usize>>>,
+    /// For each link annotation, the element that it belongs to.
+    annotations: Vec<Option<usize>>,
+}
+
+/// An element in the document's logical structure.
+struct StructElem {
+    role: Role,
+    parent: Option<usize>,
+    kids: Vec<Kid>,
+}
+
+/// A kid of a structure element.
+enum Kid {
+    /// Another structure element.
+    Elem(usize),
+    /// A marked-content sequence on a page.
+    Content(usize, i32),
+    /// An annotation on a page.
+    Annot(usize, Ref),
+}
+
+impl StructTree {
+    /// Find or create the elements along a path of tags from the outermost to
+    /// the innermost element and return the innermost one.
+    pub fn open(&mut self, tags: &[Tag]) -> Option<usize> {
+        let mut parent = None;
+        for tag in tags {
+            let index = match self.indices.get(&tag.location) {
+                Some(&index) => index,
+                None => {
+                    let index = self.elems.len();
+                    self.elems.push(StructElem {
+                        role: tag.role.clone(),
+                        parent,
+                        kids: vec![],
+                    });
+                    self.indices.insert(tag.location, index);
+                    self.kids_mut(parent).push(Kid::Elem(index));
+                    index
+                }
+            };
+            parent = Some(index);
+        }
+        parent
+    }
+
+    /// Allocate a marked-content ID on a page for content that belongs to the
+    /// given element, or to the document if there is none.
+    pub fn mark(&mut self, page: usize, elem: Option<usize>) -> i32 {
+        if self.marked.len() <= page {
+            self.marked.resize_with(page + 1, Vec::new);
+        }
+        let mcid = self.marked[page].len() as i32;
+        self.marked[page].push(elem);
+        self.kids_mut(elem).push(Kid::Content(page, mcid));
+        mcid
+    }
+
+    /// Register a link annotation on a page that belongs to the given
+    /// element and return its index among all annotations.
+    pub fn annotate(&mut self, page: usize, annot: Ref, elem: Option<usize>) -> usize {
+        let index = self.annotations.len();
+        self.annotations.push(elem);
+        self.kids_mut(elem).push(Kid::Annot(page, annot));
+        index
+    }
+
+    /// The kids of an element or of the document.
+    fn kids_mut(&mut self, elem: Option<usize>) -> &mut Vec<Kid> {
+        match elem {
+            Some(index) => &mut self.elems[index].kids,
+            None => &mut self.root,
+        }
+    }
+}
+
+/// Write the structure tree and return the reference of its root.
+///
+/// Annotations are keyed in the parent tree after the pages, so the
+/// annotation with index `i` has the key `page_count + i`.
+pub fn write_struct_tree(ctx: &mut PdfContext) -> Ref {
+    let tree = std::mem::take(&mut ctx.struct_tree);
+    let root_ref = ctx.alloc.bump();
+    let document_ref = ctx.alloc.bump();
+    let parent_tree_ref = ctx.alloc.bump();
+    let elem_refs: Vec<_> = tree.elems.iter().map(|_| ctx.alloc.bump()).collect();
+    let elem_ref = |elem: Option<usize>| elem.map_or(document_ref, |i| elem_refs[i]);
+
+    let page_count = ctx.page_refs.len();
+    let mut root = ctx.writer.indirect(root_ref).dict();
+    root.pair(Name(b"Type"), Name(b"StructTreeRoot"));
+    root.pair(Name(b"K"), document_ref);
+    root.pair(Name(b"ParentTree"), parent_tree_ref);
+    root.pair(Name(b"ParentTreeNextKey"), (page_count + tree.annotations.len()) as i32);
+    root.finish();
+
+    let mut document = ctx.writer.indirect(document_ref).dict();
+    document.pair(Name(b"Type"), Name(b"StructElem"));
+    document.pair(Name(b"S"), Name(b"Document"));
+    document.pair(Name(b"P"), root_ref);
+    write_kids(&mut document, &tree.root, &elem_refs, &ctx.page_refs);
+    document.finish();
+
+    for (elem, &r) in tree.elems.iter().zip(&elem_refs) {
+        let mut dict = ctx.writer.indirect(r).dict();
+        dict.pair(Name(b"Type"), Name(b"StructElem"));
+        dict.pair(Name(b"S"), role_name(&elem.role));
+        dict.pair(Name(b"P"), elem_ref(elem.parent));
+        if let Role::Figure(Some(alt)) = &elem.role {
+            dict.pair(Name(b"Alt"), TextStr(alt));
+        }
+        write_kids(&mut dict, &elem.kids, &elem_refs, &ctx.page_refs);
+    }
+
+    let mut parent_tree = ctx.writer.indirect(parent_tree_ref).dict();
+    let mut nums = parent_tree.insert(Name(b"Nums")).array();
+    for page in 0..page_count {
+        nums.item(page as i32);
+        let marked = tree.marked.get(page).map_or(&[][..], Vec::as_slice);
+        nums.push().array().items(marked.iter().map(|&elem| elem_ref(elem)));
+    }
+    for (i, &elem) in tree.annotations.iter().enumerate() {
+        nums.item((page_count + i) as i32);
+        nums.item(elem_ref(elem));
+    }
+    nums.finish();
+    parent_tree.finish();
+
+    root_ref
+}
+
+/// Write the `/K` entry of a structure element.
+fn write_kids(dict: &mut Dict, kids: &[Kid], elem_refs: &[Ref], page_refs: &[Ref]) {
+    let mut array = dict.insert(Name(b"K")).array();
+    for kid in kids {
+        match *kid {
+            Kid::Elem(index) => {
+                array.item(elem_refs[index]);
+            }
+            Kid::Content(page, mcid) => {
+                let mut mcr = array.push().dict();
+                mcr.pair(Name(b"Type"), Name(b"MCR"));
+                mcr.pair(Name(b"Pg"), page_refs[page]);
+                mcr.pair(Name(b"MCID"), mcid);
+            }
+            Kid::Annot(page, annot) => {
+                let mut objr = array.push().dict();
+                objr.pair(Name(b"Type"), Name(b"OBJR"));
+                objr.pair(Name(b"Obj"), annot);
+                objr.pair(Name(b"Pg"), page_refs[page]);
+            }
+        }
+    }
+}
+
+/// The standard structure type of a role.
+pub fn role_name(role: &Role) -> Name<'static> {
+    match role {
+        Role::Heading(level) => match level.get() {
+            1 => Name(b"H1"),
+            2 => Name(b"H2"),
+            3 => Name(b"H3"),
+            4 => Name(b"H4"),
+            5 => Name(b"H5"),
+            _ => Name(b"H6"),
+        },
+        Role::Paragraph => Name(b"P"),
+        Role::List => Name(b"L"),
+        Role::ListItem => Name(b"LI"),
+        Role::ListLabel => Name(b"Lbl"),
+        Role::ListBody => Name(b"LBody"),
+        Role::Table => Name(b"Table"),
+        Role::TableHeader => Name(b"THead"),
+        Role::TableFooter => Name(b"TFoot"),
+        Role::TableRow => Name(b"TR"),
+        Role::TableCell => Name(b"TD"),
+        Role::TableHeaderCell => Name(b"TH"),
+        Role::Figure(_) => Name(b"Figure"),
+        Role::Caption => Name(b"Caption"),
+        Role::Link => Name(b"Link"),
+        Role::Artifact => Name(b"Artifact"),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::model::StabilityProvider;
+    use crate::util::hash128;
+
+    #[test]
+    fn test_struct_tree_separates_identical_elements() {
+        // Tags for equal values get distinct locations in the order in which
+        // they are created.
+        let mut provider = StabilityProvider::new();
+        let mut tag =
+            |role: Role| Tag { location: provider.locate(hash128(&role)), role };
+        let cell = tag(Role::TableCell);
+        let first = tag(Role::Paragraph);
+        let second = tag(Role::Paragraph);
+
+        // The first paragraph is split across two pages.
+        let mut tree = StructTree::default();
+        let a = tree.open(&[cell.clone(), first.clone()]);
+        tree.mark(0, a);
+        let b = tree.open(&[cell.clone(), first]);
+        tree.mark(1, b);
+        let c = tree.open(&[cell, second]);
+        tree.mark(1, c);
+
+        assert_eq!(a, b);
+        assert_ne!(a, c);
+        assert_eq!(tree.elems.len(), 3);
+        assert!(matches!(tree.root[..], [Kid::Elem(0)]));
+        assert!(matches!(tree.elems[0].kids[..], [Kid::Elem(1), Kid::Elem(2)]));
+        assert!(matches!(
+            tree.elems[1].kids[..],
+            [Kid::Content(0, 0), Kid::Content(1, 0)]
+        ));
+        assert!(matches!(tree.elems[2].kids[..], [Kid::Content(1, 1)]));
+        assert_eq!(tree.elems[2].parent, Some(0));
+    }
+}
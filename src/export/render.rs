@@ -11,7 +11,8 @@ use usvg::FitTo;
 
 use crate::doc::{Frame, FrameItem, GroupItem, Meta, TextItem};
 use crate::geom::{
-    self, Abs, Color, Geometry, Paint, PathItem, Shape, Size, Stroke, Transform,
+    self, Abs, Color, Geometry, Gradient, LineCap, LineJoin, Paint, PathItem, Pattern,
+    RelativeTo, Shape, Size, Stroke, Transform,
 };
 use crate::image::{DecodedImage, Image};
 
@@ -28,15 +29,26 @@ pub fn render(frame: &Frame, pixel_per_pt: f32, fill: Color) -> sk::Pixmap {
     canvas.fill(fill.into());
 
     let ts = sk::Transform::from_scale(pixel_per_pt, pixel_per_pt);
-    render_frame(&mut canvas, ts, None, frame);
+    let container = Container { ts, size };
+    render_frame(&mut canvas, ts, container, None, frame);
 
     canvas
 }
 
+/// The transform and size of the frame that items are contained in.
+///
+/// Gradients that are relative to the parent span this area.
+#[derive(Copy, Clone)]
+struct Container {
+    ts: sk::Transform,
+    size: Size,
+}
+
 /// Render a frame into the canvas.
 fn render_frame(
     canvas: &mut sk::Pixmap,
     ts: sk::Transform,
+    container: Container,
     mask: Option<&sk::ClipMask>,
     frame: &Frame,
 ) {
@@ -50,10 +62,10 @@ fn render_frame(
                 render_group(canvas, ts, mask, group);
             }
             FrameItem::Text(text) => {
-                render_text(canvas, ts, mask, text);
+                render_text(canvas, ts, container, mask, text);
             }
             FrameItem::Shape(shape, _) => {
-                render_shape(canvas, ts, mask, shape);
+                render_shape(canvas, ts, container, mask, shape);
             }
             FrameItem::Image(image, size, _) => {
                 render_image(canvas, ts, mask, image, *size);
@@ -61,6 +73,7 @@ fn render_frame(
             FrameItem::Meta(meta, _) => match meta {
                 Meta::Link(_) => {}
                 Meta::Elem(_) => {}
+                Meta::Tag(_) => {}
                 Meta::Hide => {}
             },
         }
@@ -106,16 +119,25 @@ fn render_group(
         }
     }
 
-    render_frame(canvas, ts, mask, &group.frame);
+    let container = Container { ts, size: group.frame.size() };
+    render_frame(canvas, ts, container, mask, &group.frame);
 }
 
 /// Render a text run into the canvas.
 fn render_text(
     canvas: &mut sk::Pixmap,
     ts: sk::Transform,
+    container: Container,
     mask: Option<&sk::ClipMask>,
     text: &TextItem,
 ) {
+    // The bounding box of the whole run, which gradients relative to the
+    // text itself span.
+    let bbox = Bbox {
+        ts: ts.pre_translate(0.0, -text.size.to_f32()),
+        size: Size::new(text.width(), text.size),
+    };
+
     let mut x = 0.0;
     for glyph in &text.glyphs {
         let id = GlyphId(glyph.id);
@@ -124,7 +146,9 @@ fn render_text(
 
         render_svg_glyph(canvas, ts, mask, text, id)
             .or_else(|| render_bitmap_glyph(canvas, ts, mask, text, id))
-            .or_else(|| render_outline_glyph(canvas, ts, mask, text, id));
+            .or_else(|| {
+                render_outline_glyph(canvas, ts, bbox, container, mask, text, id)
+            });
 
         x += glyph.x_advance.at(text.size).to_f32();
     }
@@ -207,6 +231,8 @@ fn render_bitmap_glyph(
 fn render_outline_glyph(
     canvas: &mut sk::Pixmap,
     ts: sk::Transform,
+    bbox: Bbox,
+    container: Container,
     mask: Option<&sk::ClipMask>,
     text: &TextItem,
     id: GlyphId,
@@ -214,22 +240,28 @@ fn render_outline_glyph(
     let ppem = text.size.to_f32() * ts.sy;
 
     // Render a glyph directly as a path. This only happens when the fast glyph
-    // rasterization can't be used due to very large text size or weird
-    // scale/skewing transforms.
-    if ppem > 100.0 || ts.kx != 0.0 || ts.ky != 0.0 || ts.sx != ts.sy {
+    // rasterization can't be used due to very large text size, weird
+    // scale/skewing transforms or a gradient or pattern fill.
+    if ppem > 100.0
+        || ts.kx != 0.0
+        || ts.ky != 0.0
+        || ts.sx != ts.sy
+        || !matches!(text.fill, Paint::Solid(_))
+    {
         let path = {
             let mut builder = WrappedPathBuilder(sk::PathBuilder::new());
             text.font.ttf().outline_glyph(id, &mut builder)?;
             builder.0.finish()?
         };
 
-        let paint = text.fill.into();
-        let rule = sk::FillRule::default();
-
         // Flip vertically because font design coordinate
         // system is Y-up.
         let scale = text.size.to_f32() / text.font.units_per_em() as f32;
         let ts = ts.pre_scale(scale, -scale);
+
+        let mut pixmap = None;
+        let paint = to_sk_paint(&text.fill, ts, bbox, container, true, &mut pixmap)?;
+        let rule = sk::FillRule::default();
         canvas.fill_path(&path, &paint, rule, ts, mask)?;
         return Some(());
     }
@@ -251,7 +283,7 @@ fn render_outline_glyph(
     let bottom = top + mh;
 
     // Premultiply the text color.
-    let Paint::Solid(color) = text.fill;
+    let Paint::Solid(color) = text.fill else { return None };
     let c = color.to_rgba();
     let color = sk::ColorU8::from_rgba(c.r, c.g, c.b, 255).premultiply().get();
 
@@ -284,6 +316,7 @@ fn render_outline_glyph(
 fn render_shape(
     canvas: &mut sk::Pixmap,
     ts: sk::Transform,
+    container: Container,
     mask: Option<&sk::ClipMask>,
     shape: &Shape,
 ) -> Option<()> {
@@ -302,8 +335,11 @@ fn render_shape(
         Geometry::Path(ref path) => convert_path(path)?,
     };
 
-    if let Some(fill) = shape.fill {
-        let mut paint: sk::Paint = fill.into();
+    let bbox = Bbox { ts, size: shape.geometry.bbox_size() };
+
+    if let Some(fill) = &shape.fill {
+        let mut pixmap = None;
+        let mut paint = to_sk_paint(fill, ts, bbox, container, false, &mut pixmap)?;
         if matches!(shape.geometry, Geometry::Rect(_)) {
             paint.anti_alias = false;
         }
@@ -312,9 +348,34 @@ fn render_shape(
         canvas.fill_path(&path, &paint, rule, ts, mask);
     }
 
-    if let Some(Stroke { paint, thickness }) = shape.stroke {
-        let paint = paint.into();
-        let stroke = sk::Stroke { width: thickness.to_f32(), ..Default::default() };
+    if let Some(Stroke {
+        paint,
+        thickness,
+        line_cap,
+        line_join,
+        dash_pattern,
+        miter_limit,
+    }) = &shape.stroke
+    {
+        let dash = dash_pattern.as_ref().and_then(|pattern| {
+            // tiny-skia only allows dash patterns with an even number of
+            // elements, while PDF allows any number.
+            let len = pattern.array.len();
+            let len = if len % 2 == 1 { 2 * len } else { len };
+            let array =
+                pattern.array.iter().map(|l| l.to_f32()).cycle().take(len).collect();
+            sk::StrokeDash::new(array, pattern.phase.to_f32())
+        });
+
+        let mut pixmap = None;
+        let paint = to_sk_paint(paint, ts, bbox, container, false, &mut pixmap)?;
+        let stroke = sk::Stroke {
+            width: thickness.to_f32(),
+            line_cap: line_cap.into(),
+            line_join: line_join.into(),
+            dash,
+            miter_limit: miter_limit.0 as f32,
+        };
         canvas.stroke_path(&path, &paint, &stroke, ts, mask);
     }
 
@@ -414,6 +475,120 @@ fn scaled_texture(image: &Image, w: u32, h: u32) -> Option<Arc<sk::Pixmap>> {
     Some(Arc::new(pixmap))
 }
 
+/// The bounding box of a painted item.
+#[derive(Copy, Clone)]
+struct Bbox {
+    ts: sk::Transform,
+    size: Size,
+}
+
+/// Convert a paint into a tiny-skia paint for an item drawn with the
+/// transform `ts`.
+///
+/// Gradients are rasterized into a texture that is stored in `pixmap`, which
+/// the returned paint borrows from.
+fn to_sk_paint<'a>(
+    paint: &Paint,
+    ts: sk::Transform,
+    bbox: Bbox,
+    container: Container,
+    on_text: bool,
+    pixmap: &'a mut Option<Arc<sk::Pixmap>>,
+) -> Option<sk::Paint<'a>> {
+    let mut sk_paint = sk::Paint { anti_alias: true, ..Default::default() };
+
+    if let Paint::Solid(color) = paint {
+        sk_paint.set_color((*color).into());
+        return Some(sk_paint);
+    }
+
+    let (area_ts, area_size) = match paint.unwrap_relative(on_text) {
+        RelativeTo::Self_ => (bbox.ts, bbox.size),
+        RelativeTo::Parent => (container.ts, container.size),
+    };
+
+    // Gradients span the whole area while patterns repeat a single cell.
+    let (size, spread) = match paint {
+        Paint::Pattern(pattern) => (pattern.step(), sk::SpreadMode::Repeat),
+        _ => (area_size, sk::SpreadMode::Pad),
+    };
+
+    // Rasterize the paint at the resolution it is displayed at.
+    let scale = area_ts.sx.abs().max(area_ts.sy.abs()).max(1.0);
+    let w = (size.x.to_f32() * scale).ceil().clamp(1.0, MAX_TEXTURE_SIZE);
+    let h = (size.y.to_f32() * scale).ceil().clamp(1.0, MAX_TEXTURE_SIZE);
+    let texture = pixmap.insert(match paint {
+        Paint::Solid(_) => unreachable!(),
+        Paint::Gradient(gradient) => gradient_texture(gradient, w as u32, h as u32),
+        Paint::Pattern(pattern) => pattern_texture(pattern, w as u32, h as u32)?,
+    });
+
+    // The shader is specified relative to the item's transform, so we need to
+    // map from there to the texture's area.
+    let shader_ts = invert(ts)?
+        .pre_concat(area_ts)
+        .pre_scale(size.x.to_f32() / w, size.y.to_f32() / h);
+
+    sk_paint.shader = sk::Pattern::new(
+        (**texture).as_ref(),
+        spread,
+        sk::FilterQuality::Bilinear,
+        1.0,
+        shader_ts,
+    );
+
+    Some(sk_paint)
+}
+
+/// The maximum width and height of a rasterized gradient or pattern cell.
+const MAX_TEXTURE_SIZE: f32 = 4096.0;
+
+/// Rasterize a gradient into a texture of the given size.
+#[comemo::memoize]
+pub(super) fn gradient_texture(gradient: &Gradient, w: u32, h: u32) -> Arc<sk::Pixmap> {
+    let mut pixmap = sk::Pixmap::new(w, h).unwrap();
+    let size = (w as f64, h as f64);
+    for (i, dest) in pixmap.pixels_mut().iter_mut().enumerate() {
+        let x = (i as u32 % w) as f64 + 0.5;
+        let y = (i as u32 / w) as f64 + 0.5;
+        let c = gradient.sample_at((x, y), size).to_rgba();
+        *dest = sk::ColorU8::from_rgba(c.r, c.g, c.b, c.a).premultiply();
+    }
+    Arc::new(pixmap)
+}
+
+/// Rasterize a cell of a pattern, i.e. a tile and its spacing, into a texture
+/// of the given size.
+#[comemo::memoize]
+fn pattern_texture(pattern: &Pattern, w: u32, h: u32) -> Option<Arc<sk::Pixmap>> {
+    let mut pixmap = sk::Pixmap::new(w, h)?;
+    let step = pattern.step();
+    let ts =
+        sk::Transform::from_scale(w as f32 / step.x.to_f32(), h as f32 / step.y.to_f32());
+    let frame = pattern.frame();
+    let container = Container { ts, size: frame.size() };
+    render_frame(&mut pixmap, ts, container, None, frame);
+    Some(Arc::new(pixmap))
+}
+
+/// Invert a transform, if it is invertible.
+fn invert(ts: sk::Transform) -> Option<sk::Transform> {
+    let sk::Transform { sx, kx, ky, sy, tx, ty } = ts;
+    let det = sx * sy - kx * ky;
+    if det == 0.0 || !det.is_finite() {
+        return None;
+    }
+
+    Some(sk::Transform::from_row(
+        sy / det,
+        -ky / det,
+        -kx / det,
+        sx / det,
+        (kx * ty - sy * tx) / det,
+        (ky * tx - sx * ty) / det,
+    ))
+}
+
 impl From<Transform> for sk::Transform {
     fn from(transform: Transform) -> Self {
         let Transform { sx, ky, kx, sy, tx, ty } = transform;
@@ -428,16 +603,6 @@ impl From<Transform> for sk::Transform {
     }
 }
 
-impl From<Paint> for sk::Paint<'static> {
-    fn from(paint: Paint) -> Self {
-        let mut sk_paint = sk::Paint::default();
-        let Paint::Solid(color) = paint;
-        sk_paint.set_color(color.into());
-        sk_paint.anti_alias = true;
-        sk_paint
-    }
-}
-
 impl From<Color> for sk::Color {
     fn from(color: Color) -> Self {
         let c = color.to_rgba();
@@ -498,3 +663,23 @@ fn alpha_mul(color: u32, scale: u32) -> u32 {
     let ag = ((color >> 8) & mask) * scale;
     (rb & mask) | (ag & !mask)
 }
+
+impl From<&LineCap> for sk::LineCap {
+    fn from(line_cap: &LineCap) -> Self {
+        match line_cap {
+            LineCap::Butt => sk::LineCap::Butt,
+            LineCap::Round => sk::LineCap::Round,
+            LineCap::Square => sk::LineCap::Square,
+        }
+    }
+}
+
+impl From<&LineJoin> for sk::LineJoin {
+    fn from(line_join: &LineJoin) -> Self {
+        match line_join {
+            LineJoin::Miter => sk::LineJoin::Miter,
+            LineJoin::Round => sk::LineJoin::Round,
+            LineJoin::Bevel => sk::LineJoin::Bevel,
+        }
+    }
+}
@@ -0,0 +1,686 @@
+//! Exporting into SVG images.
+
+use std::collections::HashMap;
+use std::fmt::Write;
+use std::hash::Hash;
+
+use ecow::{eco_format, EcoString};
+use siphasher::sip128::{Hasher128, SipHasher};
+use ttf_parser::{GlyphId, OutlineBuilder};
+
+use super::render::gradient_texture;
+use crate::doc::{Destination, Frame, FrameItem, GroupItem, Meta, TextItem};
+use crate::font::Font;
+use crate::geom::{
+    self, Abs, Geometry, Gradient, LineCap, LineJoin, Paint, PathItem, Pattern, Ratio,
+    RelativeTo, Shape, Size, Stroke, Transform,
+};
+use crate::image::{Image, ImageFormat, RasterFormat, VectorFormat};
+
+/// Export a frame into an SVG image.
+///
+/// Returns the SVG source of a standalone image that has the size of the
+/// frame. Glyphs are deduplicated into `<symbol>` definitions and links to
+/// URLs are turned into `<a>` elements.
+pub fn svg(frame: &Frame) -> String {
+    let size = frame.size();
+    let mut ctx = SvgContext::new(size);
+    ctx.write_frame(frame);
+
+    let w = size.x.to_pt();
+    let h = size.y.to_pt();
+
+    let mut out = String::new();
+    write!(
+        out,
+        "<svg class=\"typst-doc\" viewBox=\"0 0 {w} {h}\" width=\"{w}pt\" \
+         height=\"{h}pt\" xmlns=\"http://www.w3.org/2000/svg\" \
+         xmlns:xlink=\"http://www.w3.org/1999/xlink\">"
+    )
+    .unwrap();
+
+    if !ctx.glyphs.is_empty() || !ctx.defs.is_empty() {
+        out.push_str("<defs>");
+        for (id, glyph) in &ctx.glyphs {
+            write!(out, "<symbol id=\"{id}\" overflow=\"visible\">{glyph}</symbol>")
+                .unwrap();
+        }
+        out.push_str(&ctx.defs);
+        out.push_str("</defs>");
+    }
+
+    out.push_str(&ctx.body);
+    out.push_str("</svg>");
+    out
+}
+
+/// Context for exporting a single frame into an SVG image.
+struct SvgContext {
+    /// The markup of the visible elements.
+    body: String,
+    /// Additional definitions like clip paths.
+    defs: String,
+    /// The glyph definitions in order of first use, keyed by symbol id.
+    glyphs: Vec<(EcoString, String)>,
+    /// Maps from glyphs to the ids of their symbols.
+    glyph_ids: HashMap<(Font, u16), Option<EcoString>>,
+    /// The number of clip paths emitted so far.
+    clip_paths: usize,
+    /// Maps from gradients and the space they are painted in to the ids of
+    /// their definitions.
+    gradient_ids: HashMap<(Gradient, Transform, Size), EcoString>,
+    /// Maps from patterns and the space they are painted in to the ids of
+    /// their definitions.
+    pattern_ids: HashMap<(Pattern, Transform), EcoString>,
+    /// The size of the frame that is currently being written.
+    container_size: Size,
+}
+
+impl SvgContext {
+    fn new(size: Size) -> Self {
+        Self {
+            body: String::new(),
+            defs: String::new(),
+            glyphs: vec![],
+            glyph_ids: HashMap::new(),
+            clip_paths: 0,
+            gradient_ids: HashMap::new(),
+            pattern_ids: HashMap::new(),
+            container_size: size,
+        }
+    }
+
+    /// Write a frame into the body.
+    fn write_frame(&mut self, frame: &Frame) {
+        for (pos, item) in frame.items() {
+            let x = pos.x.to_pt();
+            let y = pos.y.to_pt();
+            match item {
+                FrameItem::Group(group) => {
+                    let transform =
+                        Transform::translate(pos.x, pos.y).pre_concat(group.transform);
+                    self.write_group(transform, group);
+                }
+                FrameItem::Text(text) => self.write_text(x, y, text),
+                FrameItem::Shape(shape, _) => self.write_shape(x, y, shape),
+                FrameItem::Image(image, size, _) => self.write_image(x, y, image, *size),
+                FrameItem::Meta(meta, size) => match meta {
+                    Meta::Link(dest) => self.write_link(x, y, dest, *size),
+                    Meta::Elem(_) => {}
+                    Meta::Tag(_) => {}
+                    Meta::Hide => {}
+                },
+            }
+        }
+    }
+
+    /// Write a group with transform and optional clipping into the body.
+    fn write_group(&mut self, transform: Transform, group: &GroupItem) {
+        let Transform { sx, ky, kx, sy, tx, ty } = transform;
+        write!(
+            self.body,
+            "<g transform=\"matrix({} {} {} {} {} {})\"",
+            sx.get(),
+            ky.get(),
+            kx.get(),
+            sy.get(),
+            tx.to_pt(),
+            ty.to_pt(),
+        )
+        .unwrap();
+
+        if group.clips {
+            let id = self.clip_paths;
+            self.clip_paths += 1;
+
+            let size = group.frame.size();
+            write!(
+                self.defs,
+                "<clipPath id=\"c{id}\"><rect width=\"{}\" height=\"{}\"/></clipPath>",
+                size.x.to_pt(),
+                size.y.to_pt(),
+            )
+            .unwrap();
+            write!(self.body, " clip-path=\"url(#c{id})\"").unwrap();
+        }
+
+        self.body.push('>');
+        let prev = std::mem::replace(&mut self.container_size, group.frame.size());
+        self.write_frame(&group.frame);
+        self.container_size = prev;
+        self.body.push_str("</g>");
+    }
+
+    /// Write a text run into the body.
+    fn write_text(&mut self, x: f64, y: f64, text: &TextItem) {
+        let scale = text.size.to_pt() / text.font.units_per_em();
+        write!(
+            self.body,
+            "<g class=\"typst-text\" transform=\"translate({x} {y}) scale({scale} {})\"",
+            -scale,
+        )
+        .unwrap();
+
+        // The glyphs are drawn in flipped font units, so gradients need to be
+        // mapped from their bounding box into that space.
+        let unflip = Transform::scale(Ratio::new(1.0 / scale), Ratio::new(-1.0 / scale));
+        let (ts, size) = match text.fill.unwrap_relative(true) {
+            RelativeTo::Self_ => (
+                unflip.pre_concat(Transform::translate(Abs::zero(), -text.size)),
+                Size::new(text.width(), text.size),
+            ),
+            RelativeTo::Parent => (
+                unflip.pre_concat(Transform::translate(Abs::pt(-x), Abs::pt(-y))),
+                self.container_size,
+            ),
+        };
+        self.write_paint("fill", &text.fill, ts, size);
+        self.body.push('>');
+
+        let mut offset = Abs::zero();
+        for glyph in &text.glyphs {
+            let at = offset + glyph.x_offset.at(text.size);
+            if let Some(id) = self.glyph(&text.font, glyph.id) {
+                write!(
+                    self.body,
+                    "<use xlink:href=\"#{id}\" x=\"{}\"/>",
+                    at.to_pt() / scale,
+                )
+                .unwrap();
+            }
+            offset += glyph.x_advance.at(text.size);
+        }
+
+        self.body.push_str("</g>");
+    }
+
+    /// Retrieve the symbol id of a glyph, defining the symbol on first use.
+    ///
+    /// Returns `None` if the glyph has neither an outline nor a bitmap.
+    fn glyph(&mut self, font: &Font, id: u16) -> Option<EcoString> {
+        let key = (font.clone(), id);
+        if let Some(id) = self.glyph_ids.get(&key) {
+            return id.clone();
+        }
+
+        let symbol = outline_glyph(font, id).or_else(|| bitmap_glyph(font, id));
+        let name = symbol.map(|symbol| {
+            let name = eco_format!("g{:x}", hash128(&key));
+            self.glyphs.push((name.clone(), symbol));
+            name
+        });
+
+        self.glyph_ids.insert(key, name.clone());
+        name
+    }
+
+    /// Write a geometrical shape into the body.
+    fn write_shape(&mut self, x: f64, y: f64, shape: &Shape) {
+        if shape.fill.is_none() && shape.stroke.is_none() {
+            return;
+        }
+
+        write!(self.body, "<path transform=\"translate({x} {y})\" d=\"").unwrap();
+        match shape.geometry {
+            Geometry::Line(target) => {
+                write!(self.body, "M 0 0 L {} {}", target.x.to_pt(), target.y.to_pt())
+                    .unwrap();
+            }
+            Geometry::Rect(size) => {
+                let w = size.x.to_pt();
+                let h = size.y.to_pt();
+                write!(self.body, "M 0 0 H {w} V {h} H 0 Z").unwrap();
+            }
+            Geometry::Path(ref path) => write_path(&mut self.body, path),
+        }
+        self.body.push('"');
+
+        let bbox = shape.geometry.bbox_size();
+        let parent =
+            (Transform::translate(Abs::pt(-x), Abs::pt(-y)), self.container_size);
+        let area = |paint: &Paint| match paint.unwrap_relative(false) {
+            RelativeTo::Self_ => (Transform::identity(), bbox),
+            RelativeTo::Parent => parent,
+        };
+
+        match &shape.fill {
+            Some(fill) => {
+                let (ts, size) = area(fill);
+                self.write_paint("fill", fill, ts, size);
+            }
+            None => self.body.push_str(" fill=\"none\""),
+        }
+
+        if let Some(stroke) = &shape.stroke {
+            let (ts, size) = area(&stroke.paint);
+            self.write_paint("stroke", &stroke.paint, ts, size);
+            self.write_stroke_style(stroke);
+        }
+
+        self.body.push_str("/>");
+    }
+
+    /// Write the non-paint attributes of a stroke into the body.
+    fn write_stroke_style(&mut self, stroke: &Stroke) {
+        write!(self.body, " stroke-width=\"{}\"", stroke.thickness.to_pt()).unwrap();
+
+        let cap = match stroke.line_cap {
+            LineCap::Butt => "butt",
+            LineCap::Round => "round",
+            LineCap::Square => "square",
+        };
+        let join = match stroke.line_join {
+            LineJoin::Miter => "miter",
+            LineJoin::Round => "round",
+            LineJoin::Bevel => "bevel",
+        };
+        write!(self.body, " stroke-linecap=\"{cap}\" stroke-linejoin=\"{join}\"")
+            .unwrap();
+        write!(self.body, " stroke-miterlimit=\"{}\"", stroke.miter_limit.0).unwrap();
+
+        if let Some(pattern) = &stroke.dash_pattern {
+            if !pattern.array.is_empty() {
+                let array: Vec<_> =
+                    pattern.array.iter().map(|l| l.to_pt().to_string()).collect();
+                write!(self.body, " stroke-dasharray=\"{}\"", array.join(" ")).unwrap();
+                write!(self.body, " stroke-dashoffset=\"{}\"", pattern.phase.to_pt())
+                    .unwrap();
+            }
+        }
+    }
+
+    /// Write a paint as a fill or stroke attribute into the body.
+    ///
+    /// Gradients span an area of the given size whose top-left corner is
+    /// mapped into the element's coordinate system by `ts`.
+    fn write_paint(&mut self, attr: &str, paint: &Paint, ts: Transform, size: Size) {
+        match paint {
+            Paint::Solid(color) => {
+                let c = color.to_rgba();
+                write!(self.body, " {attr}=\"#{:02x}{:02x}{:02x}\"", c.r, c.g, c.b)
+                    .unwrap();
+                if c.a != 255 {
+                    write!(self.body, " {attr}-opacity=\"{}\"", c.a as f64 / 255.0)
+                        .unwrap();
+                }
+            }
+            Paint::Gradient(gradient) => {
+                let id = self.gradient(gradient, ts, size);
+                write!(self.body, " {attr}=\"url(#{id})\"").unwrap();
+            }
+            Paint::Pattern(pattern) => {
+                let id = self.pattern(pattern, ts);
+                write!(self.body, " {attr}=\"url(#{id})\"").unwrap();
+            }
+        }
+    }
+
+    /// Retrieve the id of a gradient definition, defining it on first use.
+    fn gradient(&mut self, gradient: &Gradient, ts: Transform, size: Size) -> EcoString {
+        let key = (gradient.clone(), ts, size);
+        if let Some(id) = self.gradient_ids.get(&key) {
+            return id.clone();
+        }
+
+        let id = eco_format!("gr{}", self.gradient_ids.len());
+        let (w, h) = (size.x.to_pt(), size.y.to_pt());
+
+        // Radial and conic gradients are defined in the unit square.
+        let unit = ts.pre_concat(Transform::scale(
+            Ratio::new(w.max(f64::EPSILON)),
+            Ratio::new(h.max(f64::EPSILON)),
+        ));
+
+        match gradient {
+            Gradient::Linear(linear) => {
+                let (sin, cos) = (linear.angle.sin(), linear.angle.cos());
+                let length = (w * cos).abs() + (h * sin).abs();
+                let (dx, dy) = (cos * length / 2.0, sin * length / 2.0);
+                write!(
+                    self.defs,
+                    "<linearGradient id=\"{id}\" gradientUnits=\"userSpaceOnUse\" \
+                     gradientTransform=\"{}\" x1=\"{}\" y1=\"{}\" x2=\"{}\" y2=\"{}\">",
+                    matrix(ts),
+                    w / 2.0 - dx,
+                    h / 2.0 - dy,
+                    w / 2.0 + dx,
+                    h / 2.0 + dy,
+                )
+                .unwrap();
+                write_stops(&mut self.defs, gradient);
+                self.defs.push_str("</linearGradient>");
+            }
+            Gradient::Radial(radial) => {
+                write!(
+                    self.defs,
+                    "<radialGradient id=\"{id}\" gradientUnits=\"userSpaceOnUse\" \
+                     gradientTransform=\"{}\" cx=\"{}\" cy=\"{}\" r=\"{}\">",
+                    matrix(unit),
+                    radial.center.x.get(),
+                    radial.center.y.get(),
+                    radial.radius.get(),
+                )
+                .unwrap();
+                write_stops(&mut self.defs, gradient);
+                self.defs.push_str("</radialGradient>");
+            }
+            Gradient::Conic(_) => {
+                // SVG has no conic gradients, so we embed a rasterized version.
+                const RESOLUTION: u32 = 256;
+                let pixmap = gradient_texture(gradient, RESOLUTION, RESOLUTION);
+                let data = pixmap.encode_png().unwrap_or_default();
+                write!(
+                    self.defs,
+                    "<pattern id=\"{id}\" patternUnits=\"userSpaceOnUse\" \
+                     patternTransform=\"{}\" width=\"1\" height=\"1\">\
+                     <image width=\"1\" height=\"1\" preserveAspectRatio=\"none\" \
+                     xlink:href=\"{}\"/></pattern>",
+                    matrix(unit),
+                    data_url(ImageFormat::Raster(RasterFormat::Png), &data),
+                )
+                .unwrap();
+            }
+        }
+
+        self.gradient_ids.insert(key, id.clone());
+        id
+    }
+
+    /// Retrieve the id of a pattern definition, defining it on first use.
+    fn pattern(&mut self, pattern: &Pattern, ts: Transform) -> EcoString {
+        let key = (pattern.clone(), ts);
+        if let Some(id) = self.pattern_ids.get(&key) {
+            return id.clone();
+        }
+
+        // Write the tile into a fresh body and then move it into the
+        // definitions.
+        let body = std::mem::take(&mut self.body);
+        let prev = std::mem::replace(&mut self.container_size, pattern.size());
+        self.write_frame(pattern.frame());
+        self.container_size = prev;
+        let tile = std::mem::replace(&mut self.body, body);
+
+        let id = eco_format!("p{}", self.pattern_ids.len());
+        let step = pattern.step();
+        write!(
+            self.defs,
+            "<pattern id=\"{id}\" patternUnits=\"userSpaceOnUse\" \
+             patternTransform=\"{}\" width=\"{}\" height=\"{}\">{tile}</pattern>",
+            matrix(ts),
+            step.x.to_pt(),
+            step.y.to_pt(),
+        )
+        .unwrap();
+
+        self.pattern_ids.insert(key, id.clone());
+        id
+    }
+
+    /// Write a raster or vector image into the body.
+    fn write_image(&mut self, x: f64, y: f64, image: &Image, size: Size) {
+        write!(
+            self.body,
+            "<image x=\"{x}\" y=\"{y}\" width=\"{}\" height=\"{}\" \
+             preserveAspectRatio=\"none\" xlink:href=\"{}\"/>",
+            size.x.to_pt(),
+            size.y.to_pt(),
+            data_url(image.format(), image.data()),
+        )
+        .unwrap();
+    }
+
+    /// Write a transparent link area into the body.
+    ///
+    /// Only links to URLs are exported since a standalone SVG image has no
+    /// notion of the other pages in the document.
+    fn write_link(&mut self, x: f64, y: f64, dest: &Destination, size: Size) {
+        let Destination::Url(url) = dest else { return };
+        write!(
+            self.body,
+            "<a xlink:href=\"{}\"><rect x=\"{x}\" y=\"{y}\" width=\"{}\" \
+             height=\"{}\" fill=\"transparent\"/></a>",
+            escape(url),
+            size.x.to_pt(),
+            size.y.to_pt(),
+        )
+        .unwrap();
+    }
+}
+
+/// Create the contents of a symbol for an outline glyph.
+fn outline_glyph(font: &Font, id: u16) -> Option<String> {
+    let mut builder = SvgPathBuilder(String::new());
+    font.ttf().outline_glyph(GlyphId(id), &mut builder)?;
+    Some(format!("<path d=\"{}\"/>", builder.0.trim_end()))
+}
+
+/// Create the contents of a symbol for a bitmap glyph.
+///
+/// The image is placed in font units since symbols are used in a coordinate
+/// system that is scaled to the font size and flipped vertically.
+fn bitmap_glyph(font: &Font, id: u16) -> Option<String> {
+    let upem = font.units_per_em();
+    let raster = font.ttf().glyph_raster_image(GlyphId(id), u16::MAX)?;
+    let image = Image::new(raster.data.into(), raster.format.into()).ok()?;
+
+    let h = upem;
+    let w = (image.width() as f64 / image.height() as f64) * h;
+    // The raster offsets are given in pixels at the strike's size.
+    let scale = upem / raster.pixels_per_em as f64;
+    let dx = raster.x as f64 * scale;
+    let dy = raster.y as f64 * scale;
+    Some(format!(
+        "<image x=\"{dx}\" y=\"{}\" width=\"{w}\" height=\"{h}\" \
+         transform=\"scale(1 -1)\" preserveAspectRatio=\"none\" xlink:href=\"{}\"/>",
+        -h - dy,
+        data_url(image.format(), image.data()),
+    ))
+}
+
+/// Write the path data of a bezier path.
+fn write_path(out: &mut String, path: &geom::Path) {
+    for item in &path.0 {
+        match item {
+            PathItem::MoveTo(p) => {
+                write!(out, "M {} {} ", p.x.to_pt(), p.y.to_pt()).unwrap()
+            }
+            PathItem::LineTo(p) => {
+                write!(out, "L {} {} ", p.x.to_pt(), p.y.to_pt()).unwrap()
+            }
+            PathItem::CubicTo(p1, p2, p3) => write!(
+                out,
+                "C {} {} {} {} {} {} ",
+                p1.x.to_pt(),
+                p1.y.to_pt(),
+                p2.x.to_pt(),
+                p2.y.to_pt(),
+                p3.x.to_pt(),
+                p3.y.to_pt(),
+            )
+            .unwrap(),
+            PathItem::ClosePath => out.push_str("Z "),
+        }
+    }
+}
+
+/// Write the stops of a gradient.
+fn write_stops(out: &mut String, gradient: &Gradient) {
+    for (color, offset) in gradient.srgb_stops() {
+        let c = color.to_rgba();
+        write!(
+            out,
+            "<stop offset=\"{}\" stop-color=\"#{:02x}{:02x}{:02x}\"",
+            offset.get(),
+            c.r,
+            c.g,
+            c.b,
+        )
+        .unwrap();
+        if c.a != 255 {
+            write!(out, " stop-opacity=\"{}\"", c.a as f64 / 255.0).unwrap();
+        }
+        out.push_str("/>");
+    }
+}
+
+/// Format a transform as an SVG matrix.
+fn matrix(transform: Transform) -> String {
+    let Transform { sx, ky, kx, sy, tx, ty } = transform;
+    format!(
+        "matrix({} {} {} {} {} {})",
+        sx.get(),
+        ky.get(),
+        kx.get(),
+        sy.get(),
+        tx.to_pt(),
+        ty.to_pt(),
+    )
+}
+
+/// Encode image data as a data URL.
+fn data_url(format: ImageFormat, data: &[u8]) -> String {
+    let mime = match format {
+        ImageFormat::Raster(RasterFormat::Png) => "image/png",
+        ImageFormat::Raster(RasterFormat::Jpg) => "image/jpeg",
+        ImageFormat::Raster(RasterFormat::Gif) => "image/gif",
+        ImageFormat::Vector(VectorFormat::Svg) => "image/svg+xml",
+    };
+    format!("data:{mime};base64,{}", base64::encode(data))
+}
+
+/// Escape a string for use in an attribute value.
+fn escape(text: &str) -> String {
+    let mut out = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '&' => out.push_str("&amp;"),
+            '<' => out.push_str("&lt;"),
+            '>' => out.push_str("&gt;"),
+            '"' => out.push_str("&quot;"),
+            c => out.push(c),
+        }
+    }
+    out
+}
+
+/// Hash a value into a 128-bit number.
+fn hash128<T: Hash>(value: &T) -> u128 {
+    let mut state = SipHasher::new();
+    value.hash(&mut state);
+    state.finish128().as_u128()
+}
+
+/// Allows to build SVG path data from glyph outlines.
+struct SvgPathBuilder(String);
+
+impl OutlineBuilder for SvgPathBuilder {
+    fn move_to(&mut self, x: f32, y: f32) {
+        write!(self.0, "M {x} {y} ").unwrap();
+    }
+
+    fn line_to(&mut self, x: f32, y: f32) {
+        write!(self.0, "L {x} {y} ").unwrap();
+    }
+
+    fn quad_to(&mut self, x1: f32, y1: f32, x: f32, y: f32) {
+        write!(self.0, "Q {x1} {y1} {x} {y} ").unwrap();
+    }
+
+    fn curve_to(&mut self, x1: f32, y1: f32, x2: f32, y2: f32, x: f32, y: f32) {
+        write!(self.0, "C {x1} {y1} {x2} {y2} {x} {y} ").unwrap();
+    }
+
+    fn close(&mut self) {
+        self.0.push_str("Z ");
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::num::NonZeroUsize;
+
+    use super::*;
+    use crate::doc::{Glyph, Lang, Position};
+    use crate::geom::{Em, Point, RgbaColor};
+    use crate::syntax::Span;
+    use crate::util::{Buffer, NonZeroExt};
+
+    const FONT: &[u8] = include_bytes!("../../assets/fonts/LinLibertine_R.ttf");
+
+    /// A frame with the given items at the origin.
+    fn frame(items: Vec<FrameItem>) -> Frame {
+        let mut frame = Frame::new(Size::splat(Abs::pt(100.0)));
+        for item in items {
+            frame.push(Point::zero(), item);
+        }
+        frame
+    }
+
+    /// A text item with the given glyph ids.
+    fn text(ids: &[u16]) -> FrameItem {
+        let font = Font::new(Buffer::from_static(FONT), 0).unwrap();
+        let glyphs = ids
+            .iter()
+            .map(|&id| Glyph {
+                id,
+                x_advance: Em::new(0.5),
+                x_offset: Em::zero(),
+                c: 'a',
+                span: Span::detached(),
+                offset: 0,
+            })
+            .collect();
+        FrameItem::Text(TextItem {
+            font,
+            size: Abs::pt(10.0),
+            fill: Paint::from(RgbaColor::new(0, 0, 0, 255)),
+            lang: Lang::ENGLISH,
+            glyphs,
+        })
+    }
+
+    /// Export the frame and check that the result is well-formed.
+    #[track_caller]
+    fn export(frame: &Frame) -> String {
+        let svg = svg(frame);
+        if let Err(err) = roxmltree::Document::parse(&svg) {
+            panic!("invalid svg ({err}): {svg}");
+        }
+        svg
+    }
+
+    #[test]
+    fn test_svg_deduplicates_glyphs() {
+        let svg = export(&frame(vec![text(&[68, 69, 68]), text(&[69, 69])]));
+        assert_eq!(svg.matches("<symbol").count(), 2);
+        assert_eq!(svg.matches("<use").count(), 5);
+    }
+
+    #[test]
+    fn test_svg_links() {
+        let size = Size::splat(Abs::pt(10.0));
+        let url = Destination::Url("https://typst.app/?a=1&b=<2>".into());
+        let position = Destination::Position(Position {
+            page: NonZeroUsize::ONE,
+            point: Point::zero(),
+        });
+        let svg = export(&frame(vec![
+            FrameItem::Meta(Meta::Link(url), size),
+            FrameItem::Meta(Meta::Link(position), size),
+        ]));
+        assert_eq!(svg.matches("<a ").count(), 1);
+        assert!(svg.contains("xlink:href=\"https://typst.app/?a=1&amp;b=&lt;2&gt;\""));
+    }
+
+    #[test]
+    fn test_svg_clips_groups() {
+        let mut inner = frame(vec![text(&[68])]);
+        inner.clip();
+        let mut outer = frame(vec![]);
+        outer.push_frame(Point::zero(), inner);
+        let svg = export(&outer);
+        assert_eq!(svg.matches("<clipPath").count(), 1);
+        assert!(svg.contains("clip-path=\"url(#c0)\""));
+    }
+}
@@ -39,6 +39,13 @@ impl FontBook {
         self.infos.push(info);
     }
 
+    /// Whether the book contains a font from the given family.
+    ///
+    /// The `family` should be all lowercase.
+    pub fn contains_family(&self, family: &str) -> bool {
+        self.families.contains_key(family)
+    }
+
     /// An ordered iterator over all font families this book knows and details
     /// about the fonts that are part of them.
     pub fn families(
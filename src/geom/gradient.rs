@@ -0,0 +1,577 @@
+use std::f64::consts::TAU;
+use std::sync::Arc;
+
+use super::*;
+use crate::eval::Array;
+
+/// A color gradient.
+#[derive(Clone, Eq, PartialEq, Hash)]
+pub enum Gradient {
+    /// A gradient along a straight line.
+    Linear(Arc<LinearGradient>),
+    /// A gradient radiating outwards from a center point.
+    Radial(Arc<RadialGradient>),
+    /// A gradient sweeping around a center point.
+    Conic(Arc<ConicGradient>),
+}
+
+/// A gradient along a straight line.
+#[derive(Debug, Clone, Eq, PartialEq, Hash)]
+pub struct LinearGradient {
+    /// The color stops of the gradient.
+    pub stops: Vec<(Color, Ratio)>,
+    /// The direction of the gradient.
+    pub angle: Angle,
+    /// The color space in which to interpolate.
+    pub space: ColorSpace,
+    /// The bounding box the gradient is relative to.
+    pub relative: Smart<RelativeTo>,
+}
+
+/// A gradient radiating outwards from a center point.
+#[derive(Debug, Clone, Eq, PartialEq, Hash)]
+pub struct RadialGradient {
+    /// The color stops of the gradient.
+    pub stops: Vec<(Color, Ratio)>,
+    /// The center of the gradient, relative to the bounding box.
+    pub center: Axes<Ratio>,
+    /// The radius of the gradient, relative to the bounding box.
+    pub radius: Ratio,
+    /// The color space in which to interpolate.
+    pub space: ColorSpace,
+    /// The bounding box the gradient is relative to.
+    pub relative: Smart<RelativeTo>,
+}
+
+/// A gradient sweeping around a center point.
+#[derive(Debug, Clone, Eq, PartialEq, Hash)]
+pub struct ConicGradient {
+    /// The color stops of the gradient.
+    pub stops: Vec<(Color, Ratio)>,
+    /// The angle at which the gradient starts.
+    pub angle: Angle,
+    /// The center of the gradient, relative to the bounding box.
+    pub center: Axes<Ratio>,
+    /// The color space in which to interpolate.
+    pub space: ColorSpace,
+    /// The bounding box the gradient is relative to.
+    pub relative: Smart<RelativeTo>,
+}
+
+impl Gradient {
+    /// Create a new linear gradient.
+    pub fn linear(
+        stops: Vec<GradientStop>,
+        angle: Angle,
+        space: ColorSpace,
+        relative: Smart<RelativeTo>,
+    ) -> StrResult<Self> {
+        Ok(Self::Linear(Arc::new(LinearGradient {
+            stops: process_stops(stops)?,
+            angle,
+            space,
+            relative,
+        })))
+    }
+
+    /// Create a new radial gradient.
+    pub fn radial(
+        stops: Vec<GradientStop>,
+        center: Axes<Ratio>,
+        radius: Ratio,
+        space: ColorSpace,
+        relative: Smart<RelativeTo>,
+    ) -> StrResult<Self> {
+        if radius.get() <= 0.0 {
+            return Err("radius must be positive".into());
+        }
+
+        Ok(Self::Radial(Arc::new(RadialGradient {
+            stops: process_stops(stops)?,
+            center,
+            radius,
+            space,
+            relative,
+        })))
+    }
+
+    /// Create a new conic gradient.
+    pub fn conic(
+        stops: Vec<GradientStop>,
+        angle: Angle,
+        center: Axes<Ratio>,
+        space: ColorSpace,
+        relative: Smart<RelativeTo>,
+    ) -> StrResult<Self> {
+        Ok(Self::Conic(Arc::new(ConicGradient {
+            stops: process_stops(stops)?,
+            angle,
+            center,
+            space,
+            relative,
+        })))
+    }
+
+    /// The name of the gradient's kind.
+    pub fn kind(&self) -> &'static str {
+        match self {
+            Self::Linear(_) => "linear",
+            Self::Radial(_) => "radial",
+            Self::Conic(_) => "conic",
+        }
+    }
+
+    /// The color stops of the gradient, with offsets in ascending order.
+    pub fn stops(&self) -> &[(Color, Ratio)] {
+        match self {
+            Self::Linear(linear) => &linear.stops,
+            Self::Radial(radial) => &radial.stops,
+            Self::Conic(conic) => &conic.stops,
+        }
+    }
+
+    /// The color space in which the gradient interpolates.
+    pub fn space(&self) -> ColorSpace {
+        match self {
+            Self::Linear(linear) => linear.space,
+            Self::Radial(radial) => radial.space,
+            Self::Conic(conic) => conic.space,
+        }
+    }
+
+    /// The bounding box the gradient is relative to, as specified.
+    pub fn relative(&self) -> Smart<RelativeTo> {
+        match self {
+            Self::Linear(linear) => linear.relative,
+            Self::Radial(radial) => radial.relative,
+            Self::Conic(conic) => conic.relative,
+        }
+    }
+
+    /// The bounding box the gradient is relative to, with `auto` resolved.
+    ///
+    /// Shapes default to their own bounding box while text defaults to the
+    /// bounding box of its parent container.
+    pub fn unwrap_relative(&self, on_text: bool) -> RelativeTo {
+        self.relative().unwrap_or(if on_text {
+            RelativeTo::Parent
+        } else {
+            RelativeTo::Self_
+        })
+    }
+
+    /// The angle of the gradient, if it has one.
+    pub fn angle(&self) -> Option<Angle> {
+        match self {
+            Self::Linear(linear) => Some(linear.angle),
+            Self::Radial(_) => None,
+            Self::Conic(conic) => Some(conic.angle),
+        }
+    }
+
+    /// Sample the gradient at a position along its color axis.
+    pub fn sample(&self, t: Ratio) -> Color {
+        let t = t.get().clamp(0.0, 1.0);
+        let stops = self.stops();
+        let space = self.space();
+
+        let Some(i) = stops.iter().position(|&(_, offset)| offset.get() >= t) else {
+            return stops[stops.len() - 1].0;
+        };
+
+        if i == 0 {
+            return stops[0].0;
+        }
+
+        let (c0, o0) = stops[i - 1];
+        let (c1, o1) = stops[i];
+        let span = o1.get() - o0.get();
+        if span <= 0.0 {
+            return c1;
+        }
+
+        let a = space.encode(c0);
+        let b = space.encode(c1);
+        let f = (t - o0.get()) / span;
+        let mixed = [0, 1, 2, 3].map(|k| a[k] + (b[k] - a[k]) * f);
+        space.decode(mixed).into()
+    }
+
+    /// The stops of the gradient, resampled such that interpolating between
+    /// them in sRGB approximates the interpolation in the gradient's color
+    /// space. The resulting stops always span the full range from zero to one.
+    ///
+    /// This is useful for export formats that only support sRGB gradients.
+    pub fn srgb_stops(&self) -> Vec<(Color, Ratio)> {
+        /// How many samples to take per stop interval.
+        const SAMPLES: usize = 16;
+
+        let stops = self.stops();
+        let mut resolved = vec![];
+
+        let (first, start) = stops[0];
+        if start.get() > 0.0 {
+            resolved.push((first, Ratio::zero()));
+        }
+
+        for window in stops.windows(2) {
+            let (c0, o0) = window[0];
+            let o1 = window[1].1;
+            resolved.push((c0, o0));
+
+            if self.space() != ColorSpace::Srgb && o1 > o0 {
+                for i in 1..SAMPLES {
+                    let t = o0 + (o1 - o0) * (i as f64 / SAMPLES as f64);
+                    resolved.push((self.sample(t), t));
+                }
+            }
+        }
+
+        let (last, end) = stops[stops.len() - 1];
+        resolved.push((last, end));
+        if end.get() < 1.0 {
+            resolved.push((last, Ratio::one()));
+        }
+
+        resolved
+    }
+
+    /// Sample the gradient at a point within a bounding box of the given size.
+    ///
+    /// The point and the size must be given in the same (arbitrary) unit.
+    pub fn sample_at(&self, (x, y): (f64, f64), (w, h): (f64, f64)) -> Color {
+        let t = match self {
+            Self::Linear(linear) => {
+                let (sin, cos) = (linear.angle.sin(), linear.angle.cos());
+                let length = (w * cos).abs() + (h * sin).abs();
+                if length <= 0.0 {
+                    0.0
+                } else {
+                    ((x - w / 2.0) * cos + (y - h / 2.0) * sin) / length + 0.5
+                }
+            }
+            Self::Radial(radial) => {
+                let dx = x / w.max(f64::EPSILON) - radial.center.x.get();
+                let dy = y / h.max(f64::EPSILON) - radial.center.y.get();
+                dx.hypot(dy) / radial.radius.get()
+            }
+            Self::Conic(conic) => {
+                let dx = x / w.max(f64::EPSILON) - conic.center.x.get();
+                let dy = y / h.max(f64::EPSILON) - conic.center.y.get();
+                (dy.atan2(dx) - conic.angle.to_rad()).rem_euclid(TAU) / TAU
+            }
+        };
+
+        self.sample(Ratio::new(t))
+    }
+}
+
+impl Debug for Gradient {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        write!(f, "gradient.{}(", self.kind())?;
+
+        for (i, (color, offset)) in self.stops().iter().enumerate() {
+            if i > 0 {
+                f.write_str(", ")?;
+            }
+            write!(f, "({color:?}, {offset:?})")?;
+        }
+
+        match self {
+            Self::Linear(linear) => {
+                if linear.angle != Angle::zero() {
+                    write!(f, ", angle: {:?}", linear.angle)?;
+                }
+            }
+            Self::Radial(radial) => {
+                if radial.center != Axes::splat(Ratio::new(0.5)) {
+                    write!(
+                        f,
+                        ", center: ({:?}, {:?})",
+                        radial.center.x, radial.center.y
+                    )?;
+                }
+                if radial.radius != Ratio::new(0.5) {
+                    write!(f, ", radius: {:?}", radial.radius)?;
+                }
+            }
+            Self::Conic(conic) => {
+                if conic.angle != Angle::zero() {
+                    write!(f, ", angle: {:?}", conic.angle)?;
+                }
+                if conic.center != Axes::splat(Ratio::new(0.5)) {
+                    write!(f, ", center: ({:?}, {:?})", conic.center.x, conic.center.y)?;
+                }
+            }
+        }
+
+        if self.space() != ColorSpace::Oklab {
+            write!(f, ", space: {:?}", self.space())?;
+        }
+
+        if let Smart::Custom(relative) = self.relative() {
+            write!(f, ", relative: {relative:?}")?;
+        }
+
+        f.write_str(")")
+    }
+}
+
+cast_from_value! {
+    Gradient: "gradient",
+}
+
+/// A color stop of a gradient, as specified by the user.
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
+pub struct GradientStop {
+    /// The color of the stop.
+    pub color: Color,
+    /// The offset of the stop along the gradient's color axis.
+    pub offset: Option<Ratio>,
+}
+
+cast_from_value! {
+    GradientStop,
+    color: Color => Self { color, offset: None },
+    array: Array => {
+        let mut iter = array.into_iter();
+        match (iter.next(), iter.next(), iter.next()) {
+            (Some(a), Some(b), None) => Self {
+                color: a.cast()?,
+                offset: Some(b.cast()?),
+            },
+            _ => Err("a color stop must contain exactly two entries")?,
+        }
+    },
+}
+
+/// Validate the stops and distribute missing offsets evenly.
+fn process_stops(stops: Vec<GradientStop>) -> StrResult<Vec<(Color, Ratio)>> {
+    if stops.len() < 2 {
+        return Err("a gradient must have at least two stops".into());
+    }
+
+    let with_offset = stops.iter().filter(|stop| stop.offset.is_some()).count();
+    if with_offset == 0 {
+        let last = (stops.len() - 1) as f64;
+        return Ok(stops
+            .into_iter()
+            .enumerate()
+            .map(|(i, stop)| (stop.color, Ratio::new(i as f64 / last)))
+            .collect());
+    }
+
+    if with_offset != stops.len() {
+        return Err("either all or no stops must have an offset".into());
+    }
+
+    let mut last = Ratio::zero();
+    let mut processed = Vec::with_capacity(stops.len());
+    for stop in stops {
+        let offset = stop.offset.unwrap();
+        if !(0.0..=1.0).contains(&offset.get()) {
+            return Err("offset must be between 0% and 100%".into());
+        }
+        if offset < last {
+            return Err("offsets must be in ascending order".into());
+        }
+        processed.push((stop.color, offset));
+        last = offset;
+    }
+
+    Ok(processed)
+}
+
+/// The color space in which a gradient interpolates between its stops.
+#[derive(Copy, Clone, Eq, PartialEq, Hash)]
+pub enum ColorSpace {
+    /// The perceptually uniform Oklab color space.
+    Oklab,
+    /// The sRGB color space.
+    Srgb,
+    /// The sRGB color space with a linear transfer function.
+    LinearRgb,
+}
+
+impl ColorSpace {
+    /// Convert a color into this space's components, with alpha last.
+    fn encode(self, color: Color) -> [f64; 4] {
+        let RgbaColor { r, g, b, a } = color.to_rgba();
+        let alpha = a as f64 / 255.0;
+        match self {
+            Self::Srgb => [r as f64 / 255.0, g as f64 / 255.0, b as f64 / 255.0, alpha],
+            Self::LinearRgb => [linearize(r), linearize(g), linearize(b), alpha],
+            Self::Oklab => {
+                let [l, a, b] =
+                    linear_to_oklab([linearize(r), linearize(g), linearize(b)]);
+                [l, a, b, alpha]
+            }
+        }
+    }
+
+    /// Convert components of this space back into an RGBA color.
+    fn decode(self, [x, y, z, alpha]: [f64; 4]) -> RgbaColor {
+        let alpha = round_u8(alpha * 255.0);
+        match self {
+            Self::Srgb => RgbaColor::new(
+                round_u8(x * 255.0),
+                round_u8(y * 255.0),
+                round_u8(z * 255.0),
+                alpha,
+            ),
+            Self::LinearRgb => {
+                RgbaColor::new(delinearize(x), delinearize(y), delinearize(z), alpha)
+            }
+            Self::Oklab => {
+                let [r, g, b] = oklab_to_linear([x, y, z]);
+                RgbaColor::new(delinearize(r), delinearize(g), delinearize(b), alpha)
+            }
+        }
+    }
+}
+
+impl Debug for ColorSpace {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        f.pad(match self {
+            Self::Oklab => "\"oklab\"",
+            Self::Srgb => "\"srgb\"",
+            Self::LinearRgb => "\"linear-rgb\"",
+        })
+    }
+}
+
+cast_from_value! {
+    ColorSpace,
+    /// Interpolate in the perceptually uniform Oklab color space.
+    "oklab" => Self::Oklab,
+    /// Interpolate in the sRGB color space.
+    "srgb" => Self::Srgb,
+    /// Interpolate in linear RGB, i.e. by physical light intensity.
+    "linear-rgb" => Self::LinearRgb,
+}
+
+cast_to_value! {
+    v: ColorSpace => Value::from(match v {
+        ColorSpace::Oklab => "oklab",
+        ColorSpace::Srgb => "srgb",
+        ColorSpace::LinearRgb => "linear-rgb",
+    })
+}
+
+/// The bounding box a gradient or pattern is relative to.
+#[derive(Copy, Clone, Eq, PartialEq, Hash)]
+pub enum RelativeTo {
+    /// The bounding box of the painted shape or text itself.
+    Self_,
+    /// The bounding box of the container the shape or text is placed in.
+    Parent,
+}
+
+impl Debug for RelativeTo {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        f.pad(match self {
+            Self::Self_ => "\"self\"",
+            Self::Parent => "\"parent\"",
+        })
+    }
+}
+
+cast_from_value! {
+    RelativeTo,
+    /// Relative to the bounding box of the painted element itself.
+    "self" => Self::Self_,
+    /// Relative to the bounding box of the containing block or page.
+    "parent" => Self::Parent,
+}
+
+cast_to_value! {
+    v: RelativeTo => Value::from(match v {
+        RelativeTo::Self_ => "self",
+        RelativeTo::Parent => "parent",
+    })
+}
+
+/// Convert an 8-bit sRGB component into a linear one.
+fn linearize(c: u8) -> f64 {
+    let c = c as f64 / 255.0;
+    if c <= 0.04045 {
+        c / 12.92
+    } else {
+        ((c + 0.055) / 1.055).powf(2.4)
+    }
+}
+
+/// Convert a linear component into an 8-bit sRGB one.
+fn delinearize(c: f64) -> u8 {
+    let c = c.clamp(0.0, 1.0);
+    let c = if c <= 0.0031308 { 12.92 * c } else { 1.055 * c.powf(1.0 / 2.4) - 0.055 };
+    round_u8(c * 255.0)
+}
+
+/// Convert linear RGB into Oklab.
+fn linear_to_oklab([r, g, b]: [f64; 3]) -> [f64; 3] {
+    let l = (0.4122214708 * r + 0.5363325363 * g + 0.0514459929 * b).cbrt();
+    let m = (0.2119034982 * r + 0.6806995451 * g + 0.1073969566 * b).cbrt();
+    let s = (0.0883024619 * r + 0.2817188376 * g + 0.6299787005 * b).cbrt();
+    [
+        0.2104542553 * l + 0.7936177850 * m - 0.0040720468 * s,
+        1.9779984951 * l - 2.4285922050 * m + 0.4505937099 * s,
+        0.0259040371 * l + 0.7827717662 * m - 0.8086757660 * s,
+    ]
+}
+
+/// Convert Oklab into linear RGB.
+fn oklab_to_linear([l, a, b]: [f64; 3]) -> [f64; 3] {
+    let l_ = (l + 0.3963377774 * a + 0.2158037573 * b).powi(3);
+    let m_ = (l - 0.1055613458 * a - 0.0638541728 * b).powi(3);
+    let s_ = (l - 0.0894841775 * a - 1.2914855480 * b).powi(3);
+    [
+        4.0767416621 * l_ - 3.3077115913 * m_ + 0.2309699292 * s_,
+        -1.2684380046 * l_ + 2.6097574011 * m_ - 0.3413193965 * s_,
+        -0.0041960863 * l_ - 0.7034186147 * m_ + 1.7076147010 * s_,
+    ]
+}
+
+/// Convert to the closest u8.
+fn round_u8(value: f64) -> u8 {
+    value.round().clamp(0.0, 255.0) as u8
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_gradient_sample_srgb() {
+        let stops = vec![
+            GradientStop {
+                color: RgbaColor::new(0, 0, 0, 255).into(),
+                offset: None,
+            },
+            GradientStop {
+                color: RgbaColor::new(255, 255, 255, 255).into(),
+                offset: None,
+            },
+        ];
+        let gradient =
+            Gradient::linear(stops, Angle::zero(), ColorSpace::Srgb, Smart::Auto)
+                .unwrap();
+        assert_eq!(gradient.sample(Ratio::zero()), RgbaColor::new(0, 0, 0, 255).into());
+        assert_eq!(
+            gradient.sample(Ratio::new(0.5)),
+            RgbaColor::new(128, 128, 128, 255).into()
+        );
+        assert_eq!(
+            gradient.sample(Ratio::one()),
+            RgbaColor::new(255, 255, 255, 255).into()
+        );
+    }
+
+    #[test]
+    fn test_oklab_roundtrip() {
+        for color in [Color::RED, Color::BLUE, Color::OLIVE, Color::WHITE] {
+            let space = ColorSpace::Oklab;
+            assert_eq!(space.decode(space.encode(color)), color.to_rgba());
+        }
+    }
+}
@@ -11,9 +11,11 @@ mod dir;
 mod ellipse;
 mod em;
 mod fr;
+mod gradient;
 mod length;
 mod paint;
 mod path;
+mod pattern;
 mod point;
 mod ratio;
 mod rel;
@@ -35,9 +37,11 @@ pub use self::dir::*;
 pub use self::ellipse::*;
 pub use self::em::*;
 pub use self::fr::*;
+pub use self::gradient::*;
 pub use self::length::*;
 pub use self::paint::*;
 pub use self::path::*;
+pub use self::pattern::*;
 pub use self::point::*;
 pub use self::ratio::*;
 pub use self::rel::*;
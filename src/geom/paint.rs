@@ -3,10 +3,28 @@ use std::str::FromStr;
 use super::*;
 
 /// How a fill or stroke should be painted.
-#[derive(Copy, Clone, Eq, PartialEq, Hash)]
+#[derive(Clone, Eq, PartialEq, Hash)]
 pub enum Paint {
     /// A solid color.
     Solid(Color),
+    /// A gradient.
+    Gradient(Gradient),
+    /// A repeating pattern.
+    Pattern(Pattern),
+}
+
+impl Paint {
+    /// The bounding box the paint is relative to, with `auto` resolved.
+    ///
+    /// Solid colors look the same everywhere, so they are simply relative to
+    /// the painted item itself.
+    pub fn unwrap_relative(&self, on_text: bool) -> RelativeTo {
+        match self {
+            Self::Solid(_) => RelativeTo::Self_,
+            Self::Gradient(gradient) => gradient.unwrap_relative(on_text),
+            Self::Pattern(pattern) => pattern.unwrap_relative(on_text),
+        }
+    }
 }
 
 impl<T: Into<Color>> From<T> for Paint {
@@ -19,6 +37,8 @@ impl Debug for Paint {
     fn fmt(&self, f: &mut Formatter) -> fmt::Result {
         match self {
             Self::Solid(color) => color.fmt(f),
+            Self::Gradient(gradient) => gradient.fmt(f),
+            Self::Pattern(pattern) => pattern.fmt(f),
         }
     }
 }
@@ -26,10 +46,16 @@ impl Debug for Paint {
 cast_from_value! {
     Paint,
     color: Color => Self::Solid(color),
+    gradient: Gradient => Self::Gradient(gradient),
+    pattern: Pattern => Self::Pattern(pattern),
 }
 
 cast_to_value! {
-    Paint::Solid(color): Paint => Value::Color(color)
+    v: Paint => match v {
+        Paint::Solid(color) => Value::Color(color),
+        Paint::Gradient(gradient) => gradient.into(),
+        Paint::Pattern(pattern) => pattern.into(),
+    }
 }
 
 /// A color in a dynamic format.
@@ -51,4 +51,29 @@ impl Path {
     pub fn close_path(&mut self) {
         self.0.push(PathItem::ClosePath);
     }
+
+    /// The size of the path's bounding box, measured from the origin.
+    ///
+    /// Control points are included, so the box may be slightly too large.
+    pub fn bbox_size(&self) -> Size {
+        let mut size = Size::zero();
+        let mut extend = |p: Point| {
+            size.x.set_max(p.x);
+            size.y.set_max(p.y);
+        };
+
+        for item in &self.0 {
+            match *item {
+                PathItem::MoveTo(p) | PathItem::LineTo(p) => extend(p),
+                PathItem::CubicTo(p1, p2, p3) => {
+                    extend(p1);
+                    extend(p2);
+                    extend(p3);
+                }
+                PathItem::ClosePath => {}
+            }
+        }
+
+        size
+    }
 }
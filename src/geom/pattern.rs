@@ -0,0 +1,87 @@
+use std::sync::Arc;
+
+use comemo::Prehashed;
+
+use super::*;
+use crate::doc::Frame;
+
+/// A repeating pattern fill, built from a tile of content.
+#[derive(Clone, Eq, PartialEq, Hash)]
+pub struct Pattern(Arc<Repr>);
+
+/// The internal representation of a [`Pattern`].
+#[derive(Eq, PartialEq, Hash)]
+struct Repr {
+    /// The laid out content of a single tile.
+    frame: Prehashed<Frame>,
+    /// The gap between adjacent tiles.
+    spacing: Size,
+    /// The bounding box the pattern is relative to.
+    relative: Smart<RelativeTo>,
+}
+
+impl Pattern {
+    /// Create a new pattern from a laid out tile.
+    pub fn new(frame: Frame, spacing: Size, relative: Smart<RelativeTo>) -> Self {
+        Self(Arc::new(Repr { frame: Prehashed::new(frame), spacing, relative }))
+    }
+
+    /// The laid out content of a single tile.
+    pub fn frame(&self) -> &Frame {
+        &self.0.frame
+    }
+
+    /// The size of a single tile.
+    pub fn size(&self) -> Size {
+        self.0.frame.size()
+    }
+
+    /// The gap between adjacent tiles.
+    pub fn spacing(&self) -> Size {
+        self.0.spacing
+    }
+
+    /// The distance from the start of one tile to the start of the next.
+    pub fn step(&self) -> Size {
+        self.size() + self.spacing()
+    }
+
+    /// The bounding box the pattern is relative to, as specified.
+    pub fn relative(&self) -> Smart<RelativeTo> {
+        self.0.relative
+    }
+
+    /// The bounding box the pattern is relative to, with `auto` resolved.
+    ///
+    /// Shapes default to their own bounding box while text defaults to the
+    /// bounding box of its parent container.
+    pub fn unwrap_relative(&self, on_text: bool) -> RelativeTo {
+        self.relative().unwrap_or(if on_text {
+            RelativeTo::Parent
+        } else {
+            RelativeTo::Self_
+        })
+    }
+}
+
+impl Debug for Pattern {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        let size = self.size();
+        write!(f, "pattern(size: ({:?}, {:?})", size.x, size.y)?;
+
+        let spacing = self.spacing();
+        if !spacing.x.is_zero() || !spacing.y.is_zero() {
+            write!(f, ", spacing: ({:?}, {:?})", spacing.x, spacing.y)?;
+        }
+
+        if let Smart::Custom(relative) = self.relative() {
+            write!(f, ", relative: {relative:?}")?;
+        }
+
+        f.write_str(", ..)")
+    }
+}
+
+cast_from_value! {
+    Pattern: "pattern",
+}
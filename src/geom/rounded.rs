@@ -14,7 +14,7 @@ pub fn rounded_rect(
         res.push(Shape {
             geometry: fill_geometry(size, radius),
             fill,
-            stroke: if stroke.is_uniform() { stroke.top } else { None },
+            stroke: if stroke.is_uniform() { stroke.top.clone() } else { None },
         });
     }
 
@@ -55,7 +55,7 @@ fn stroke_segments(
     let max_radius = size.x.min(size.y) / 2.0;
 
     for side in [Side::Top, Side::Right, Side::Bottom, Side::Left] {
-        let continuous = stroke.get(side) == stroke.get(side.next_cw());
+        let continuous = stroke.get_ref(side) == stroke.get_ref(side.next_cw());
         connection = connection.advance(continuous && side != Side::Left);
         always_continuous &= continuous;
 
@@ -69,7 +69,7 @@ fn stroke_segments(
         );
 
         if !continuous {
-            res.push((mem::take(&mut path), stroke.get(side)));
+            res.push((mem::take(&mut path), stroke.get_ref(side).clone()));
         }
     }
 
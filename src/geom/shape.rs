@@ -32,4 +32,13 @@ impl Geometry {
     pub fn stroked(self, stroke: Stroke) -> Shape {
         Shape { geometry: self, fill: None, stroke: Some(stroke) }
     }
+
+    /// The size of the geometry's bounding box, measured from its origin.
+    pub fn bbox_size(&self) -> Size {
+        match self {
+            Self::Line(target) => Size::new(target.x.abs(), target.y.abs()),
+            Self::Rect(size) => *size,
+            Self::Path(path) => path.bbox_size(),
+        }
+    }
 }
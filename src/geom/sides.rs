@@ -60,6 +60,16 @@ impl<T> Sides<T> {
         [&self.left, &self.top, &self.right, &self.bottom].into_iter()
     }
 
+    /// Borrow the component for the specified side.
+    pub fn get_ref(&self, side: Side) -> &T {
+        match side {
+            Side::Left => &self.left,
+            Side::Top => &self.top,
+            Side::Right => &self.right,
+            Side::Bottom => &self.bottom,
+        }
+    }
+
     /// Whether all sides are equal.
     pub fn is_uniform(&self) -> bool
     where
@@ -180,30 +190,40 @@ impl Side {
 
 impl<T> Cast for Sides<Option<T>>
 where
-    T: Default + Cast + Copy,
+    T: Default + Cast + Clone,
 {
     fn is(value: &Value) -> bool {
         matches!(value, Value::Dict(_)) || T::is(value)
     }
 
     fn cast(mut value: Value) -> StrResult<Self> {
+        let keys = ["left", "top", "right", "bottom", "x", "y", "rest"];
         if let Value::Dict(dict) = &mut value {
-            let mut take = |key| dict.take(key).ok().map(T::cast).transpose();
-
-            let rest = take("rest")?;
-            let x = take("x")?.or(rest);
-            let y = take("y")?.or(rest);
-            let sides = Sides {
-                left: take("left")?.or(x),
-                top: take("top")?.or(y),
-                right: take("right")?.or(x),
-                bottom: take("bottom")?.or(y),
-            };
-
-            dict.finish(&["left", "top", "right", "bottom", "x", "y", "rest"])?;
-
-            Ok(sides)
-        } else if T::is(&value) {
+            // A dictionary without any of the side keys can also be a valid
+            // value for all sides at once (e.g. a stroke dictionary).
+            if dict.is_empty()
+                || dict.iter().any(|(key, _)| keys.contains(&key.as_str()))
+                || !T::is(&Value::Dict(dict.clone()))
+            {
+                let mut take = |key| dict.take(key).ok().map(T::cast).transpose();
+
+                let rest = take("rest")?;
+                let x = take("x")?.or_else(|| rest.clone());
+                let y = take("y")?.or_else(|| rest.clone());
+                let sides = Sides {
+                    left: take("left")?.or_else(|| x.clone()),
+                    top: take("top")?.or_else(|| y.clone()),
+                    right: take("right")?.or(x),
+                    bottom: take("bottom")?.or(y),
+                };
+
+                dict.finish(&keys)?;
+
+                return Ok(sides);
+            }
+        }
+
+        if T::is(&value) {
             Ok(Self::splat(Some(T::cast(value)?)))
         } else {
             <Self as Cast>::error(value)
@@ -1,12 +1,20 @@
 use super::*;
 
 /// A stroke of a geometric shape.
-#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
+#[derive(Debug, Clone, Eq, PartialEq, Hash)]
 pub struct Stroke {
     /// The stroke's paint.
     pub paint: Paint,
     /// The stroke's thickness.
     pub thickness: Abs,
+    /// The stroke's line cap.
+    pub line_cap: LineCap,
+    /// The stroke's line join.
+    pub line_join: LineJoin,
+    /// The stroke's line dash pattern.
+    pub dash_pattern: Option<DashPattern<Abs, Abs>>,
+    /// The miter limit. Defaults to 4.0, same as `tiny-skia`.
+    pub miter_limit: Scalar,
 }
 
 impl Default for Stroke {
@@ -14,29 +22,61 @@ impl Default for Stroke {
         Self {
             paint: Paint::Solid(Color::BLACK),
             thickness: Abs::pt(1.0),
+            line_cap: LineCap::Butt,
+            line_join: LineJoin::Miter,
+            dash_pattern: None,
+            miter_limit: Scalar(4.0),
         }
     }
 }
 
 /// A partial stroke representation.
 ///
-/// In this representation, both fields are optional so that you can pass either
-/// just a paint (`red`), just a thickness (`0.1em`) or both (`2pt + red`) where
-/// this is expected.
-#[derive(Default, Copy, Clone, Eq, PartialEq, Hash)]
+/// In this representation, all fields are optional so that you can pass either
+/// just a paint (`red`), just a thickness (`0.1em`), both (`2pt + red`) or a
+/// dictionary with more details (`(thickness: 2pt, dash: "dotted")`) where this
+/// is expected.
+#[derive(Default, Clone, Eq, PartialEq, Hash)]
 pub struct PartialStroke<T = Length> {
     /// The stroke's paint.
     pub paint: Smart<Paint>,
     /// The stroke's thickness.
     pub thickness: Smart<T>,
+    /// The stroke's line cap.
+    pub line_cap: Smart<LineCap>,
+    /// The stroke's line join.
+    pub line_join: Smart<LineJoin>,
+    /// The stroke's line dash pattern.
+    pub dash_pattern: Smart<Option<DashPattern<T>>>,
+    /// The miter limit.
+    pub miter_limit: Smart<Scalar>,
 }
 
 impl PartialStroke<Abs> {
     /// Unpack the stroke, filling missing fields from the `default`.
     pub fn unwrap_or(self, default: Stroke) -> Stroke {
+        let thickness = self.thickness.unwrap_or(default.thickness);
+        let dash_pattern = self
+            .dash_pattern
+            .map(|pattern| {
+                pattern.map(|pattern| DashPattern {
+                    array: pattern
+                        .array
+                        .into_iter()
+                        .map(|length| length.finish(thickness))
+                        .collect(),
+                    phase: pattern.phase,
+                })
+            })
+            .unwrap_or(default.dash_pattern);
+
         Stroke {
             paint: self.paint.unwrap_or(default.paint),
-            thickness: self.thickness.unwrap_or(default.thickness),
+            thickness,
+            line_cap: self.line_cap.unwrap_or(default.line_cap),
+            line_join: self.line_join.unwrap_or(default.line_join),
+            dash_pattern,
+            miter_limit: self.miter_limit.unwrap_or(default.miter_limit),
         }
     }
 
@@ -48,26 +88,96 @@ impl PartialStroke<Abs> {
 
 impl<T: Debug> Debug for PartialStroke<T> {
     fn fmt(&self, f: &mut Formatter) -> fmt::Result {
-        match (self.paint, &self.thickness) {
-            (Smart::Custom(paint), Smart::Custom(thickness)) => {
-                write!(f, "{thickness:?} + {paint:?}")
+        let Self {
+            paint,
+            thickness,
+            line_cap,
+            line_join,
+            dash_pattern,
+            miter_limit,
+        } = &self;
+
+        if line_cap.is_auto()
+            && line_join.is_auto()
+            && dash_pattern.is_auto()
+            && miter_limit.is_auto()
+        {
+            return match (paint, thickness) {
+                (Smart::Custom(paint), Smart::Custom(thickness)) => {
+                    write!(f, "{thickness:?} + {paint:?}")
+                }
+                (Smart::Custom(paint), Smart::Auto) => paint.fmt(f),
+                (Smart::Auto, Smart::Custom(thickness)) => thickness.fmt(f),
+                (Smart::Auto, Smart::Auto) => f.pad("<stroke>"),
+            };
+        }
+
+        let mut parts = vec![];
+        if let Smart::Custom(paint) = paint {
+            parts.push(format!("paint: {paint:?}"));
+        }
+        if let Smart::Custom(thickness) = thickness {
+            parts.push(format!("thickness: {thickness:?}"));
+        }
+        if let Smart::Custom(cap) = line_cap {
+            parts.push(format!("cap: {cap:?}"));
+        }
+        if let Smart::Custom(join) = line_join {
+            parts.push(format!("join: {join:?}"));
+        }
+        if let Smart::Custom(dash) = dash_pattern {
+            match dash {
+                Some(dash) => parts.push(format!("dash: {dash:?}")),
+                None => parts.push("dash: none".into()),
             }
-            (Smart::Custom(paint), Smart::Auto) => paint.fmt(f),
-            (Smart::Auto, Smart::Custom(thickness)) => thickness.fmt(f),
-            (Smart::Auto, Smart::Auto) => f.pad("<stroke>"),
         }
+        if let Smart::Custom(miter_limit) = miter_limit {
+            parts.push(format!("miter-limit: {miter_limit:?}"));
+        }
+
+        write!(f, "({})", parts.join(", "))
     }
 }
 
 cast_from_value! {
     PartialStroke: "stroke",
     thickness: Length => Self {
-        paint: Smart::Auto,
         thickness: Smart::Custom(thickness),
+        ..Default::default()
     },
     color: Color => Self {
         paint: Smart::Custom(color.into()),
-        thickness: Smart::Auto,
+        ..Default::default()
+    },
+    gradient: Gradient => Self {
+        paint: Smart::Custom(Paint::Gradient(gradient)),
+        ..Default::default()
+    },
+    pattern: Pattern => Self {
+        paint: Smart::Custom(Paint::Pattern(pattern)),
+        ..Default::default()
+    },
+    mut dict: Dict => {
+        fn take<T: Cast<Value>>(dict: &mut Dict, key: &str) -> StrResult<Smart<T>> {
+            Ok(dict.take(key).ok().map(T::cast).transpose()?.map_or(Smart::Auto, Smart::Custom))
+        }
+
+        let paint = take::<Paint>(&mut dict, "paint")?;
+        let thickness = take::<Length>(&mut dict, "thickness")?;
+        let line_cap = take::<LineCap>(&mut dict, "cap")?;
+        let line_join = take::<LineJoin>(&mut dict, "join")?;
+        let dash_pattern = take::<Option<DashPattern>>(&mut dict, "dash")?;
+        let miter_limit = take::<f64>(&mut dict, "miter-limit")?;
+        dict.finish(&["paint", "thickness", "cap", "join", "dash", "miter-limit"])?;
+
+        Self {
+            paint,
+            thickness,
+            line_cap,
+            line_join,
+            dash_pattern,
+            miter_limit: miter_limit.map(Scalar),
+        }
     },
 }
 
@@ -78,6 +188,10 @@ impl Resolve for PartialStroke {
         PartialStroke {
             paint: self.paint,
             thickness: self.thickness.resolve(styles),
+            line_cap: self.line_cap,
+            line_join: self.line_join,
+            dash_pattern: self.dash_pattern.resolve(styles),
+            miter_limit: self.miter_limit,
         }
     }
 }
@@ -89,6 +203,186 @@ impl Fold for PartialStroke<Abs> {
         Self {
             paint: self.paint.or(outer.paint),
             thickness: self.thickness.or(outer.thickness),
+            line_cap: self.line_cap.or(outer.line_cap),
+            line_join: self.line_join.or(outer.line_join),
+            dash_pattern: self.dash_pattern.or(outer.dash_pattern),
+            miter_limit: self.miter_limit.or(outer.miter_limit),
+        }
+    }
+}
+
+/// The line cap of a stroke.
+#[derive(Cast, Copy, Clone, Eq, PartialEq, Hash)]
+pub enum LineCap {
+    /// Square stroke cap with the edge at the stroke's end point.
+    Butt,
+    /// Circular stroke cap centered at the stroke's end point.
+    Round,
+    /// Square stroke cap centered at the stroke's end point.
+    Square,
+}
+
+impl Debug for LineCap {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        match self {
+            LineCap::Butt => write!(f, "\"butt\""),
+            LineCap::Round => write!(f, "\"round\""),
+            LineCap::Square => write!(f, "\"square\""),
+        }
+    }
+}
+
+/// The line join of a stroke.
+#[derive(Cast, Copy, Clone, Eq, PartialEq, Hash)]
+pub enum LineJoin {
+    /// Segments are joined with sharp edges. Sharp bends exceeding the miter
+    /// limit are beveled instead.
+    Miter,
+    /// Segments are joined with circular corners.
+    Round,
+    /// Segments are joined with a bevel (a straight edge connecting the butts
+    /// of the joined segments).
+    Bevel,
+}
+
+impl Debug for LineJoin {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        match self {
+            LineJoin::Miter => write!(f, "\"miter\""),
+            LineJoin::Round => write!(f, "\"round\""),
+            LineJoin::Bevel => write!(f, "\"bevel\""),
+        }
+    }
+}
+
+/// A line dash pattern.
+#[derive(Clone, Eq, PartialEq, Hash)]
+pub struct DashPattern<T = Length, DT = DashLength<T>> {
+    /// The dash array.
+    pub array: Vec<DT>,
+    /// The dash phase.
+    pub phase: T,
+}
+
+impl<T: Debug, DT: Debug> Debug for DashPattern<T, DT> {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        write!(f, "(array: (")?;
+        for (i, elem) in self.array.iter().enumerate() {
+            if i == 0 {
+                write!(f, "{elem:?}")?;
+            } else {
+                write!(f, ", {elem:?}")?;
+            }
+        }
+        write!(f, "), phase: {:?})", self.phase)
+    }
+}
+
+impl<T: Default> From<Vec<DashLength<T>>> for DashPattern<T> {
+    fn from(array: Vec<DashLength<T>>) -> Self {
+        Self { array, phase: T::default() }
+    }
+}
+
+// Same names as tikz:
+// https://tex.stackexchange.com/questions/45275/tikz-get-values-for-predefined-dash-patterns
+cast_from_value! {
+    DashPattern,
+
+    "solid" => Vec::new().into(),
+    "dotted" => vec![DashLength::LineWidth, Abs::pt(2.0).into()].into(),
+    "densely-dotted" => vec![DashLength::LineWidth, Abs::pt(1.0).into()].into(),
+    "loosely-dotted" => vec![DashLength::LineWidth, Abs::pt(4.0).into()].into(),
+    "dashed" => vec![Abs::pt(3.0).into(), Abs::pt(3.0).into()].into(),
+    "densely-dashed" => vec![Abs::pt(3.0).into(), Abs::pt(2.0).into()].into(),
+    "loosely-dashed" => vec![Abs::pt(3.0).into(), Abs::pt(6.0).into()].into(),
+    "dash-dotted" => vec![
+        Abs::pt(3.0).into(),
+        Abs::pt(2.0).into(),
+        DashLength::LineWidth,
+        Abs::pt(2.0).into(),
+    ].into(),
+    "densely-dash-dotted" => vec![
+        Abs::pt(3.0).into(),
+        Abs::pt(1.0).into(),
+        DashLength::LineWidth,
+        Abs::pt(1.0).into(),
+    ].into(),
+    "loosely-dash-dotted" => vec![
+        Abs::pt(3.0).into(),
+        Abs::pt(4.0).into(),
+        DashLength::LineWidth,
+        Abs::pt(4.0).into(),
+    ].into(),
+
+    array: Vec<DashLength> => Self { array, phase: Length::zero() },
+    mut dict: Dict => {
+        let array: Vec<DashLength> = dict.take("array")?.cast()?;
+        let phase = dict.take("phase").ok().map(Value::cast)
+            .transpose()?.unwrap_or(Length::zero());
+        dict.finish(&["array", "phase"])?;
+        Self { array, phase }
+    },
+}
+
+impl Resolve for DashPattern {
+    type Output = DashPattern<Abs>;
+
+    fn resolve(self, styles: StyleChain) -> Self::Output {
+        DashPattern {
+            array: self.array.into_iter().map(|l| l.resolve(styles)).collect(),
+            phase: self.phase.resolve(styles),
+        }
+    }
+}
+
+/// The length of a dash in a line dash pattern.
+#[derive(Clone, Copy, Eq, PartialEq, Hash)]
+pub enum DashLength<T = Length> {
+    /// The dash is as long as the stroke is thick.
+    LineWidth,
+    /// The dash has a fixed length.
+    Length(T),
+}
+
+impl From<Abs> for DashLength {
+    fn from(length: Abs) -> Self {
+        Self::Length(length.into())
+    }
+}
+
+impl<T> DashLength<T> {
+    /// Resolve the dash length, given the stroke's thickness.
+    fn finish(self, line_width: T) -> T {
+        match self {
+            Self::LineWidth => line_width,
+            Self::Length(length) => length,
+        }
+    }
+}
+
+impl<T: Debug> Debug for DashLength<T> {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        match self {
+            Self::LineWidth => write!(f, "\"dot\""),
+            Self::Length(length) => length.fmt(f),
+        }
+    }
+}
+
+cast_from_value! {
+    DashLength,
+    "dot" => Self::LineWidth,
+    length: Length => Self::Length(length),
+}
+
+impl Resolve for DashLength {
+    type Output = DashLength<Abs>;
+
+    fn resolve(self, styles: StyleChain) -> Self::Output {
+        match self {
+            Self::LineWidth => DashLength::LineWidth,
+            Self::Length(length) => DashLength::Length(length.resolve(styles)),
         }
     }
 }
@@ -386,6 +386,13 @@ fn field_access_completions(ctx: &mut CompletionContext, value: &Value) {
                 ctx.value_completion(Some(name.clone()), value, true, None);
             }
         }
+        Value::Func(func) => {
+            if let Some(info) = func.info() {
+                for (name, value) in info.scope.iter() {
+                    ctx.value_completion(Some(name.clone()), value, true, None);
+                }
+            }
+        }
         _ => {}
     }
 }
@@ -1094,7 +1101,9 @@ impl<'a> CompletionContext<'a> {
             let mut sibling = Some(node.clone());
             while let Some(node) = &sibling {
                 if let Some(v) = node.cast::<ast::LetBinding>() {
-                    defined.insert(v.binding().take());
+                    for ident in v.kind().idents() {
+                        defined.insert(ident.take());
+                    }
                 }
                 sibling = node.prev_sibling();
             }
@@ -1106,7 +1115,9 @@ impl<'a> CompletionContext<'a> {
                         if let Some(key) = pattern.key() {
                             defined.insert(key.take());
                         }
-                        defined.insert(pattern.value().take());
+                        for ident in pattern.value().idents() {
+                            defined.insert(ident.take());
+                        }
                     }
                 }
 
@@ -0,0 +1,670 @@
+use std::path::PathBuf;
+
+use crate::package::{PackageManifest, PackageSpec, MANIFEST};
+use crate::syntax::ast::{self, AstNode};
+use crate::syntax::{LinkedNode, Source, SourceId, Span, SyntaxKind, SyntaxNode};
+use crate::util::PathExt;
+use crate::World;
+
+/// How many imports deep definitions are followed, which also guards against
+/// cyclic imports.
+const MAX_DEPTH: usize = 16;
+
+/// Find the definition of the identifier or label at the cursor.
+///
+/// For an identifier, returns the span of the identifier that binds it: in a
+/// `let` binding, a closure's parameters or a `for` loop's pattern, possibly
+/// in another file. For a label or reference, returns the span of the label
+/// that is attached to the referenced element.
+///
+/// Returns `None` for identifiers that are not defined in the project's
+/// sources, like those of the standard library.
+pub fn definition(
+    world: &(dyn World + 'static),
+    source: &Source,
+    cursor: usize,
+) -> Option<Span> {
+    let leaf = LinkedNode::new(source.root()).leaf_at(cursor)?;
+    match leaf.kind() {
+        SyntaxKind::Ident | SyntaxKind::MathIdent => {
+            resolve_ident(world, &leaf, 0).map(|binding| binding.span)
+        }
+        SyntaxKind::RefMarker | SyntaxKind::Label => {
+            let name = label_name(&leaf)?;
+            project(world, source.id()).into_iter().find_map(|id| {
+                let mut spans = vec![];
+                find_labels(world.source(id).root(), &name, true, &mut spans);
+                spans.into_iter().next()
+            })
+        }
+        _ => None,
+    }
+}
+
+/// Find all uses of the binding or label at the cursor across the project's
+/// sources, including its definition.
+///
+/// The project consists of the main source, the source the cursor is in and
+/// all sources they import or include, directly or indirectly. Each use is
+/// reported with the span of its identifier, label or reference, so that it
+/// can be renamed.
+pub fn references(
+    world: &(dyn World + 'static),
+    source: &Source,
+    cursor: usize,
+) -> Vec<Span> {
+    let Some(leaf) = LinkedNode::new(source.root()).leaf_at(cursor) else {
+        return vec![];
+    };
+
+    let mut spans = vec![];
+    match leaf.kind() {
+        SyntaxKind::Ident | SyntaxKind::MathIdent => {
+            let Some(target) = resolve_ident(world, &leaf, 0) else { return vec![] };
+            for id in project(world, source.id()) {
+                let root = LinkedNode::new(world.source(id).root());
+                find_uses(world, &root, leaf.text(), target.span, &mut spans);
+            }
+        }
+        SyntaxKind::RefMarker | SyntaxKind::Label => {
+            let Some(name) = label_name(&leaf) else { return vec![] };
+            for id in project(world, source.id()) {
+                find_labels(world.source(id).root(), &name, false, &mut spans);
+            }
+        }
+        _ => {}
+    }
+
+    spans
+}
+
+/// An identifier that binds a name.
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+struct Binding {
+    /// The span of the binding identifier.
+    span: Span,
+    /// The imported file if the binding is the name of a module import.
+    module: Option<SourceId>,
+}
+
+impl Binding {
+    fn new(span: Span) -> Self {
+        Self { span, module: None }
+    }
+}
+
+/// Resolve an identifier to its binding.
+fn resolve_ident(
+    world: &(dyn World + 'static),
+    leaf: &LinkedNode,
+    depth: usize,
+) -> Option<Binding> {
+    let name = leaf.text().as_str();
+    let parent = leaf.parent()?;
+
+    // The field of a field access on an imported module: `utils.name`.
+    if parent.kind() == SyntaxKind::FieldAccess && leaf.next_sibling().is_none() {
+        let target = parent.children().next()?;
+        if !matches!(target.kind(), SyntaxKind::Ident | SyntaxKind::MathIdent) {
+            return None;
+        }
+        let module = resolve_ident(world, &target, depth)?.module?;
+        return top_level(world, module, name, depth + 1);
+    }
+
+    // The identifier may be a binding itself.
+    if let Some(binding) = binding_at(world, leaf, depth) {
+        return Some(binding);
+    }
+
+    // The name of a named argument or a dictionary key isn't a variable.
+    if parent.kind() == SyntaxKind::Named && leaf.prev_sibling().is_none() {
+        return None;
+    }
+
+    // Otherwise, walk outwards through the scopes, visiting the bindings in
+    // each scope from the innermost to the outermost.
+    let source = leaf.get().span().source();
+    let mut child = leaf;
+    while let Some(parent) = child.parent() {
+        let node = parent.get();
+        match parent.kind() {
+            // Earlier expressions in the same scope.
+            SyntaxKind::Markup | SyntaxKind::Code => {
+                for sibling in node.children().take(child.index()).rev() {
+                    if let Some(binding) = bound_in(world, source, sibling, name, depth) {
+                        return Some(binding);
+                    }
+                }
+            }
+
+            // The closure's own name and parameters within its body.
+            SyntaxKind::Closure if child.next_sibling().is_none() => {
+                let closure = node.cast::<ast::Closure>()?;
+                if let Some(binding) = closure_idents(&closure)
+                    .into_iter()
+                    .find(|ident| ident.as_str() == name)
+                {
+                    return Some(Binding::new(binding.span()));
+                }
+            }
+
+            // The loop's pattern within its body.
+            SyntaxKind::ForLoop if child.next_sibling().is_none() => {
+                let for_loop = node.cast::<ast::ForLoop>()?;
+                if let Some(binding) =
+                    for_idents(&for_loop).into_iter().find(|ident| ident.as_str() == name)
+                {
+                    return Some(Binding::new(binding.span()));
+                }
+            }
+
+            _ => {}
+        }
+
+        child = parent;
+    }
+
+    None
+}
+
+/// Determine whether an identifier is in a binding position and resolve it if
+/// so. Imported items resolve to their definitions in the imported file.
+fn binding_at(
+    world: &(dyn World + 'static),
+    leaf: &LinkedNode,
+    depth: usize,
+) -> Option<Binding> {
+    let span = leaf.get().span();
+    let source = span.source();
+    let mut ancestor = leaf.parent()?;
+    loop {
+        let node = ancestor.get();
+        let idents = match ancestor.kind() {
+            SyntaxKind::LetBinding => node.cast::<ast::LetBinding>()?.kind().idents(),
+            SyntaxKind::Closure => closure_idents(&node.cast()?),
+            SyntaxKind::ForLoop => for_idents(&node.cast()?),
+            SyntaxKind::ModuleImport => {
+                let import = node.cast::<ast::ModuleImport>()?;
+                let Some(ast::Imports::Items(items)) = import.imports() else {
+                    return None;
+                };
+                if !items.iter().any(|item| item.span() == span) {
+                    return None;
+                }
+                let binding = imported_file(world, source, &import)
+                    .and_then(|id| top_level(world, id, leaf.text(), depth + 1));
+                return Some(binding.unwrap_or(Binding::new(span)));
+            }
+            SyntaxKind::Markup | SyntaxKind::Code => return None,
+            _ => {
+                ancestor = ancestor.parent()?;
+                continue;
+            }
+        };
+
+        return idents
+            .iter()
+            .any(|ident| ident.span() == span)
+            .then(|| Binding::new(span));
+    }
+}
+
+/// Find a binding for a name in the top-level scope of a file.
+fn top_level(
+    world: &(dyn World + 'static),
+    id: SourceId,
+    name: &str,
+    depth: usize,
+) -> Option<Binding> {
+    world
+        .source(id)
+        .root()
+        .children()
+        .rev()
+        .find_map(|child| bound_in(world, id, child, name, depth))
+}
+
+/// Find the binding for a name that an expression introduces into its
+/// enclosing scope.
+fn bound_in(
+    world: &(dyn World + 'static),
+    source: SourceId,
+    node: &SyntaxNode,
+    name: &str,
+    depth: usize,
+) -> Option<Binding> {
+    if depth > MAX_DEPTH {
+        return None;
+    }
+
+    match node.kind() {
+        SyntaxKind::LetBinding => node
+            .cast::<ast::LetBinding>()?
+            .kind()
+            .idents()
+            .into_iter()
+            .rev()
+            .find(|ident| ident.as_str() == name)
+            .map(|ident| Binding::new(ident.span())),
+
+        SyntaxKind::ModuleImport => {
+            let import = node.cast::<ast::ModuleImport>()?;
+            let file = imported_file(world, source, &import);
+            match import.imports() {
+                None => {
+                    let module = module_name(world, source, &import)?;
+                    (module == name)
+                        .then(|| Binding { span: import.source().span(), module: file })
+                }
+                Some(ast::Imports::Wildcard) => top_level(world, file?, name, depth + 1),
+                Some(ast::Imports::Items(items)) => {
+                    let item = items.into_iter().find(|item| item.as_str() == name)?;
+                    let binding =
+                        file.and_then(|id| top_level(world, id, name, depth + 1));
+                    Some(binding.unwrap_or(Binding::new(item.span())))
+                }
+            }
+        }
+
+        // Markup in these elements doesn't open a new scope, so their
+        // bindings stay visible after them.
+        SyntaxKind::Markup
+        | SyntaxKind::Strong
+        | SyntaxKind::Emph
+        | SyntaxKind::Heading
+        | SyntaxKind::ListItem
+        | SyntaxKind::EnumItem
+        | SyntaxKind::TermItem => node
+            .children()
+            .rev()
+            .find_map(|child| bound_in(world, source, child, name, depth)),
+
+        _ => None,
+    }
+}
+
+/// The identifiers a closure binds within its body: its name and parameters.
+fn closure_idents(closure: &ast::Closure) -> Vec<ast::Ident> {
+    let mut idents: Vec<_> = closure.name().into_iter().collect();
+    for param in closure.params().children() {
+        match param {
+            ast::Param::Pos(pattern) => idents.extend(pattern.idents()),
+            ast::Param::Named(named) => idents.push(named.name()),
+            ast::Param::Sink(ident) => idents.push(ident),
+        }
+    }
+    idents
+}
+
+/// The identifiers a for loop binds within its body.
+fn for_idents(for_loop: &ast::ForLoop) -> Vec<ast::Ident> {
+    let pattern = for_loop.pattern();
+    let mut idents: Vec<_> = pattern.key().into_iter().collect();
+    idents.extend(pattern.value().idents());
+    idents
+}
+
+/// The name that an import without items binds the module to.
+fn module_name(
+    world: &(dyn World + 'static),
+    source: SourceId,
+    import: &ast::ModuleImport,
+) -> Option<String> {
+    let ast::Expr::Str(path) = import.source() else { return None };
+    let path = path.get();
+    if path.starts_with('@') {
+        let spec = path.parse::<PackageSpec>().ok()?;
+        return Some(spec.name.to_string());
+    }
+
+    let full = locate(world, source, &path)?;
+    Some(full.file_stem()?.to_string_lossy().into())
+}
+
+/// The file that an import refers to.
+fn imported_file(
+    world: &(dyn World + 'static),
+    source: SourceId,
+    import: &ast::ModuleImport,
+) -> Option<SourceId> {
+    let ast::Expr::Str(path) = import.source() else { return None };
+    resolve_path(world, source, &path.get())
+}
+
+/// Resolve the path of an import or include like the evaluator does.
+fn resolve_path(
+    world: &(dyn World + 'static),
+    source: SourceId,
+    path: &str,
+) -> Option<SourceId> {
+    let full = if path.starts_with('@') {
+        let spec = path.parse::<PackageSpec>().ok()?;
+        let root = world.package(&spec).ok()?.normalize();
+        let data = world.file(&root.join(MANIFEST)).ok()?;
+        let manifest = PackageManifest::parse(&data).ok()?;
+        root.join(&manifest.package.entrypoint).normalize()
+    } else {
+        locate(world, source, path)?
+    };
+
+    world.resolve(&full).ok()
+}
+
+/// Resolve a path relative to a source file, with absolute paths being
+/// relative to the project's or package's root.
+fn locate(
+    world: &(dyn World + 'static),
+    source: SourceId,
+    path: &str,
+) -> Option<PathBuf> {
+    let source = world.source(source);
+    let root = match source.package() {
+        Some(spec) => world.package(spec).ok()?.normalize(),
+        None => world.root().into(),
+    };
+
+    let full = match path.strip_prefix('/') {
+        Some(path) => root.join(path),
+        None => source.path().parent()?.join(path),
+    };
+
+    Some(full.normalize())
+}
+
+/// The main source, the given source and all sources that they import or
+/// include, directly or indirectly.
+fn project(world: &(dyn World + 'static), source: SourceId) -> Vec<SourceId> {
+    let mut ids = vec![world.main().id()];
+    if !ids.contains(&source) {
+        ids.push(source);
+    }
+
+    let mut i = 0;
+    while i < ids.len() {
+        let mut paths = vec![];
+        find_paths(world.source(ids[i]).root(), &mut paths);
+        for path in paths {
+            if let Some(id) = resolve_path(world, ids[i], &path) {
+                if !ids.contains(&id) {
+                    ids.push(id);
+                }
+            }
+        }
+        i += 1;
+    }
+
+    ids
+}
+
+/// Find the paths of all imports and includes in a tree.
+fn find_paths(node: &SyntaxNode, paths: &mut Vec<String>) {
+    let source = match node.kind() {
+        SyntaxKind::ModuleImport => node.cast::<ast::ModuleImport>().map(|v| v.source()),
+        SyntaxKind::ModuleInclude => {
+            node.cast::<ast::ModuleInclude>().map(|v| v.source())
+        }
+        _ => None,
+    };
+
+    if let Some(ast::Expr::Str(path)) = source {
+        paths.push(path.get().into());
+    }
+
+    for child in node.children() {
+        find_paths(child, paths);
+    }
+}
+
+/// Find all identifiers in a tree that resolve to the given binding.
+fn find_uses(
+    world: &(dyn World + 'static),
+    node: &LinkedNode,
+    name: &str,
+    target: Span,
+    spans: &mut Vec<Span>,
+) {
+    if matches!(node.kind(), SyntaxKind::Ident | SyntaxKind::MathIdent)
+        && node.text().as_str() == name
+        && resolve_ident(world, node, 0).map(|binding| binding.span) == Some(target)
+    {
+        spans.push(node.get().span());
+    }
+
+    for child in node.children() {
+        find_uses(world, &child, name, target, spans);
+    }
+}
+
+/// Find labels with the given name in a tree, either only those that are
+/// attached to elements or also references to them.
+fn find_labels(node: &SyntaxNode, name: &str, attached: bool, spans: &mut Vec<Span>) {
+    let text = node.text();
+    let found = match node.kind() {
+        SyntaxKind::Label => text.strip_prefix('<').and_then(|s| s.strip_suffix('>')),
+        SyntaxKind::RefMarker if !attached => text.strip_prefix('@'),
+        _ => None,
+    };
+
+    if found == Some(name) {
+        spans.push(node.span());
+    }
+
+    for child in node.children() {
+        // Labels in code, like `ref(<name>)`, aren't attached to anything.
+        if attached
+            && node.kind() != SyntaxKind::Markup
+            && child.kind() == SyntaxKind::Label
+        {
+            continue;
+        }
+        find_labels(child, name, attached, spans);
+    }
+}
+
+/// The name of the label or reference at a leaf.
+fn label_name(leaf: &LinkedNode) -> Option<String> {
+    let text = leaf.text();
+    let name = match leaf.kind() {
+        SyntaxKind::Label => text.strip_prefix('<')?.strip_suffix('>')?,
+        SyntaxKind::RefMarker => text.strip_prefix('@')?,
+        _ => return None,
+    };
+    Some(name.into())
+}
+
+#[cfg(test)]
+mod tests {
+    use std::ops::Range;
+    use std::path::Path;
+
+    use comemo::Prehashed;
+
+    use super::*;
+    use crate::diag::{FileError, FileResult};
+    use crate::eval::{Datetime, Library};
+    use crate::font::{Font, FontBook};
+    use crate::util::Buffer;
+
+    /// A world that only holds sources. Its first source is the main one.
+    struct TestWorld {
+        sources: Vec<Source>,
+        book: Prehashed<FontBook>,
+    }
+
+    impl TestWorld {
+        fn new(files: &[(&str, &str)]) -> Self {
+            let sources = files
+                .iter()
+                .enumerate()
+                .map(|(i, (path, text))| {
+                    let id = SourceId::from_u16(i as u16);
+                    Source::new(id, Path::new(path), text.to_string())
+                })
+                .collect();
+            Self { sources, book: Prehashed::new(FontBook::new()) }
+        }
+    }
+
+    impl World for TestWorld {
+        fn root(&self) -> &Path {
+            Path::new("/")
+        }
+
+        fn library(&self) -> &Prehashed<Library> {
+            unimplemented!("definitions are found without evaluation")
+        }
+
+        fn main(&self) -> &Source {
+            &self.sources[0]
+        }
+
+        fn resolve(&self, path: &Path) -> FileResult<SourceId> {
+            self.sources
+                .iter()
+                .find(|source| source.path() == path)
+                .map(|source| source.id())
+                .ok_or_else(|| FileError::NotFound(path.into()))
+        }
+
+        fn source(&self, id: SourceId) -> &Source {
+            &self.sources[id.into_u16() as usize]
+        }
+
+        fn book(&self) -> &Prehashed<FontBook> {
+            &self.book
+        }
+
+        fn font(&self, _: usize) -> Option<Font> {
+            None
+        }
+
+        fn file(&self, path: &Path) -> FileResult<Buffer> {
+            Err(FileError::NotFound(path.into()))
+        }
+
+        fn today(&self, _: Option<i64>) -> Option<Datetime> {
+            None
+        }
+    }
+
+    /// The range of the `n`-th occurrence of a needle in a file.
+    fn find(world: &TestWorld, (path, needle, n): (&str, &str, usize)) -> Range<usize> {
+        let id = world.resolve(Path::new(path)).unwrap();
+        let text = world.source(id).text();
+        let start = text.match_indices(needle).nth(n).unwrap().0;
+        start..start + needle.len()
+    }
+
+    /// Test the definition found with the cursor right after the first
+    /// character of a needle.
+    #[track_caller]
+    fn test(
+        files: &[(&str, &str)],
+        cursor: (&str, &str, usize),
+        exp: Option<(&str, &str, usize)>,
+    ) {
+        let world = TestWorld::new(files);
+        let id = world.resolve(Path::new(cursor.0)).unwrap();
+        let found = definition(&world, world.source(id), find(&world, cursor).start + 1)
+            .map(|span| {
+                let source = world.source(span.source());
+                (source.path().to_path_buf(), source.range(span))
+            });
+        let exp = exp.map(|exp| (PathBuf::from(exp.0), find(&world, exp)));
+        assert_eq!(found, exp);
+    }
+
+    #[test]
+    fn test_definition_locals() {
+        let files = [("/main.typ", "#let x = 1\n#let (y, z) = (2, 3)\n#x #z")];
+        test(&files, ("/main.typ", "x", 1), Some(("/main.typ", "x", 0)));
+        test(&files, ("/main.typ", "z", 1), Some(("/main.typ", "z", 0)));
+
+        // Later bindings shadow earlier ones.
+        let files = [("/main.typ", "#let x = 1\n#let x = 2\n#x")];
+        test(&files, ("/main.typ", "x", 2), Some(("/main.typ", "x", 1)));
+
+        // Bindings in a block don't escape it.
+        let files = [("/main.typ", "#let x = 1\n#{ let x = 2 }\n#x")];
+        test(&files, ("/main.typ", "x", 2), Some(("/main.typ", "x", 0)));
+
+        // Names from the standard library are not defined in the sources.
+        test(&[("/main.typ", "#rect()")], ("/main.typ", "rect", 0), None);
+    }
+
+    #[test]
+    fn test_definition_closure_params() {
+        let files = [("/main.typ", "#let f(a, b: 1, ..c) = a + b + c.len()\n#f(1)")];
+        test(&files, ("/main.typ", "a", 1), Some(("/main.typ", "a", 0)));
+        test(&files, ("/main.typ", "b", 1), Some(("/main.typ", "b", 0)));
+        test(&files, ("/main.typ", "c", 1), Some(("/main.typ", "c", 0)));
+        test(&files, ("/main.typ", "f", 1), Some(("/main.typ", "f", 0)));
+
+        // Parameters shadow outer bindings within the closure's body only.
+        let files = [("/main.typ", "#let a = 1\n#let g = a => a\n#a")];
+        test(&files, ("/main.typ", "a", 2), Some(("/main.typ", "a", 1)));
+        test(&files, ("/main.typ", "a", 3), Some(("/main.typ", "a", 0)));
+
+        // Loop patterns within the loop's body.
+        let files = [("/main.typ", "#for (k, v) in (a: 1) { v }")];
+        test(&files, ("/main.typ", "v", 1), Some(("/main.typ", "v", 0)));
+    }
+
+    #[test]
+    fn test_definition_imports() {
+        let files = [
+            (
+                "/main.typ",
+                "#import \"utils.typ\": double\n#import \"utils.typ\"\n#double #utils.half",
+            ),
+            ("/utils.typ", "#let double(x) = 2 * x\n#let half(x) = x / 2"),
+        ];
+
+        // An imported item and its use resolve to the other file.
+        test(&files, ("/main.typ", "double", 0), Some(("/utils.typ", "double", 0)));
+        test(&files, ("/main.typ", "double", 1), Some(("/utils.typ", "double", 0)));
+
+        // A field of an imported module.
+        test(&files, ("/main.typ", "half", 0), Some(("/utils.typ", "half", 0)));
+
+        // The module name resolves to the path of its import.
+        test(
+            &files,
+            ("/main.typ", "utils.half", 0),
+            Some(("/main.typ", "\"utils.typ\"", 1)),
+        );
+
+        // Wildcard imports, across two files.
+        let files = [
+            ("/main.typ", "#import \"a.typ\": *\n#value"),
+            ("/a.typ", "#import \"b.typ\": *"),
+            ("/b.typ", "#let value = 1"),
+        ];
+        test(&files, ("/main.typ", "value", 0), Some(("/b.typ", "value", 0)));
+    }
+
+    #[test]
+    fn test_definition_labels() {
+        let files = [
+            (
+                "/main.typ",
+                "= Intro <intro>\nSee @intro and @outro.\n#include \"chapter.typ\"",
+            ),
+            ("/chapter.typ", "= Outro <outro>\nBack to @intro."),
+        ];
+
+        // A reference in the same file and one to an included file.
+        test(&files, ("/main.typ", "@intro", 0), Some(("/main.typ", "<intro>", 0)));
+        test(&files, ("/main.typ", "@outro", 0), Some(("/chapter.typ", "<outro>", 0)));
+
+        // A reference from the included file back to the main file.
+        test(&files, ("/chapter.typ", "@intro", 0), Some(("/main.typ", "<intro>", 0)));
+
+        // A label resolves to itself.
+        test(&files, ("/main.typ", "<intro>", 0), Some(("/main.typ", "<intro>", 0)));
+
+        // References to missing labels have no definition.
+        test(&[("/main.typ", "@missing")], ("/main.typ", "@missing", 0), None);
+    }
+}
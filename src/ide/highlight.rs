@@ -234,6 +234,7 @@ pub fn highlight(node: &LinkedNode) -> Option<Tag> {
         SyntaxKind::Closure => None,
         SyntaxKind::Params => None,
         SyntaxKind::LetBinding => None,
+        SyntaxKind::Destructuring => None,
         SyntaxKind::SetRule => None,
         SyntaxKind::ShowRule => None,
         SyntaxKind::Conditional => None,
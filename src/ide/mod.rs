@@ -2,12 +2,14 @@
 
 mod analyze;
 mod complete;
+mod definition;
 mod highlight;
 mod jump;
 mod tooltip;
 
 pub use self::analyze::analyze_labels;
 pub use self::complete::*;
+pub use self::definition::*;
 pub use self::highlight::*;
 pub use self::jump::*;
 pub use self::tooltip::*;
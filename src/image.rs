@@ -4,6 +4,7 @@ use std::io;
 use std::sync::Arc;
 
 use crate::diag::{format_xml_like_error, StrResult};
+use crate::eval::{cast_from_value, cast_to_value, Value};
 use crate::util::Buffer;
 
 /// A raster or vector image.
@@ -97,6 +98,27 @@ pub enum VectorFormat {
     Svg,
 }
 
+cast_from_value! {
+    ImageFormat,
+    /// Raster format for illustrations and transparent graphics.
+    "png" => Self::Raster(RasterFormat::Png),
+    /// Lossy raster format suitable for photos.
+    "jpg" => Self::Raster(RasterFormat::Jpg),
+    /// Raster format that is typically used for short animated clips.
+    "gif" => Self::Raster(RasterFormat::Gif),
+    /// The vector graphics format of the web.
+    "svg" => Self::Vector(VectorFormat::Svg),
+}
+
+cast_to_value! {
+    v: ImageFormat => Value::from(match v {
+        ImageFormat::Raster(RasterFormat::Png) => "png",
+        ImageFormat::Raster(RasterFormat::Jpg) => "jpg",
+        ImageFormat::Raster(RasterFormat::Gif) => "gif",
+        ImageFormat::Vector(VectorFormat::Svg) => "svg",
+    })
+}
+
 impl From<RasterFormat> for image::ImageFormat {
     fn from(format: RasterFormat) -> Self {
         match format {
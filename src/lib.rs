@@ -17,7 +17,7 @@
 //!   per page with items at fixed positions.
 //! - **Exporting:**
 //!   These frames can finally be exported into an output format (currently
-//!   supported are [PDF] and [raster images]).
+//!   supported are [PDF], [raster images], and [SVG]).
 //!
 //! [tokens]: syntax::SyntaxKind
 //! [parsed]: syntax::parse
@@ -31,6 +31,7 @@
 //! [frame]: doc::Frame
 //! [PDF]: export::pdf
 //! [raster images]: export::render
+//! [SVG]: export::svg
 
 #![recursion_limit = "1000"]
 
@@ -49,24 +50,30 @@ pub mod geom;
 pub mod ide;
 pub mod image;
 pub mod model;
+pub mod package;
 pub mod syntax;
 
-use std::path::Path;
+use std::path::{Path, PathBuf};
 
 use comemo::{Prehashed, Track};
 
-use crate::diag::{FileResult, SourceResult};
+use crate::diag::{FileError, FileResult, SourceResult};
 use crate::doc::Document;
-use crate::eval::{Library, Route, Tracer};
+use crate::eval::{Datetime, Library, Route, Tracer};
 use crate::font::{Font, FontBook};
+use crate::package::PackageSpec;
 use crate::syntax::{Source, SourceId};
 use crate::util::Buffer;
 
 /// Compile a source file into a fully layouted document.
-pub fn compile(world: &(dyn World + 'static)) -> SourceResult<Document> {
+///
+/// Warnings that occur during compilation are collected in the `tracer`.
+pub fn compile(
+    world: &(dyn World + 'static),
+    tracer: &mut Tracer,
+) -> SourceResult<Document> {
     // Evaluate the source file into a module.
     let route = Route::default();
-    let mut tracer = Tracer::default();
     let module =
         eval::eval(world.track(), route.track(), tracer.track_mut(), world.main())?;
 
@@ -104,4 +111,30 @@ pub trait World {
 
     /// Try to access a file at a path.
     fn file(&self, path: &Path) -> FileResult<Buffer>;
+
+    /// Try to find the root directory of a package.
+    ///
+    /// The directory must contain the package's `typst.toml` manifest. Files
+    /// within it are accessed through [`file`](Self::file) and
+    /// [`resolve`](Self::resolve) like all other files. Sources loaded from a
+    /// package directory should be tagged with the package's specification
+    /// through [`Source::with_package`].
+    ///
+    /// Defaults to not finding any packages.
+    fn package(&self, spec: &PackageSpec) -> FileResult<PathBuf> {
+        Err(FileError::PackageNotFound(spec.clone()))
+    }
+
+    /// Get the current date.
+    ///
+    /// If no offset is specified, the local date should be chosen. Otherwise,
+    /// the UTC date should be chosen with the corresponding offset in hours.
+    ///
+    /// If this function returns `None`, Typst's `datetime.today()` will
+    /// return an error.
+    ///
+    /// Defaults to not knowing the date.
+    fn today(&self, _offset: Option<i64>) -> Option<Datetime> {
+        None
+    }
 }
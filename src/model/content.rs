@@ -4,13 +4,14 @@ use std::iter::Sum;
 use std::ops::{Add, AddAssign};
 
 use ecow::{eco_format, EcoString, EcoVec};
+use serde::{Serialize, Serializer};
 
 use super::{
     element, Behave, Behaviour, ElemFunc, Element, Fold, Guard, Label, Locatable,
-    Location, Recipe, Style, Styles, Synthesize,
+    Location, Recipe, Style, StyleChain, Styles, Synthesize,
 };
 use crate::diag::{SourceResult, StrResult};
-use crate::doc::Meta;
+use crate::doc::{Meta, Role};
 use crate::eval::{Cast, Str, Value, Vm};
 use crate::syntax::Span;
 use crate::util::pretty_array_like;
@@ -33,6 +34,7 @@ enum Attr {
     Prepared,
     Guard(Guard),
     Location(Location),
+    Parent(Location),
 }
 
 impl Content {
@@ -347,6 +349,19 @@ impl Content {
     pub fn set_location(&mut self, location: Location) {
         self.attrs.push(Attr::Location(location));
     }
+
+    /// The location of the closest located element this content is nested in.
+    pub fn parent(&self) -> Option<Location> {
+        self.attrs.iter().find_map(|modifier| match modifier {
+            Attr::Parent(location) => Some(*location),
+            _ => None,
+        })
+    }
+
+    /// Attach the location of the element this content is nested in.
+    pub fn set_parent(&mut self, location: Location) {
+        self.attrs.push(Attr::Parent(location));
+    }
 }
 
 impl Debug for Content {
@@ -393,6 +408,18 @@ impl PartialEq for Content {
     }
 }
 
+impl Serialize for Content {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        serializer.collect_map(
+            std::iter::once(("func", Value::Str(self.func().name().into())))
+                .chain(self.fields().map(|(name, value)| (name.as_str(), value))),
+        )
+    }
+}
+
 impl Add for Content {
     type Output = Self;
 
@@ -494,6 +521,17 @@ pub struct MetaElem {
     pub data: Vec<Meta>,
 }
 
+impl MetaElem {
+    /// The role of the innermost structure element that content styled with
+    /// the given style chain is part of.
+    pub fn role_in(styles: StyleChain) -> Option<Role> {
+        Self::data_in(styles).into_iter().find_map(|meta| match meta {
+            Meta::Tag(tag) => Some(tag.role),
+            _ => None,
+        })
+    }
+}
+
 impl Behave for MetaElem {
     fn behaviour(&self) -> Behaviour {
         Behaviour::Ignorant
@@ -1,3 +1,4 @@
+use std::collections::{HashMap, HashSet};
 use std::fmt::{self, Debug, Formatter};
 use std::hash::Hash;
 use std::num::NonZeroUsize;
@@ -84,12 +85,18 @@ impl StabilityProvider {
 pub struct Introspector {
     pages: usize,
     elems: Vec<(Content, Position)>,
+    /// Maps locations to indices in `elems`.
+    indices: HashMap<Location, usize>,
 }
 
 impl Introspector {
     /// Create a new introspector.
     pub fn new(frames: &[Frame]) -> Self {
-        let mut introspector = Self { pages: frames.len(), elems: vec![] };
+        let mut introspector = Self {
+            pages: frames.len(),
+            elems: vec![],
+            indices: HashMap::new(),
+        };
         for (i, frame) in frames.iter().enumerate() {
             let page = NonZeroUsize::new(1 + i).unwrap();
             introspector.extract(frame, page, Transform::identity());
@@ -112,19 +119,85 @@ impl Introspector {
                         .pre_concat(group.transform);
                     self.extract(&group.frame, page, ts);
                 }
-                FrameItem::Meta(Meta::Elem(content), _)
-                    if !self
-                        .elems
-                        .iter()
-                        .any(|(prev, _)| prev.location() == content.location()) =>
-                {
+                FrameItem::Meta(Meta::Elem(content), _) => {
+                    let Some(location) = content.location() else { continue };
+                    if self.indices.contains_key(&location) {
+                        continue;
+                    }
                     let pos = pos.transform(ts);
+                    self.indices.insert(location, self.elems.len());
                     self.elems.push((content.clone(), Position { page, point: pos }));
                 }
                 _ => {}
             }
         }
     }
+
+    /// The indices of all elements matching the selector, in document order.
+    fn select(&self, selector: &Selector) -> Vec<usize> {
+        match selector {
+            Selector::Location(location) => {
+                self.indices.get(location).copied().into_iter().collect()
+            }
+            Selector::Any(selectors) if !selector.is_local() => {
+                let mut indices: Vec<_> =
+                    selectors.iter().flat_map(|sel| self.select(sel)).collect();
+                indices.sort_unstable();
+                indices.dedup();
+                indices
+            }
+            Selector::And(selectors) if !selector.is_local() => {
+                let mut iter = selectors.iter();
+                let mut indices =
+                    iter.next().map(|sel| self.select(sel)).unwrap_or_default();
+                for sel in iter {
+                    let other = self.select(sel);
+                    indices.retain(|i| other.binary_search(i).is_ok());
+                }
+                indices
+            }
+            Selector::Before { selector, end, inclusive } => {
+                let mut indices = self.select(selector);
+                if let Some(&end) = self.select(end).first() {
+                    let split =
+                        indices.partition_point(|&i| i < end || (*inclusive && i == end));
+                    indices.truncate(split);
+                }
+                indices
+            }
+            Selector::After { selector, start, inclusive } => {
+                let mut indices = self.select(selector);
+                if let Some(&start) = self.select(start).first() {
+                    let split = indices
+                        .partition_point(|&i| i < start || (!*inclusive && i == start));
+                    indices.drain(..split);
+                }
+                indices
+            }
+            Selector::Within { selector, ancestor } => {
+                let ancestors: HashSet<_> = self.select(ancestor).into_iter().collect();
+                self.select(selector)
+                    .into_iter()
+                    .filter(|&i| self.ancestors(i).any(|a| ancestors.contains(&a)))
+                    .collect()
+            }
+            _ => (0..self.elems.len())
+                .filter(|&i| selector.matches(&self.elems[i].0))
+                .collect(),
+        }
+    }
+
+    /// Iterate over the indices of the elements the element at the given index
+    /// is nested in, from the inside out.
+    fn ancestors(&self, index: usize) -> impl Iterator<Item = usize> + '_ {
+        std::iter::successors(Some(index), |&i| {
+            self.elems[i]
+                .0
+                .parent()
+                .and_then(|parent| self.indices.get(&parent).copied())
+        })
+        .skip(1)
+    }
 }
 
 #[comemo::track]
@@ -136,7 +209,10 @@ impl Introspector {
 
     /// Query for all matching elements.
     pub fn query(&self, selector: Selector) -> Vec<Content> {
-        self.all().filter(|elem| selector.matches(elem)).cloned().collect()
+        self.select(&selector)
+            .into_iter()
+            .map(|i| self.elems[i].0.clone())
+            .collect()
     }
 
     /// Query for all matching element up to the given location.
@@ -186,10 +262,9 @@ impl Introspector {
 
     /// Find the position for the given location.
     pub fn position(&self, location: Location) -> Position {
-        self.elems
-            .iter()
-            .find(|(elem, _)| elem.location() == Some(location))
-            .map(|(_, loc)| *loc)
+        self.indices
+            .get(&location)
+            .map(|&i| self.elems[i].1)
             .unwrap_or(Position { page: NonZeroUsize::ONE, point: Point::zero() })
     }
 }
@@ -16,9 +16,10 @@ pub use typst_macros::element;
 
 use comemo::{Constraint, Track, Tracked, TrackedMut};
 
-use crate::diag::SourceResult;
+use crate::diag::{warning, SourceResult};
 use crate::doc::Document;
 use crate::eval::Tracer;
+use crate::syntax::Span;
 use crate::World;
 
 /// Typeset content into a fully layouted document.
@@ -36,7 +37,7 @@ pub fn typeset(
     let mut introspector = Introspector::new(&[]);
 
     // Relayout until all introspections stabilize.
-    // If that doesn't happen within five attempts, we give up.
+    // If that doesn't happen within five attempts, we give up and warn.
     loop {
         let constraint = Constraint::new();
         let mut provider = StabilityProvider::new();
@@ -52,7 +53,15 @@ pub fn typeset(
 
         introspector = Introspector::new(&document.pages);
 
-        if iter >= 5 || introspector.valid(&constraint) {
+        if introspector.valid(&constraint) {
+            break;
+        }
+
+        if iter >= 5 {
+            tracer.warn(
+                warning!(Span::detached(), "layout did not converge within 5 attempts")
+                    .with_hint("check if any states or queries are updating themselves"),
+            );
             break;
         }
     }
@@ -39,6 +39,18 @@ pub fn realize(
         if target.can::<dyn Locatable>() || target.label().is_some() {
             let location = vt.provider.locate(hash128(target));
             elem.set_location(location);
+
+            // Remember the closest enclosing element so that queries can
+            // select by containment.
+            let parent =
+                MetaElem::data_in(styles).into_iter().find_map(|meta| match meta {
+                    Meta::Elem(content) => content.location(),
+                    _ => None,
+                });
+
+            if let Some(parent) = parent {
+                elem.set_parent(parent);
+            }
         }
 
         if let Some(elem) = elem.with_mut::<dyn Synthesize>() {
@@ -151,8 +163,21 @@ fn try_apply(
             Ok(Some(Content::sequence(result)))
         }
 
+        Some(selector @ (Selector::Location(_) | Selector::And(_))) => {
+            if !selector.matches(target) {
+                return Ok(None);
+            }
+
+            recipe.apply_vt(vt, target.clone().guarded(guard)).map(Some)
+        }
+
         // Not supported here.
-        Some(Selector::Any(_)) => Ok(None),
+        Some(
+            Selector::Any(_)
+            | Selector::Before { .. }
+            | Selector::After { .. }
+            | Selector::Within { .. },
+        ) => Ok(None),
 
         None => Ok(None),
     }
@@ -1,10 +1,11 @@
 use std::fmt::{self, Debug, Formatter, Write};
 use std::iter;
 use std::mem;
+use std::sync::Arc;
 
 use ecow::{eco_format, eco_vec, EcoString, EcoVec};
 
-use super::{Content, ElemFunc, Element, Label, Vt};
+use super::{Content, ElemFunc, Element, Label, Location, Vt};
 use crate::diag::{SourceResult, Trace, Tracepoint};
 use crate::eval::{cast_from_value, Args, Cast, Dict, Func, Regex, Value, Vm};
 use crate::syntax::Span;
@@ -248,7 +249,7 @@ impl Debug for Recipe {
     }
 }
 
-/// A selector in a show rule.
+/// A selector in a show rule or query.
 #[derive(Clone, PartialEq, Hash)]
 pub enum Selector {
     /// Matches a specific type of element.
@@ -256,12 +257,23 @@ pub enum Selector {
     /// If there is a dictionary, only elements with the fields from the
     /// dictionary match.
     Elem(ElemFunc, Option<Dict>),
+    /// Matches the element at the given location.
+    Location(Location),
     /// Matches elements with a specific label.
     Label(Label),
     /// Matches text elements through a regular expression.
     Regex(Regex),
     /// Matches if any of the subselectors match.
     Any(EcoVec<Self>),
+    /// Matches if all of the subselectors match.
+    And(EcoVec<Self>),
+    /// Matches all matches of `selector` before the first match of `end`.
+    Before { selector: Arc<Self>, end: Arc<Self>, inclusive: bool },
+    /// Matches all matches of `selector` after the first match of `start`.
+    After { selector: Arc<Self>, start: Arc<Self>, inclusive: bool },
+    /// Matches all matches of `selector` that are nested in a match of
+    /// `ancestor`.
+    Within { selector: Arc<Self>, ancestor: Arc<Self> },
 }
 
 impl Selector {
@@ -270,7 +282,25 @@ impl Selector {
         Self::Regex(Regex::new(&regex::escape(text)).unwrap())
     }
 
+    /// Whether the selector can be decided by looking at an element in
+    /// isolation.
+    ///
+    /// Positional selectors like `before`, `after` and `within` need to know
+    /// about the whole document and can thus only be used in queries.
+    pub fn is_local(&self) -> bool {
+        match self {
+            Self::Elem(..) | Self::Location(_) | Self::Label(_) | Self::Regex(_) => true,
+            Self::Any(selectors) | Self::And(selectors) => {
+                selectors.iter().all(Self::is_local)
+            }
+            Self::Before { .. } | Self::After { .. } | Self::Within { .. } => false,
+        }
+    }
+
     /// Whether the selector matches for the target.
+    ///
+    /// Always returns `false` for selectors that are not
+    /// [local](Self::is_local).
     pub fn matches(&self, target: &Content) -> bool {
         match self {
             Self::Elem(element, dict) => {
@@ -280,12 +310,15 @@ impl Selector {
                         .flat_map(|dict| dict.iter())
                         .all(|(name, value)| target.field_ref(name) == Some(value))
             }
+            Self::Location(location) => target.location() == Some(*location),
             Self::Label(label) => target.label() == Some(label),
             Self::Regex(regex) => {
                 target.func() == item!(text_func)
                     && item!(text_str)(target).map_or(false, |text| regex.is_match(&text))
             }
             Self::Any(selectors) => selectors.iter().any(|sel| sel.matches(target)),
+            Self::And(selectors) => selectors.iter().all(|sel| sel.matches(target)),
+            Self::Before { .. } | Self::After { .. } | Self::Within { .. } => false,
         }
     }
 }
@@ -301,14 +334,28 @@ impl Debug for Selector {
                 }
                 Ok(())
             }
+            Self::Location(location) => location.fmt(f),
             Self::Label(label) => label.fmt(f),
             Self::Regex(regex) => regex.fmt(f),
-            Self::Any(selectors) => {
-                f.write_str("any")?;
+            Self::Any(selectors) | Self::And(selectors) => {
+                f.write_str(if matches!(self, Self::Any(_)) { "any" } else { "and" })?;
                 let pieces: Vec<_> =
                     selectors.iter().map(|sel| eco_format!("{sel:?}")).collect();
                 f.write_str(&pretty_array_like(&pieces, false))
             }
+            Self::Before { selector, end: other, inclusive }
+            | Self::After { selector, start: other, inclusive } => {
+                let method =
+                    if matches!(self, Self::Before { .. }) { "before" } else { "after" };
+                write!(f, "{selector:?}.{method}({other:?}")?;
+                if !inclusive {
+                    f.write_str(", inclusive: false")?;
+                }
+                f.write_char(')')
+            }
+            Self::Within { selector, ancestor } => {
+                write!(f, "{selector:?}.within({ancestor:?})")
+            }
         }
     }
 }
@@ -319,6 +366,7 @@ cast_from_value! {
         .element()
         .ok_or("only element functions can be used as selectors")?
         .select(),
+    location: Location => Self::Location(location),
     label: Label => Self::Label(label),
     text: EcoString => Self::text(&text),
     regex: Regex => Self::Regex(regex),
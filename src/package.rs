@@ -0,0 +1,258 @@
+//! Package specifications and manifests.
+
+use std::fmt::{self, Debug, Display, Formatter};
+use std::str::FromStr;
+
+use ecow::{eco_format, EcoString};
+use serde::{Deserialize, Deserializer};
+
+use crate::diag::StrResult;
+use crate::syntax::is_ident;
+
+/// The name of a package's manifest file.
+pub const MANIFEST: &str = "typst.toml";
+
+/// Identifies a package, e.g. `@local/letterhead:1.2.0`.
+#[derive(Clone, Eq, PartialEq, Hash)]
+pub struct PackageSpec {
+    /// The namespace the package lives in.
+    pub namespace: EcoString,
+    /// The name of the package within its namespace.
+    pub name: EcoString,
+    /// The package's version.
+    pub version: Version,
+}
+
+impl FromStr for PackageSpec {
+    type Err = EcoString;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let Some(rest) = s.strip_prefix('@') else {
+            return Err("package specification must start with '@'".into());
+        };
+
+        let Some((namespace, rest)) = rest.split_once('/') else {
+            return Err("package specification is missing name".into());
+        };
+        if namespace.is_empty() {
+            return Err("package specification is missing namespace".into());
+        } else if !is_ident(namespace) {
+            return Err(eco_format!("`{namespace}` is not a valid package namespace"));
+        }
+
+        let Some((name, version)) = rest.split_once(':') else {
+            return Err("package specification is missing version".into());
+        };
+        if name.is_empty() {
+            return Err("package specification is missing name".into());
+        } else if !is_ident(name) {
+            return Err(eco_format!("`{name}` is not a valid package name"));
+        }
+
+        Ok(Self {
+            namespace: namespace.into(),
+            name: name.into(),
+            version: version.parse()?,
+        })
+    }
+}
+
+impl Debug for PackageSpec {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        Display::fmt(self, f)
+    }
+}
+
+impl Display for PackageSpec {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        write!(f, "@{}/{}:{}", self.namespace, self.name, self.version)
+    }
+}
+
+/// A package's version, consisting of major, minor and patch number.
+#[derive(Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Hash)]
+pub struct Version {
+    /// The major version.
+    pub major: u32,
+    /// The minor version.
+    pub minor: u32,
+    /// The patch version.
+    pub patch: u32,
+}
+
+impl Version {
+    /// The version of the compiler or `None` for an unreleased development
+    /// build, which is versioned as `0.0.0`.
+    pub fn compiler() -> Option<Self> {
+        let version: Self = env!("CARGO_PKG_VERSION")
+            .parse()
+            .expect("compiler version must be valid");
+        (version != Self { major: 0, minor: 0, patch: 0 }).then_some(version)
+    }
+}
+
+impl FromStr for Version {
+    type Err = EcoString;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut parts = s.split('.');
+        let mut next = |kind: &str| -> StrResult<u32> {
+            let Some(part) = parts.next().filter(|s| !s.is_empty()) else {
+                return Err(eco_format!("version number is missing {kind} version"));
+            };
+            part.parse::<u32>()
+                .map_err(|_| eco_format!("`{part}` is not a valid {kind} version"))
+        };
+
+        let major = next("major")?;
+        let minor = next("minor")?;
+        let patch = next("patch")?;
+        if let Some(rest) = parts.next() {
+            return Err(eco_format!(
+                "version number has unexpected fourth component: `{rest}`"
+            ));
+        }
+
+        Ok(Self { major, minor, patch })
+    }
+}
+
+impl Debug for Version {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        Display::fmt(self, f)
+    }
+}
+
+impl Display for Version {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        write!(f, "{}.{}.{}", self.major, self.minor, self.patch)
+    }
+}
+
+impl<'de> Deserialize<'de> for Version {
+    fn deserialize<D: Deserializer<'de>>(d: D) -> Result<Self, D::Error> {
+        String::deserialize(d)?.parse().map_err(serde::de::Error::custom)
+    }
+}
+
+/// A parsed package manifest.
+#[derive(Debug, Clone, Eq, PartialEq, Deserialize)]
+pub struct PackageManifest {
+    /// Details about the package itself.
+    pub package: PackageInfo,
+}
+
+/// The `[package]` section of a package manifest.
+#[derive(Debug, Clone, Eq, PartialEq, Deserialize)]
+pub struct PackageInfo {
+    /// The name of the package within its namespace.
+    pub name: String,
+    /// The package's version.
+    pub version: Version,
+    /// The path of the entrypoint into the package, relative to its root.
+    pub entrypoint: String,
+    /// The minimum required compiler version for the package.
+    pub compiler: Option<Version>,
+}
+
+impl PackageManifest {
+    /// Parse a manifest from the contents of a `typst.toml` file.
+    pub fn parse(data: &[u8]) -> StrResult<Self> {
+        let string = std::str::from_utf8(data)
+            .map_err(|_| "package manifest is not valid utf-8")?;
+        toml::from_str(string).map_err(|err| {
+            eco_format!("package manifest is malformed: {}", err.message())
+        })
+    }
+
+    /// Ensure that this manifest describes the package with the given
+    /// specification and that the package can be used with the `compiler`
+    /// version, if there is one.
+    pub fn validate(
+        &self,
+        spec: &PackageSpec,
+        compiler: Option<Version>,
+    ) -> StrResult<()> {
+        if self.package.name != spec.name.as_str() {
+            return Err(eco_format!(
+                "package manifest contains mismatched name `{}`",
+                self.package.name
+            ));
+        }
+
+        if self.package.version != spec.version {
+            return Err(eco_format!(
+                "package manifest contains mismatched version {}",
+                self.package.version
+            ));
+        }
+
+        if let (Some(required), Some(current)) = (self.package.compiler, compiler) {
+            if current < required {
+                return Err(eco_format!(
+                    "package requires typst {required} or newer \
+                     (current version is {current})"
+                ));
+            }
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_package_spec() {
+        let spec: PackageSpec = "@local/letterhead:1.2.0".parse().unwrap();
+        assert_eq!(spec.namespace, "local");
+        assert_eq!(spec.name, "letterhead");
+        assert_eq!(spec.version, Version { major: 1, minor: 2, patch: 0 });
+        assert_eq!(spec.to_string(), "@local/letterhead:1.2.0");
+        assert!("local/letterhead:1.2.0".parse::<PackageSpec>().is_err());
+        assert!("@local/letterhead".parse::<PackageSpec>().is_err());
+        assert!("@local/letterhead:1.2".parse::<PackageSpec>().is_err());
+        assert!("@local/letter head:1.2.0".parse::<PackageSpec>().is_err());
+    }
+
+    #[test]
+    fn test_validate_manifest() {
+        let manifest = PackageManifest::parse(
+            b"[package]\n\
+              name = \"adder\"\n\
+              version = \"0.1.0\"\n\
+              entrypoint = \"lib.typ\"\n\
+              compiler = \"0.3.0\"\n",
+        )
+        .unwrap();
+
+        let spec = |s: &str| s.parse::<PackageSpec>().unwrap();
+        let v = |s: &str| s.parse::<Version>().ok();
+        let adder = spec("@local/adder:0.1.0");
+        assert_eq!(manifest.validate(&adder, v("0.3.0")), Ok(()));
+        assert_eq!(manifest.validate(&adder, v("1.0.0")), Ok(()));
+        assert_eq!(manifest.validate(&adder, None), Ok(()));
+        assert_eq!(
+            manifest.validate(&adder, v("0.2.9")).unwrap_err(),
+            "package requires typst 0.3.0 or newer (current version is 0.2.9)"
+        );
+        assert_eq!(
+            manifest.validate(&spec("@local/subtract:0.1.0"), None).unwrap_err(),
+            "package manifest contains mismatched name `adder`"
+        );
+        assert_eq!(
+            manifest.validate(&spec("@local/adder:0.2.0"), None).unwrap_err(),
+            "package manifest contains mismatched version 0.1.0"
+        );
+    }
+
+    #[test]
+    fn test_compare_versions() {
+        let v = |s: &str| s.parse::<Version>().unwrap();
+        assert!(v("0.2.0") < v("0.10.0"));
+        assert!(v("1.0.0") > v("0.99.99"));
+        assert_eq!(v("1.2.3"), v("1.2.3"));
+    }
+}
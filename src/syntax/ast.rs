@@ -1169,6 +1169,13 @@ impl Named {
     pub fn expr(&self) -> Expr {
         self.0.cast_last_match().unwrap_or_default()
     }
+
+    /// The right-hand side of the pair as a pattern.
+    ///
+    /// This is used in destructuring patterns: `(name: n)`.
+    pub fn pattern(&self) -> Pattern {
+        self.0.cast_last_match().unwrap_or_default()
+    }
 }
 
 node! {
@@ -1552,8 +1559,8 @@ impl Params {
 /// A parameter to a closure.
 #[derive(Debug, Clone, Hash)]
 pub enum Param {
-    /// A positional parameter: `x`.
-    Pos(Ident),
+    /// A positional parameter: `x` or `(x, y)`.
+    Pos(Pattern),
     /// A named parameter with a default value: `draw: false`.
     Named(Named),
     /// An argument sink: `..args`.
@@ -1563,10 +1570,9 @@ pub enum Param {
 impl AstNode for Param {
     fn from_untyped(node: &SyntaxNode) -> Option<Self> {
         match node.kind() {
-            SyntaxKind::Ident => node.cast().map(Self::Pos),
             SyntaxKind::Named => node.cast().map(Self::Named),
             SyntaxKind::Spread => node.cast_first_match().map(Self::Sink),
-            _ => Option::None,
+            _ => node.cast().map(Self::Pos),
         }
     }
 
@@ -1579,29 +1585,145 @@ impl AstNode for Param {
     }
 }
 
+/// A pattern that values can be bound to.
+#[derive(Debug, Clone, Hash)]
+pub enum Pattern {
+    /// A single identifier: `x`.
+    Normal(Ident),
+    /// A placeholder that discards the value: `_`.
+    Placeholder(Underscore),
+    /// A destructuring pattern: `(x, _, ..y)`.
+    Destructuring(Destructuring),
+}
+
+impl AstNode for Pattern {
+    fn from_untyped(node: &SyntaxNode) -> Option<Self> {
+        match node.kind() {
+            SyntaxKind::Ident => node.cast().map(Self::Normal),
+            SyntaxKind::Underscore => node.cast().map(Self::Placeholder),
+            SyntaxKind::Destructuring => node.cast().map(Self::Destructuring),
+            _ => Option::None,
+        }
+    }
+
+    fn as_untyped(&self) -> &SyntaxNode {
+        match self {
+            Self::Normal(v) => v.as_untyped(),
+            Self::Placeholder(v) => v.as_untyped(),
+            Self::Destructuring(v) => v.as_untyped(),
+        }
+    }
+}
+
+impl Pattern {
+    /// The identifiers this pattern binds, from left to right.
+    pub fn idents(&self) -> Vec<Ident> {
+        match self {
+            Self::Normal(ident) => vec![ident.clone()],
+            Self::Placeholder(_) => vec![],
+            Self::Destructuring(destructuring) => destructuring.idents(),
+        }
+    }
+}
+
+impl Default for Pattern {
+    fn default() -> Self {
+        Self::Normal(Ident::default())
+    }
+}
+
+node! {
+    /// A placeholder in a pattern: `_`.
+    Underscore
+}
+
+node! {
+    /// A destructuring pattern: `(x, _, ..y)` or `(name: n, age)`.
+    Destructuring
+}
+
+impl Destructuring {
+    /// The items of the destructuring pattern.
+    pub fn items(&self) -> impl DoubleEndedIterator<Item = DestructuringItem> + '_ {
+        self.0.children().filter_map(|child| match child.kind() {
+            SyntaxKind::Named => child.cast().map(DestructuringItem::Named),
+            SyntaxKind::Spread => Some(DestructuringItem::Sink(child.cast_first_match())),
+            _ => child.cast().map(DestructuringItem::Pattern),
+        })
+    }
+
+    /// The identifiers this pattern binds, from left to right.
+    pub fn idents(&self) -> Vec<Ident> {
+        self.items()
+            .flat_map(|item| match item {
+                DestructuringItem::Pattern(pattern) => pattern.idents(),
+                DestructuringItem::Named(named) => named.pattern().idents(),
+                DestructuringItem::Sink(sink) => sink.into_iter().collect(),
+            })
+            .collect()
+    }
+}
+
+/// An item in a destructuring pattern.
+#[derive(Debug, Clone, Hash)]
+pub enum DestructuringItem {
+    /// A nested pattern: `x`, `_` or `(y, z)`.
+    Pattern(Pattern),
+    /// A named pair that extracts a key from a dictionary: `name: n`.
+    Named(Named),
+    /// A sink for the remaining elements: `..rest` or `..`.
+    Sink(Option<Ident>),
+}
+
 node! {
     /// A let binding: `let x = 1`.
     LetBinding
 }
 
+/// The kind of a let binding.
+#[derive(Debug, Clone, Hash)]
+pub enum LetBindingKind {
+    /// A normal binding: `let x = 1` or `let (a, b) = arr`.
+    Normal(Pattern),
+    /// A closure binding: `let f(x) = 1`.
+    Closure(Ident),
+}
+
+impl LetBindingKind {
+    /// The identifiers this binding defines, from left to right.
+    pub fn idents(&self) -> Vec<Ident> {
+        match self {
+            Self::Normal(pattern) => pattern.idents(),
+            Self::Closure(ident) => vec![ident.clone()],
+        }
+    }
+}
+
 impl LetBinding {
-    /// The binding to assign to.
-    pub fn binding(&self) -> Ident {
-        match self.0.cast_first_match() {
-            Some(Expr::Ident(binding)) => binding,
-            Some(Expr::Closure(closure)) => closure.name().unwrap_or_default(),
-            _ => Ident::default(),
+    /// The kind of the binding.
+    pub fn kind(&self) -> LetBindingKind {
+        match self.0.cast_first_match::<Pattern>() {
+            Some(pattern) => LetBindingKind::Normal(pattern),
+            Option::None => LetBindingKind::Closure(
+                self.0
+                    .cast_first_match::<Closure>()
+                    .and_then(|closure| closure.name())
+                    .unwrap_or_default(),
+            ),
         }
     }
 
     /// The expression the binding is initialized with.
     pub fn init(&self) -> Option<Expr> {
-        if self.0.cast_first_match::<Ident>().is_some() {
+        match self.kind() {
             // This is a normal binding like `let x = 1`.
-            self.0.children().filter_map(SyntaxNode::cast).nth(1)
-        } else {
+            LetBindingKind::Normal(_) => self
+                .0
+                .children()
+                .skip_while(|child| child.kind() != SyntaxKind::Eq)
+                .find_map(SyntaxNode::cast),
             // This is a closure binding like `let f(x) = 1`.
-            self.0.cast_first_match()
+            LetBindingKind::Closure(_) => self.0.cast_first_match(),
         }
     }
 }
@@ -1718,24 +1840,25 @@ impl ForLoop {
 }
 
 node! {
-    /// A for loop's destructuring pattern: `x` or `x, y`.
+    /// A for loop's pattern: `x`, `(x, y)` or `x, y`.
     ForPattern
 }
 
 impl ForPattern {
     /// The key part of the pattern: index for arrays, name for dictionaries.
+    ///
+    /// This only exists for the key-value form `x, y`.
     pub fn key(&self) -> Option<Ident> {
-        let mut children = self.0.children().filter_map(SyntaxNode::cast);
+        let mut children = self.0.children().filter_map(SyntaxNode::cast::<Pattern>);
         let key = children.next();
-        if children.next().is_some() {
-            key
-        } else {
-            Option::None
+        match (key, children.next()) {
+            (Some(Pattern::Normal(key)), Some(_)) => Some(key),
+            _ => Option::None,
         }
     }
 
     /// The value part of the pattern.
-    pub fn value(&self) -> Ident {
+    pub fn value(&self) -> Pattern {
         self.0.cast_last_match().unwrap_or_default()
     }
 }
@@ -220,6 +220,8 @@ pub enum SyntaxKind {
     Params,
     /// A let binding: `let x = 1`.
     LetBinding,
+    /// A destructuring pattern: `(x, _, ..y)`.
+    Destructuring,
     /// A set rule: `set text(...)`.
     SetRule,
     /// A show rule: `show heading: it => emph(it.body)`.
@@ -230,7 +232,7 @@ pub enum SyntaxKind {
     WhileLoop,
     /// A for loop: `for x in y { z }`.
     ForLoop,
-    /// A for loop's destructuring pattern: `x` or `x, y`.
+    /// A for loop's pattern: `x`, `(x, y)` or `x, y`.
     ForPattern,
     /// A module import: `import a, b, c from "utils.typ"`.
     ModuleImport,
@@ -418,6 +420,7 @@ impl SyntaxKind {
             Self::Closure => "closure",
             Self::Params => "closure parameters",
             Self::LetBinding => "`let` expression",
+            Self::Destructuring => "destructuring pattern",
             Self::SetRule => "`set` expression",
             Self::ShowRule => "`show` expression",
             Self::Conditional => "`if` expression",
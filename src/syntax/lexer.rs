@@ -494,6 +494,9 @@ impl Lexer<'_> {
     fn ident(&mut self, start: usize) -> SyntaxKind {
         self.s.eat_while(is_id_continue);
         let ident = self.s.from(start);
+        if ident == "_" {
+            return SyntaxKind::Underscore;
+        }
 
         let prev = self.s.get(0..start);
         if !prev.ends_with(['.', '@']) || prev.ends_with("..") {
@@ -636,6 +636,17 @@ fn code_primary(p: &mut Parser, atomic: bool) {
                 p.wrap(m, SyntaxKind::Closure);
             }
         }
+        SyntaxKind::Underscore if !atomic => {
+            p.eat();
+            if p.at(SyntaxKind::Arrow) {
+                p.wrap(m, SyntaxKind::Params);
+                p.assert(SyntaxKind::Arrow);
+                code_expr(p);
+                p.wrap(m, SyntaxKind::Closure);
+            } else if let Some(underscore) = p.node_mut(m) {
+                underscore.convert_to_error("expected expression, found underscore");
+            }
+        }
 
         SyntaxKind::LeftBrace => code_block(p),
         SyntaxKind::LeftBracket => content_block(p),
@@ -709,6 +720,7 @@ fn with_paren(p: &mut Parser) {
     let m = p.marker();
     let mut kind = collection(p, true);
     if p.at(SyntaxKind::Arrow) {
+        restore_underscores(p, m);
         validate_params(p, m);
         p.wrap(m, SyntaxKind::Params);
         p.assert(SyntaxKind::Arrow);
@@ -838,17 +850,30 @@ fn let_binding(p: &mut Parser) {
     p.assert(SyntaxKind::Let);
 
     let m2 = p.marker();
-    p.expect(SyntaxKind::Ident);
-
-    let closure = p.directly_at(SyntaxKind::LeftParen);
-    if closure {
-        let m3 = p.marker();
-        collection(p, false);
-        validate_params(p, m3);
-        p.wrap(m3, SyntaxKind::Params);
+    let mut closure = false;
+    let mut destructuring = false;
+    match p.current() {
+        SyntaxKind::Ident => {
+            p.eat();
+            closure = p.directly_at(SyntaxKind::LeftParen);
+            if closure {
+                let m3 = p.marker();
+                collection(p, false);
+                restore_underscores(p, m3);
+                validate_params(p, m3);
+                p.wrap(m3, SyntaxKind::Params);
+            }
+        }
+        SyntaxKind::Underscore => p.eat(),
+        SyntaxKind::LeftParen => {
+            destructuring_pattern(p);
+            validate_bindings(p, m2);
+            destructuring = true;
+        }
+        _ => p.expected("identifier"),
     }
 
-    let f = if closure { Parser::expect } else { Parser::eat_if };
+    let f = if closure || destructuring { Parser::expect } else { Parser::eat_if };
     if f(p, SyntaxKind::Eq) {
         code_expr(p);
     }
@@ -933,14 +958,108 @@ fn for_loop(p: &mut Parser) {
 
 fn for_pattern(p: &mut Parser) {
     let m = p.marker();
-    if p.expect(SyntaxKind::Ident) {
+    if pattern(p) {
         if p.eat_if(SyntaxKind::Comma) {
-            p.expect(SyntaxKind::Ident);
+            if let Some(key) = p.node_mut(m) {
+                if key.kind() != SyntaxKind::Ident {
+                    let kind = key.kind();
+                    key.convert_to_error(eco_format!(
+                        "expected identifier, found {}",
+                        kind.name()
+                    ));
+                }
+            }
+            pattern(p);
         }
+        validate_bindings(p, m);
         p.wrap(m, SyntaxKind::ForPattern);
     }
 }
 
+fn pattern(p: &mut Parser) -> bool {
+    match p.current() {
+        SyntaxKind::Ident | SyntaxKind::Underscore => p.eat(),
+        SyntaxKind::LeftParen => destructuring_pattern(p),
+        _ => {
+            p.expected("identifier");
+            return false;
+        }
+    }
+    true
+}
+
+fn destructuring_pattern(p: &mut Parser) {
+    let m = p.marker();
+    p.stop_at_newline(false);
+    p.assert(SyntaxKind::LeftParen);
+
+    let mut sink = false;
+    while !p.current().is_terminator() {
+        let prev = p.prev_end();
+        destructuring_item(p, &mut sink);
+
+        if !p.progress(prev) {
+            p.unexpected();
+            continue;
+        }
+
+        if p.current().is_terminator() {
+            break;
+        }
+
+        p.expect(SyntaxKind::Comma);
+    }
+
+    p.expect(SyntaxKind::RightParen);
+    p.unstop();
+    p.wrap(m, SyntaxKind::Destructuring);
+}
+
+fn destructuring_item(p: &mut Parser, sink: &mut bool) {
+    let m = p.marker();
+
+    if p.eat_if(SyntaxKind::Dots) {
+        p.eat_if(SyntaxKind::Ident);
+        p.wrap(m, SyntaxKind::Spread);
+        if std::mem::replace(sink, true) {
+            if let Some(spread) = p.node_mut(m) {
+                spread.convert_to_error("only one destructuring sink is allowed");
+            }
+        }
+        return;
+    }
+
+    if !matches!(
+        p.current(),
+        SyntaxKind::Ident | SyntaxKind::Underscore | SyntaxKind::LeftParen
+    ) {
+        code_expr(p);
+        for child in p.post_process(m) {
+            let kind = child.kind();
+            child.convert_to_error(eco_format!(
+                "expected identifier or destructuring pattern, found {}",
+                kind.name()
+            ));
+        }
+        return;
+    }
+
+    pattern(p);
+    if p.eat_if(SyntaxKind::Colon) {
+        if let Some(key) = p.node_mut(m) {
+            if key.kind() != SyntaxKind::Ident {
+                let kind = key.kind();
+                key.convert_to_error(eco_format!(
+                    "expected identifier, found {}",
+                    kind.name()
+                ));
+            }
+        }
+        pattern(p);
+        p.wrap(m, SyntaxKind::Named);
+    }
+}
+
 fn module_import(p: &mut Parser) {
     let m = p.marker();
     p.assert(SyntaxKind::Import);
@@ -1045,6 +1164,11 @@ fn validate_params(p: &mut Parser, m: Marker) {
                     child.convert_to_error("duplicate parameter");
                 }
             }
+            SyntaxKind::Underscore => {}
+            SyntaxKind::Array | SyntaxKind::Dict | SyntaxKind::Parenthesized => {
+                *child = reparse_pattern(child);
+                validate_binding(child, &mut used);
+            }
             SyntaxKind::Named => {
                 let Some(within) = child.children_mut().first_mut() else { return };
                 if !used.insert(within.text().clone()) {
@@ -1073,6 +1197,60 @@ fn validate_params(p: &mut Parser, m: Marker) {
     }
 }
 
+/// Turn underscores that were parsed as erroneous expressions back into
+/// placeholders, now that we know that they are part of a pattern.
+fn restore_underscores(p: &mut Parser, m: Marker) {
+    for child in &mut p.nodes[m.0..] {
+        if child.kind() == SyntaxKind::Error && child.text() == "_" {
+            *child = SyntaxNode::leaf(SyntaxKind::Underscore, "_");
+        }
+    }
+}
+
+/// Reparse a parenthesized expression that turned out to be a pattern.
+fn reparse_pattern(node: &SyntaxNode) -> SyntaxNode {
+    let text = node.clone().into_text();
+    let mut p = Parser::new(&text, 0, LexMode::Code);
+    destructuring_pattern(&mut p);
+    let mut nodes = p.finish();
+    if nodes.len() == 1 && nodes[0].len() == node.len() {
+        nodes.pop().unwrap()
+    } else {
+        let mut node = node.clone();
+        node.convert_to_error("expected destructuring pattern");
+        node
+    }
+}
+
+fn validate_bindings(p: &mut Parser, m: Marker) {
+    let mut used = HashSet::new();
+    for child in p.post_process(m) {
+        validate_binding(child, &mut used);
+    }
+}
+
+fn validate_binding(node: &mut SyntaxNode, used: &mut HashSet<EcoString>) {
+    match node.kind() {
+        SyntaxKind::Ident => {
+            if !used.insert(node.text().clone()) {
+                node.convert_to_error("at most one binding per identifier is allowed");
+            }
+        }
+        SyntaxKind::Destructuring | SyntaxKind::Spread | SyntaxKind::Named => {
+            let skip = usize::from(node.kind() == SyntaxKind::Named);
+            let mut erroneous = false;
+            for child in node.children_mut().iter_mut().skip(skip) {
+                validate_binding(child, used);
+                erroneous |= child.erroneous();
+            }
+            if erroneous {
+                node.make_erroneous();
+            }
+        }
+        _ => {}
+    }
+}
+
 fn validate_args(p: &mut Parser, m: Marker) {
     let mut used = HashSet::new();
     for child in p.post_process(m) {
@@ -1191,6 +1369,10 @@ impl<'s> Parser<'s> {
         self.nodes.get(m.0)
     }
 
+    fn node_mut(&mut self, m: Marker) -> Option<&mut SyntaxNode> {
+        self.nodes.get_mut(m.0)
+    }
+
     fn post_process(&mut self, m: Marker) -> impl Iterator<Item = &mut SyntaxNode> {
         self.nodes[m.0..]
             .iter_mut()
@@ -12,6 +12,7 @@ use super::ast::Markup;
 use super::reparser::reparse;
 use super::{is_newline, parse, LinkedNode, Span, SyntaxNode};
 use crate::diag::SourceResult;
+use crate::package::PackageSpec;
 use crate::util::{PathExt, StrExt};
 
 /// A source file.
@@ -24,6 +25,7 @@ pub struct Source {
     lines: Vec<Line>,
     text: Prehashed<String>,
     root: Prehashed<SyntaxNode>,
+    package: Option<PackageSpec>,
 }
 
 impl Source {
@@ -37,6 +39,7 @@ impl Source {
             lines: lines(&text),
             text: Prehashed::new(text),
             root: Prehashed::new(root),
+            package: None,
         }
     }
 
@@ -55,9 +58,16 @@ impl Source {
             lines: lines(&text),
             text: Prehashed::new(text),
             root: Prehashed::new(root),
+            package: None,
         }
     }
 
+    /// Mark the source file as belonging to a package.
+    pub fn with_package(mut self, spec: PackageSpec) -> Self {
+        self.package = Some(spec);
+        self
+    }
+
     /// The root node of the file's untyped syntax tree.
     pub fn root(&self) -> &SyntaxNode {
         &self.root
@@ -83,6 +93,11 @@ impl Source {
         &self.path
     }
 
+    /// The package the source file belongs to, if any.
+    pub fn package(&self) -> Option<&PackageSpec> {
+        self.package.as_ref()
+    }
+
     /// The whole source as a string slice.
     pub fn text(&self) -> &str {
         &self.text
@@ -258,6 +273,7 @@ impl Hash for Source {
         self.path.hash(state);
         self.text.hash(state);
         self.root.hash(state);
+        self.package.hash(state);
     }
 }
 
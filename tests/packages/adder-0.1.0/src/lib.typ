@@ -0,0 +1,5 @@
+// Absolute paths are relative to the package root.
+#let data = read("/data.txt")
+#let relative = read("../data.txt")
+
+#let add(x, y) = x + y
@@ -0,0 +1,2 @@
+// Packages can't read files of other packages.
+#let data = read("../adder-0.1.0/data.txt")
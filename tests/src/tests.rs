@@ -10,11 +10,12 @@ use comemo::{Prehashed, Track};
 use elsa::FrozenVec;
 use once_cell::unsync::OnceCell;
 use tiny_skia as sk;
-use typst::diag::{bail, FileError, FileResult};
+use typst::diag::{bail, FileError, FileResult, Severity};
 use typst::doc::{Document, Frame, FrameItem, Meta};
-use typst::eval::{func, Library, Value};
+use typst::eval::{func, Datetime, Library, Value};
 use typst::font::{Font, FontBook};
 use typst::geom::{Abs, Color, RgbaColor, Sides, Smart};
+use typst::package::PackageSpec;
 use typst::syntax::{Source, SourceId, Span, SyntaxNode};
 use typst::util::{Buffer, PathExt};
 use typst::World;
@@ -29,6 +30,7 @@ const PNG_DIR: &str = "png";
 const PDF_DIR: &str = "pdf";
 const FONT_DIR: &str = "../assets/fonts";
 const FILE_DIR: &str = "../assets/files";
+const PACKAGE_DIR: &str = "packages";
 
 fn main() {
     let args = Args::new(env::args().skip(1));
@@ -283,6 +285,18 @@ impl World for TestWorld {
             .get_or_init(|| read(path).map(Buffer::from))
             .clone()
     }
+
+    fn package(&self, spec: &PackageSpec) -> FileResult<PathBuf> {
+        let root = Path::new(PACKAGE_DIR).join(format!("{}-{}", spec.name, spec.version));
+        if spec.namespace != "test" || !root.is_dir() {
+            return Err(FileError::PackageNotFound(spec.clone()));
+        }
+        Ok(root.normalize())
+    }
+
+    fn today(&self, _: Option<i64>) -> Option<Datetime> {
+        Some(Datetime::from_ymd(1970, 1, 1).unwrap())
+    }
 }
 
 impl TestWorld {
@@ -310,7 +324,10 @@ impl TestWorld {
 
     fn insert(&self, path: &Path, text: String) -> SourceId {
         let id = SourceId::from_u16(self.sources.len() as u16);
-        let source = Source::new(id, path, text);
+        let mut source = Source::new(id, path, text);
+        if let Some(spec) = package_of(path) {
+            source = source.with_package(spec);
+        }
         self.sources.push(Box::new(source));
         id
     }
@@ -331,6 +348,13 @@ fn read(path: &Path) -> FileResult<Vec<u8>> {
     }
 }
 
+/// Determine the package a file in the package directory belongs to.
+fn package_of(path: &Path) -> Option<PackageSpec> {
+    let dir = path.strip_prefix(PACKAGE_DIR).ok()?.components().next()?;
+    let (name, version) = dir.as_os_str().to_str()?.rsplit_once('-')?;
+    format!("@test/{name}:{version}").parse().ok()
+}
+
 fn test(
     world: &mut TestWorld,
     src_path: &Path,
@@ -378,7 +402,8 @@ fn test(
     let document = Document { pages: frames, ..Default::default() };
     if compare_ever {
         if let Some(pdf_path) = pdf_path {
-            let pdf_data = typst::export::pdf(&document);
+            let options = typst::export::PdfOptions::default();
+            let pdf_data = typst::export::pdf(&document, &options).unwrap();
             fs::create_dir_all(&pdf_path.parent().unwrap()).unwrap();
             fs::write(pdf_path, pdf_data).unwrap();
         }
@@ -389,6 +414,15 @@ fn test(
             }
         }
 
+        // Every page must also export to well-formed SVG.
+        for (i, frame) in document.pages.iter().enumerate() {
+            let svg = typst::export::svg(frame);
+            if let Err(err) = roxmltree::Document::parse(&svg) {
+                println!("  Page {} exports to invalid SVG: {err} ❌", i + 1);
+                ok = false;
+            }
+        }
+
         let canvas = render(&document.pages);
         fs::create_dir_all(&png_path.parent().unwrap()).unwrap();
         canvas.save_png(png_path).unwrap();
@@ -453,9 +487,14 @@ fn test_part(
         println!("Model:\n{:#?}\n", module.content());
     }
 
-    let (mut frames, errors) = match typst::compile(world) {
-        Ok(document) => (document.pages, vec![]),
-        Err(errors) => (vec![], *errors),
+    let mut tracer = typst::eval::Tracer::default();
+    let (mut frames, diagnostics) = match typst::compile(world, &mut tracer) {
+        Ok(document) => (document.pages, tracer.warnings().to_vec()),
+        Err(errors) => {
+            let mut diagnostics = tracer.warnings().to_vec();
+            diagnostics.extend(*errors);
+            (vec![], diagnostics)
+        }
     };
 
     // Don't retain frames if we don't wanna compare with reference images.
@@ -463,12 +502,22 @@ fn test_part(
         frames.clear();
     }
 
-    // Map errors to range and message format, discard traces and errors from
-    // other files.
-    let mut errors: Vec<_> = errors
+    // Map diagnostics to range and message format. Diagnostics from other
+    // files are annotated at the import in this file that led to them and
+    // discarded if there is none.
+    let mut errors: Vec<_> = diagnostics
         .into_iter()
-        .filter(|error| error.span.source() == id)
-        .map(|error| (error.range(world), error.message.replace('\\', "/")))
+        .filter_map(|diagnostic| {
+            let range = if diagnostic.span.source() == id {
+                diagnostic.range(world)
+            } else {
+                let point =
+                    diagnostic.trace.iter().find(|point| point.span.source() == id)?;
+                world.source(id).range(point.span)
+            };
+            let message = diagnostic.message.replace('\\', "/");
+            Some((range, diagnostic.severity, message))
+        })
         .collect();
 
     errors.sort_by_key(|error| error.0.start);
@@ -497,7 +546,9 @@ fn test_part(
     (ok, compare_ref, frames)
 }
 
-fn parse_metadata(source: &Source) -> (Option<bool>, Vec<(Range<usize>, String)>) {
+fn parse_metadata(
+    source: &Source,
+) -> (Option<bool>, Vec<(Range<usize>, Severity, String)>) {
     let mut compare_ref = None;
     let mut errors = vec![];
 
@@ -526,24 +577,39 @@ fn parse_metadata(source: &Source) -> (Option<bool>, Vec<(Range<usize>, String)>
             source.line_column_to_byte(line, column).unwrap()
         };
 
-        let Some(rest) = line.strip_prefix("// Error: ") else { continue };
+        let (severity, rest) = if let Some(rest) = line.strip_prefix("// Error: ") {
+            (Severity::Error, rest)
+        } else if let Some(rest) = line.strip_prefix("// Warning: ") {
+            (Severity::Warning, rest)
+        } else {
+            continue;
+        };
+
         let mut s = Scanner::new(rest);
         let start = pos(&mut s);
         let end = if s.eat_if('-') { pos(&mut s) } else { start };
         let range = start..end;
 
-        errors.push((range, s.after().trim().to_string()));
+        errors.push((range, severity, s.after().trim().to_string()));
     }
 
     (compare_ref, errors)
 }
 
-fn print_error(source: &Source, line: usize, (range, message): &(Range<usize>, String)) {
+fn print_error(
+    source: &Source,
+    line: usize,
+    (range, severity, message): &(Range<usize>, Severity, String),
+) {
     let start_line = 1 + line + source.byte_to_line(range.start).unwrap();
     let start_col = 1 + source.byte_to_column(range.start).unwrap();
     let end_line = 1 + line + source.byte_to_line(range.end).unwrap();
     let end_col = 1 + source.byte_to_column(range.end).unwrap();
-    println!("Error: {start_line}:{start_col}-{end_line}:{end_col}: {message}");
+    let kind = match severity {
+        Severity::Error => "Error",
+        Severity::Warning => "Warning",
+    };
+    println!("{kind}: {start_line}:{start_col}-{end_line}:{end_col}: {message}");
 }
 
 /// Pseudorandomly edit the source file and test whether a reparse produces the
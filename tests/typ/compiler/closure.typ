@@ -166,3 +166,34 @@
 ---
 // Error: 10-14 expected identifier, found `none`
 #let foo(none: b) = key
+
+---
+// Test destructuring parameters.
+#{
+  let f((a, b), c) = a + b + c
+  test(f((1, 2), 3), 6)
+
+  let g = ((x, y)) => x * y
+  test(g((2, 3)), 6)
+
+  let h = (_, b) => b
+  test(h(1, 2), 2)
+
+  let first((head, ..)) = head
+  test(first((3, 4, 5)), 3)
+
+  let name((id, name: n, ..)) = n
+  test(name((id: 1, name: "Typst", age: 2)), "Typst")
+
+  let ignore = _ => none
+  test(ignore(1), none)
+}
+
+---
+// Error: 8-14 not enough elements to destructure (expected 2, found 1)
+#let f((a, b)) = a
+#f((1,))
+
+---
+// Error: 16-17 duplicate parameter
+#let f((a, b), a) = none
@@ -122,3 +122,31 @@ A#for "v" thing
 
 // Error: 7 expected keyword `in`
 #for a + b in iter {}
+
+---
+// Test destructuring in for loops.
+#let out = ()
+#for (k, v) in (a: 1, b: 2) {
+  out.push(k + "=" + str(v))
+}
+#test(out, ("a=1", "b=2"))
+
+#let sum = 0
+#for (a, b) in ((1, 2), (3, 4)) {
+  sum += a * b
+}
+#test(sum, 14)
+
+#let names = ()
+#for i, (name: n, ..) in ((name: "a", x: 1), (name: "b")) {
+  names.push(str(i) + n)
+}
+#test(names, ("0a", "1b"))
+
+---
+// Error: 6-12 cannot destructure string
+#for (a, b) in "hi" {}
+
+---
+// Error: 6-12 expected identifier, found destructuring pattern
+#for (a, b), c in () {}
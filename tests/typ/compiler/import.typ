@@ -84,6 +84,7 @@
 
 ---
 // Cyclic import in other file.
+// Error: 9-31 cyclic import
 #import "./modules/cycle1.typ": *
 
 This is never reached.
@@ -68,3 +68,92 @@ Three
 
 // Error: 15 expected expression
 #let func(x) =
+
+---
+// Test destructuring of arrays.
+// Ref: false
+#let (a, b) = (1, 2)
+#test(a, 1)
+#test(b, 2)
+
+#let (a,) = (1,)
+#test(a, 1)
+
+#let ((a, b), c) = ((1, 2), 3)
+#test((a, b, c), (1, 2, 3))
+
+// Placeholders and sinks.
+#let (a, _, ..rest, z) = (1, 2, 3, 4, 5)
+#test(a, 1)
+#test(rest, (3, 4))
+#test(z, 5)
+
+#let (first, ..) = (1, 2, 3)
+#test(first, 1)
+
+#let (a, ..rest, b) = (1, 2)
+#test(rest, ())
+
+#let _ = 1
+
+---
+// Test destructuring of dictionaries.
+// Ref: false
+#let (name: n, age) = (name: "Typst", age: 2, year: 2023)
+#test(n, "Typst")
+#test(age, 2)
+
+#let (name, ..rest) = (name: "Typst", age: 2, year: 2023)
+#test(name, "Typst")
+#test(rest, (age: 2, year: 2023))
+
+#let (point: (x, y)) = (point: (1, 2))
+#test((x, y), (1, 2))
+
+---
+// Error: 6-15 not enough elements to destructure (expected 3, found 2)
+#let (a, b, c) = (1, 2)
+
+---
+// Error: 6-12 too many elements to destructure (expected 2, found 3)
+#let (a, b) = (1, 2, 3)
+
+---
+// Error: 6-20 not enough elements to destructure (expected at least 3, found 2)
+#let (a, b, ..c, d) = (1, 2)
+
+---
+// Error: 6-12 cannot destructure integer
+#let (a, b) = 1
+
+---
+// Error: 10-11 dictionary does not contain key "b"
+#let (a, b) = (a: 1)
+
+---
+// Error: 10-14 cannot destructure named elements from an array
+#let (a, b: c) = (1, 2)
+
+---
+// Error: 10-11 cannot destructure unnamed pattern from dictionary
+#let (a, _) = (a: 1, b: 2)
+
+---
+// Error: 14-15 at most one binding per identifier is allowed
+#let (a, (b, a)) = (1, (2, 3))
+
+---
+// Error: 12-15 only one destructuring sink is allowed
+#let (..a, ..b) = (1, 2)
+
+---
+// Error: 10-11 expected identifier or destructuring pattern, found integer
+#let (a, 1) = (1, 2)
+
+---
+// Error: 12 expected equals sign
+#let (a, b)
+
+---
+// Error: 3-4 expected expression, found underscore
+#(_)
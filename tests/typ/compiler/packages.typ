@@ -0,0 +1,54 @@
+// Test package imports.
+// Ref: false
+
+---
+// Test importing a package as a module.
+#import "@test/adder:0.1.0"
+#test(adder.add(2, 8), 10)
+
+---
+// Test importing items from a package.
+#import "@test/adder:0.1.0": add, data
+#test(add(1, 2), 3)
+#test(data, "Hello from the package!")
+
+---
+// Test that paths are resolved within the package.
+#import "@test/adder:0.1.0": data, relative
+#test(data, relative)
+
+---
+// Error: 9-29 package not found (searched for @local/adder:0.1.0)
+#import "@local/adder:0.1.0"
+
+---
+// Error: 9-28 package not found (searched for @test/adder:0.2.0)
+#import "@test/adder:0.2.0"
+
+---
+// Error: 9-22 package specification is missing version
+#import "@test/adder"
+
+---
+// Error: 9-24 package specification is missing namespace
+#import "@/adder:0.1.0"
+
+---
+// Error: 9-24 version number is missing minor version
+#import "@test/adder:1"
+
+---
+// Error: 9-28 `x` is not a valid patch version
+#import "@test/adder:0.1.x"
+
+---
+// Error: 9-31 package manifest contains mismatched name `adder`
+#import "@test/mismatch:0.1.0"
+
+---
+// Error: 9-29 cannot access files outside of the package
+#import "@test/escape:0.1.0"
+
+---
+// Error: 9-30 package entrypoint must lie within the package
+#import "@test/outside:0.1.0"
@@ -64,3 +64,19 @@ Hello *#x*
 ---
 // Error: 12-26 set is only allowed directly in code and content blocks
 #{ let x = set text(blue) }
+
+---
+// Test that a set rule at the end of a block warns.
+// Ref: false
+// Warning: 4-17 set rule has no effect
+#[#set text(red)]
+
+---
+// Test that trailing spaces and comments don't hide the set rule.
+// Ref: false
+#[
+  // Warning: 4-17 set rule has no effect
+  #set text(red)
+  // Nothing follows.
+
+]
@@ -96,7 +96,7 @@ Hey
 = Heading
 
 ---
-// Error: 7-10 expected function, label, string, regular expression, or selector, found color
+// Error: 7-10 expected function, location, label, string, regular expression, or selector, found color
 #show red: []
 
 ---
@@ -0,0 +1,63 @@
+// Test the bytes type.
+// Ref: false
+
+---
+// Test creating and converting bytes.
+#let data = bytes("Hello")
+#test(type(data), "bytes")
+#test(data.len(), 5)
+#test(array(data), (72, 101, 108, 108, 111))
+#test(str(data), "Hello")
+#test(bytes((72, 105)), bytes("Hi"))
+#test(bytes(data), data)
+#test(str(bytes(())), "")
+
+---
+// Test indexing and slicing.
+#let data = bytes((1, 2, 3, 4, 5))
+#test(data.at(0), 1)
+#test(data.at(-1), 5)
+#test(array(data.slice(1, 3)), (2, 3))
+#test(array(data.slice(2)), (3, 4, 5))
+#test(array(data.slice(-2)), (4, 5))
+#test(array(data.slice(1, count: 2)), (2, 3))
+
+---
+// Test concatenation.
+#test(bytes("ab") + bytes("cd"), bytes("abcd"))
+#test(str({ bytes("a"); bytes("b") }), "ab")
+#test(bytes(()) + bytes("x"), bytes("x"))
+
+---
+// Test reading binary files.
+#let data = read("/hello.txt", encoding: none)
+#test(type(data), "bytes")
+#test(str(data), read("/hello.txt"))
+#test(array(read("/rhino.png", encoding: none).slice(0, 4)), (137, 80, 78, 71))
+
+---
+// Test decoding images.
+#let svg = read("/tetrahedron.svg")
+#image.decode(svg, width: 20pt)
+#image.decode(bytes(svg), format: "svg", width: 20pt)
+#image.decode(read("/rhino.png", encoding: none), height: 1cm)
+
+---
+// Error: 2-19 byte index out of bounds (index: 3, len: 2)
+#bytes("ab").at(3)
+
+---
+// Error: 8-16 number must be between 0 and 255
+#bytes((1, 256))
+
+---
+// Error: 6-21 bytes are not valid utf-8
+#str(bytes((255, 0)))
+
+---
+// Error: 2-41 unknown image format
+#image.decode(bytes((1, 2, 3, 4, 5, 6)))
+
+---
+// Error: 34-39 expected "png", "jpg", "gif", "svg", auto, or none
+#image.decode(bytes(()), format: "bmp")
@@ -0,0 +1,113 @@
+// Test datetime and duration values.
+// Ref: false
+
+---
+// Test the different kinds of datetimes.
+#let date = datetime(year: 2023, month: 4, day: 12)
+#test(type(date), "datetime")
+#test(date.display(), "2023-04-12")
+#test(date.display("[day].[month].[year]"), "12.04.2023")
+#test(date.display("[month repr:long] [day padding:none]"), "April 12")
+#test(repr(date), "datetime(year: 2023, month: 4, day: 12)")
+
+#let time = datetime(hour: 13, minute: 5, second: 0)
+#test(time.display(), "13:05:00")
+#test(time.display("[hour repr:12][period case:lower]"), "01pm")
+#test(time.year(), none)
+
+#let full = datetime(year: 2023, month: 4, day: 12, hour: 9, minute: 30, second: 15)
+#test(full.display(), "2023-04-12 09:30:15")
+
+---
+// Test datetime component methods.
+#let date = datetime(year: 2023, month: 4, day: 12, hour: 9, minute: 30, second: 15)
+#test(date.year(), 2023)
+#test(date.month(), 4)
+#test(date.day(), 12)
+#test(date.weekday(), 3)
+#test(date.ordinal(), 102)
+#test(date.hour(), 9)
+#test(date.minute(), 30)
+#test(date.second(), 15)
+
+---
+// Test comparison and equality.
+#let a = datetime(year: 2023, month: 1, day: 1)
+#let b = datetime(year: 2023, month: 1, day: 2)
+#test(a < b, true)
+#test(a >= b, false)
+#test(a == datetime(year: 2023, month: 1, day: 1), true)
+#test(duration(hours: 1) < duration(minutes: 61), true)
+#test(duration(days: 1), duration(hours: 24))
+
+---
+// Test arithmetic with durations.
+#let date = datetime(year: 2023, month: 4, day: 12)
+#test(date + duration(days: 20), datetime(year: 2023, month: 5, day: 2))
+#test(duration(weeks: 1) + date, datetime(year: 2023, month: 4, day: 19))
+#test(date - duration(days: 12), datetime(year: 2023, month: 3, day: 31))
+#test((datetime(year: 2023, month: 5, day: 1) - datetime(year: 2023, month: 4, day: 1)).days(), 30)
+
+#let time = datetime(hour: 23, minute: 0, second: 0)
+#test((time + duration(hours: 2)).display(), "01:00:00")
+
+#let d = duration(days: 3, hours: 12)
+#test(d.hours(), 84)
+#test(d.weeks(), 0.5)
+#test((d * 2).days(), 7)
+#test(d / 2, duration(hours: 42))
+#test(d / duration(hours: 6), 14)
+#test(-d + d, duration())
+#test(d - duration(hours: 12), duration(days: 3))
+#test(repr(duration(hours: 25, seconds: 3)), "duration(days: 1, hours: 1, seconds: 3)")
+#test(repr(duration()), "duration(seconds: 0)")
+
+---
+// Test the date supplied by the environment.
+#test(datetime.today().display(), "1970-01-01")
+#test(datetime.today(offset: 2), datetime(year: 1970, month: 1, day: 1))
+
+---
+// Error: 10-41 date is invalid
+#datetime(year: 2023, month: 13, day: 1)
+
+---
+// Error: 10-42 time is invalid
+#datetime(hour: 24, minute: 0, second: 0)
+
+---
+// Error: 10-32 date is incomplete
+#datetime(year: 2023, month: 1)
+
+---
+// Error: 10-12 at least one of date or time must be fully specified
+#datetime()
+
+---
+// Error: 17-19 hour is invalid
+#datetime(hour: -1, minute: 0, second: 0)
+
+---
+// Error: 2-58 cannot display component `hour` of this datetime
+#datetime(year: 2023, month: 1, day: 1).display("[hour]")
+
+---
+// Error: 3-83 cannot subtract datetimes of different kinds
+#(datetime(hour: 1, minute: 0, second: 0) - datetime(year: 2023, month: 1, day: 1))
+
+---
+// Error: 3-24 cannot divide by zero
+#(duration(days: 1) / 0)
+
+---
+// Error: 3-64 duration is too large
+#(duration(seconds: 9223372036854775807) + duration(seconds: 1))
+
+---
+// Error: 3-65 duration is too large
+#(duration(seconds: -9223372036854775807) - duration(seconds: 2))
+
+---
+#let min = duration(seconds: -9223372036854775807) - duration(seconds: 1)
+// Error: 3-7 duration is too large
+#(-min)
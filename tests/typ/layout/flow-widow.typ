@@ -0,0 +1,32 @@
+// Test widow and orphan control.
+
+---
+// Test that a heading moves along with the block following it.
+#set page(height: 100pt, margin: 10pt)
+#lorem(5)
+= Heading <h>
+#rect(height: 60pt)
+
+#locate(loc => test(query(<h>, loc).first().location().page(), 2))
+
+---
+// Test that a paragraph with too few lines left moves to the next page.
+#set page(height: 100pt, margin: 10pt)
+#set par(orphans: 3)
+#v(50pt)
+#locate(loc => test(loc.page(), 2)) #lorem(30)
+
+---
+// Test keeping more lines together at the end of a paragraph.
+#set page(height: 100pt, margin: 10pt)
+#set par(widows: 3)
+#lorem(20)#locate(loc => {
+  // The paragraph ends in the third line on the second page. With the
+  // default of two widow lines, it would end in the second one.
+  test(loc.page(), 2)
+  test(loc.position().y > 40pt, true)
+})
+
+---
+// Error: 19-20 number must be positive
+#set par(orphans: 0)
@@ -0,0 +1,49 @@
+// Test floating placement.
+
+---
+// Test that a float which doesn't fit is deferred to the next page.
+#set page(width: 100pt, height: 100pt, margin: 10pt)
+First.
+#figure(placement: bottom, rect(height: 20pt), numbering: none) <bottom>
+#figure(placement: top, rect(height: 60pt), numbering: none) <top>
+Second.
+
+#locate(loc => {
+  let top = query(<top>, loc).first().location()
+  let bottom = query(<bottom>, loc).first().location()
+  test(top.page(), 2)
+  test(top.position().y, 10pt)
+  test(bottom.page(), 1)
+  test(bottom.position().y, 70pt)
+})
+
+---
+// Test that a float spanning all columns waits for the next row.
+#set page(width: 200pt, height: 100pt, margin: 10pt, columns: 2)
+A
+#colbreak()
+B
+#figure(
+  placement: top,
+  scope: "parent",
+  rect(height: 20pt),
+  numbering: none,
+) <span>
+
+#locate(loc => {
+  let span = query(<span>, loc).first().location()
+  test(span.page(), 2)
+  test(span.position().y, 10pt)
+})
+
+---
+// Error: 2-36 floating placement must be `auto`, `top`, or `bottom`
+#place(horizon, float: true)[Hello]
+
+---
+// Error: 2-28 floating placement must be `auto`, `top`, or `bottom`
+#figure(placement: left)[A]
+
+---
+// Error: 28-34 expected "column" or "parent"
+#place(float: true, scope: "page")[Hello]
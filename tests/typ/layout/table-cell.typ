@@ -0,0 +1,71 @@
+// Test table and grid cells.
+
+---
+// Test merged cells with explicit positions.
+#set page(height: 120pt)
+#table(
+  columns: (auto, 1fr, 1fr),
+  fill: (x, y) => if y == 0 { luma(230) },
+  table.cell(colspan: 3, align: center)[*Results*],
+  table.cell(rowspan: 2)[Trial],
+  [Day 1], [Day 2],
+  [12], [14],
+  table.cell(x: 0, y: 3, colspan: 2, fill: yellow)[Total],
+  [26],
+  table.cell(stroke: (bottom: 2pt + red))[A],
+  table.cell(inset: 10pt)[B],
+)
+
+---
+// Test that a spanning cell widens auto columns and grows auto rows.
+#grid(
+  columns: (auto, auto),
+  gutter: 3pt,
+  grid.cell(colspan: 2, fill: aqua)[A very wide cell],
+  [A], [B],
+  grid.cell(rowspan: 2, fill: teal)[Tall \ Tall \ Tall],
+  [C],
+)
+
+---
+// Test that rows connected by a spanning cell break together.
+#set page(height: 80pt)
+#table(
+  columns: 2,
+  ..range(4).map(str),
+  table.cell(rowspan: 3)[Kept together],
+  [A], [B], [C],
+)
+
+---
+// Test cells in right-to-left grids.
+#set text(dir: rtl)
+#table(columns: 3, table.cell(colspan: 2)[A], [B], [C], [D], [E])
+
+---
+// Error: 20-45 cell spans 3 columns, but the grid only has 2
+#table(columns: 2, table.cell(colspan: 3)[A])
+
+---
+// Error: 47-72 attempted to place a second cell at column 0, row 0
+#table(columns: 2, table.cell(x: 0, y: 0)[A], table.cell(x: 0, y: 0)[B])
+
+---
+// Error: 24-42 row 0 has no space left for this cell
+#grid(columns: 1, [A], grid.cell(y: 0)[B])
+
+---
+// Error: 20-57 cell at row 9223372036854775807 does not fit into the grid, which can have at most 32768 rows
+#table(columns: 2, table.cell(y: 9223372036854775807)[A])
+
+---
+// Error: 19-46 cell at row 1000000000 does not fit into the grid, which can have at most 65536 rows
+#grid(columns: 1, grid.cell(y: 1000000000)[A])
+
+---
+// Error: 20-63 cell spans 9223372036854775807 rows, but a grid can have at most 32768
+#table(columns: 2, table.cell(rowspan: 9223372036854775807)[A])
+
+---
+// Error: 29-64 cell at row 32766 does not fit into the grid, which can have at most 32768 rows
+#table(columns: 2, rows: 1, table.cell(y: 32766, rowspan: 3)[A])
@@ -0,0 +1,65 @@
+// Test table and grid headers and footers.
+
+---
+// Test that the header and footer repeat on every page.
+#set page(height: 120pt)
+#table(
+  columns: (1fr, auto),
+  fill: (x, y) => if y == 0 { luma(230) },
+  table.header[*Name*][*Score*],
+  ..range(12).map(i => ([Player #i], [#(i * 10)])).flatten(),
+  table.footer[*Total*][660],
+)
+
+---
+// Test that the header isn't left on its own at the bottom of a page.
+#set page(width: 100pt, height: 100pt, margin: 10pt)
+#v(50pt)
+#table(
+  rows: (auto, 40pt),
+  table.header[#figure(rect(height: 10pt), numbering: none) <head>],
+  [Body],
+)
+
+#locate(loc => {
+  let head = query(<head>, loc).first().location()
+  test(head.page(), 2)
+  test(head.position().y, 15pt)
+})
+
+---
+// Test headers and footers that don't repeat.
+#set page(height: 100pt)
+#table(
+  columns: 2,
+  table.header(repeat: false)[A][B],
+  ..range(16).map(str),
+  table.footer(repeat: false)[C][D],
+)
+
+---
+// Test a grid header with gutter and a cell spanning its rows.
+#set page(height: 100pt)
+#grid(
+  columns: 3,
+  gutter: 5pt,
+  grid.header(
+    grid.cell(rowspan: 2, fill: aqua)[Key],
+    grid.cell(colspan: 2, fill: teal)[Values],
+    [Min], [Max],
+  ),
+  ..range(24).map(str),
+  grid.footer(grid.cell(colspan: 3)[End]),
+)
+
+---
+// Error: 25-40 header must start at the first row
+#table(columns: 2, [A], table.header[B])
+
+---
+// Error: 25-40 cannot have more than one header
+#table(table.header[A], table.header[B])
+
+---
+// Error: 20-35 footer must end at the last row
+#table(columns: 2, table.footer[A], [B])
@@ -0,0 +1,52 @@
+// Test table strokes and lines.
+
+---
+// Test booktabs-style rules.
+#table(
+  columns: 3,
+  stroke: (x, y) => if y == 0 { (bottom: 0.7pt) },
+  table.hline(),
+  [*Name*], [*Age*], [*City*],
+  [Anna], [29], [Oslo],
+  [Ben], [41], [Rome],
+  table.hline(),
+)
+
+---
+// Test zebra rules and an outer border.
+#table(
+  columns: 3,
+  stroke: (x, y) => (
+    left: if x == 0 { 1pt },
+    right: if x == 2 { 1pt },
+    top: if y == 0 { 1pt },
+    bottom: if calc.odd(y) { 0.5pt + gray } else if y == 3 { 1pt },
+  ),
+  ..range(12).map(str),
+)
+
+---
+// Test per-side strokes and cell overrides.
+#table(
+  columns: 2,
+  stroke: (x: none, bottom: 1pt),
+  [A], [B],
+  table.cell(stroke: (right: red))[C], [D],
+)
+
+---
+// Test lines with ranges and in right-to-left tables.
+#set text(dir: rtl)
+#table(
+  columns: 3,
+  stroke: none,
+  [A], table.vline(stroke: blue), [B], [C],
+  table.hline(start: 1, stroke: (dash: "dotted")),
+  [D], [E], [F],
+  table.vline(x: 3, start: 1, end: 2),
+  table.hline(y: 1, end: 1, stroke: none),
+)
+
+---
+// Error: 30-47 cannot draw a line at row 3
+#table(columns: 2, [A], [B], table.hline(y: 3))
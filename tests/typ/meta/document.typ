@@ -24,12 +24,14 @@ Hello
 
 ---
 #box[
+  // Warning: 4-32 set rule has no effect
   // Error: 4-32 document set rules are not allowed inside of containers
   #set document(title: "Hello")
 ]
 
 ---
 #box[
+  // Warning: 4-18 set rule has no effect
   // Error: 4-18 page configuration is not allowed inside of containers
   #set page("a4")
 ]
@@ -0,0 +1,12 @@
+// Test alternative descriptions of images and figures.
+// Ref: false
+
+---
+#let img = image("/tiger.jpg", alt: "A tiger")
+#test(img.alt, "A tiger")
+#test(figure(img, alt: "A big cat").alt, "A big cat")
+#test(image("/tiger.jpg").has("alt"), false)
+
+---
+// Error: 27-29 expected string or none, found integer
+#image("/tiger.jpg", alt: 10)
@@ -0,0 +1,46 @@
+// Test footnotes.
+
+---
+#footnote[Hi]
+
+---
+// Test space collapsing before footnote.
+A#footnote[A] \
+A #footnote[A]
+
+---
+// Test nested footnotes and numbering.
+#set footnote(numbering: "*")
+First \
+Second #footnote[A, #footnote[B, #footnote[C]]] \
+Third #footnote[D, #footnote[E]] \
+Fourth
+
+---
+// Test that footnotes move to the next page together with their reference.
+#set page(height: 100pt)
+#lorem(20)
+#footnote[#lorem(20)]
+#lorem(5)
+#footnote[Another note]
+
+---
+// Test references to footnotes.
+A footnote #footnote[Hi] <fn> \
+A reference to it @fn \
+Another #footnote(<fn>)
+
+---
+// Test customization of the listing.
+#set footnote.entry(gap: 0.8em, indent: 0pt, separator: repeat[.])
+#show footnote.entry: set text(red)
+#test(type(footnote.entry), "function")
+Customized #footnote[Hello]
+
+---
+// Error: 2-21 label does not exist in the document
+#footnote(<missing>)
+
+---
+// Error: 11-14 function `footnote` does not contain `foo`
+#footnote.foo
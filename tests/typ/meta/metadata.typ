@@ -0,0 +1,13 @@
+// Test the metadata element.
+// Ref: false
+
+---
+#metadata("Hello") <greeting>
+#metadata((version: "1.2", draft: false)) <info>
+
+#locate(loc => {
+  test(query(<greeting>, loc).first().value, "Hello")
+  test(query(<info>, loc).first().value.version, "1.2")
+  test(query(metadata, loc).len(), 2)
+  test(query(metadata.where(value: "Hello"), loc).len(), 1)
+})
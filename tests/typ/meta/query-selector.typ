@@ -0,0 +1,67 @@
+// Test selector methods in queries.
+// Ref: false
+
+---
+// Test before and after.
+= Alpha
+== Beta <beta>
+
+#locate(loc => {
+  let bodies(target) = query(target, loc).map(it => it.body)
+  test(bodies(selector(heading).before(loc)), ([Alpha], [Beta]))
+  test(bodies(heading.where(level: 1).after(loc)), ([Gamma],))
+  test(bodies(selector(heading).after(<delta>)), ([Delta],))
+  test(bodies(selector(heading).after(<delta>, inclusive: false)), ())
+  test(bodies(selector(heading).before(<beta>, inclusive: false)), ([Alpha],))
+  test(bodies(selector(heading).after(<beta>).before(<delta>)), ([Beta], [Gamma], [Delta]))
+})
+
+= Gamma
+== Delta <delta>
+
+---
+// Test combining selectors.
+= Alpha
+== Beta <beta>
+= Gamma
+
+#locate(loc => {
+  let bodies(target) = query(target, loc).map(it => it.body)
+  test(bodies(heading.where(level: 2).or(<gamma>)), ([Beta],))
+  test(bodies(heading.where(level: 1).or(<beta>)), ([Alpha], [Beta], [Gamma]))
+  test(bodies(selector(heading).and(<beta>)), ([Beta],))
+  test(bodies(heading.where(level: 1).and(<beta>)), ())
+  test(bodies(selector(<beta>).or(heading.where(level: 1).after(<beta>))), ([Beta], [Gamma]))
+})
+
+---
+// Test containment.
+#figure(rect[A], caption: [Main])
+#block[
+  #figure(rect[B], caption: [Extra])
+  #block[#figure(rect[C], caption: [Nested])]
+] <appendix>
+
+#locate(loc => {
+  let captions(target) = query(target, loc).map(it => it.caption)
+  test(captions(selector(figure).within(<appendix>)), ([Extra], [Nested]))
+  test(captions(selector(figure).within(heading)), ())
+})
+
+---
+// Test the representation of selectors.
+#test(repr(selector(heading).before(<a>, inclusive: false)), "heading.before(<a>, inclusive: false)")
+#test(repr(heading.where(level: 1).or(<b>)), "any(heading.where(level: 1), <b>)")
+#test(repr(selector(figure).within(<c>)), "figure.within(<c>)")
+
+---
+// Error: 7-35 this selector cannot be used in show rules
+#show selector(heading).after(<a>): none
+
+---
+// Error: 8-24 cannot query for strongs
+#query(selector(strong), none)
+
+---
+// Error: 8-14 cannot query for text
+#query("text", none)
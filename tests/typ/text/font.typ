@@ -32,6 +32,7 @@ Emoji: 🐪, 🌋, 🏞
 
 // Disable font fallback beyond the user-specified list.
 // Without disabling, New Computer Modern Math would come to the rescue.
+// Warning: 17-51 unknown font family: twitter color emoji
 #set text(font: ("PT Sans", "Twitter Color Emoji"), fallback: false)
 2π = 𝛼 + 𝛽. ✅
 
@@ -0,0 +1,68 @@
+// Test gradients.
+
+---
+// Test construction and methods.
+// Ref: false
+#let g = gradient.linear(red, blue)
+#test(g.kind(), "linear")
+#test(g.stops(), ((red, 0%), (blue, 100%)))
+#test(g.space(), "oklab")
+#test(g.relative(), auto)
+#test(g.angle(), 0deg)
+#test(g.sample(0%), red)
+#test(g.sample(100%), blue)
+#test(type(g), "gradient")
+
+---
+// Test evenly distributed stops.
+// Ref: false
+#test(
+  gradient.conic(red, green, blue).stops(),
+  ((red, 0%), (green, 50%), (blue, 100%)),
+)
+
+---
+// Test radial gradients.
+// Ref: false
+#let g = gradient.radial(white, black, center: (20%, 30%), radius: 80%)
+#test(g.kind(), "radial")
+#test(g.angle(), none)
+#test(
+  repr(g),
+  "gradient.radial((rgb(\"#ffffff\"), 0%), (rgb(\"#000000\"), 100%), center: (20%, 30%), radius: 80%)",
+)
+
+---
+// Test sampling in sRGB.
+// Ref: false
+#let g = gradient.linear(black, white, space: "srgb")
+#test(g.sample(50%), rgb(128, 128, 128))
+#test(g.space(), "srgb")
+
+---
+// Test usage as paint.
+#rect(fill: gradient.linear(red, blue, angle: 45deg))
+#text(fill: gradient.linear(red, blue, relative: "self"))[Hello]
+#line(length: 100%, stroke: 2pt + gradient.conic(red, blue, red))
+#circle(stroke: gradient.radial(red, blue))
+#test(type(2pt + gradient.linear(red, blue)), "stroke")
+
+---
+// Error: 17-22 a gradient must have at least two stops
+#gradient.linear(red)
+
+---
+// Error: 17-34 either all or no stops must have an offset
+#gradient.linear((red, 0%), blue)
+
+---
+// Error: 17-42 offsets must be in ascending order
+#gradient.linear((red, 50%), (blue, 20%))
+
+---
+// Error: 18-30 a color stop must contain exactly two entries
+#gradient.linear((red, 0%, 1), blue)
+
+---
+// Error: 36-42 expected "oklab", "srgb", or "linear-rgb"
+#gradient.linear(red, blue, space: "cmyk")
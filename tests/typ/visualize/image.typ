@@ -54,9 +54,10 @@ A #box(image("/tiger.jpg", height: 1cm, width: 80%)) B
 #image("path/does/not/exist")
 
 ---
-// Error: 8-21 unknown image format
+// Error: 2-22 unknown image format
 #image("./image.typ")
 
 ---
-// Error: 8-18 failed to parse svg: found closing tag 'g' instead of 'style' in line 4
+// Error: 2-19 failed to parse svg: found closing tag 'g' instead of 'style' in line 4
 #image("/bad.svg")
+
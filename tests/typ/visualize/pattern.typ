@@ -0,0 +1,45 @@
+// Test patterns.
+
+---
+// Test construction and methods.
+// Ref: false
+#let p = pattern(size: (10pt, 20pt), spacing: (2pt, 0pt), relative: "parent")[x]
+#test(type(p), "pattern")
+#test(p.size(), (10pt, 20pt))
+#test(p.spacing(), (2pt, 0pt))
+#test(p.relative(), "parent")
+#test(repr(p), "pattern(size: (10pt, 20pt), spacing: (2pt, 0pt), relative: \"parent\", ..)")
+
+---
+// Test that the tile takes on the size of its content by default.
+// Ref: false
+#let p = pattern(rect(width: 5pt, height: 8pt))
+#test(p.size(), (5pt, 8pt))
+#test(p.spacing(), (0pt, 0pt))
+#test(p.relative(), auto)
+
+---
+// Test usage as paint.
+#let hatched = pattern(size: (6pt, 6pt), line(start: (0%, 100%), end: (100%, 0%)))
+#rect(width: 100%, fill: hatched)
+#circle(stroke: 3pt + hatched)
+#table(columns: 2, fill: hatched, [A], [B])
+#text(fill: hatched)[*Hatched*]
+#test(type(2pt + hatched), "stroke")
+
+---
+// Test patterns within patterns.
+#let inner = pattern(size: (2pt, 2pt), square(size: 1pt, fill: red))
+#rect(fill: pattern(size: (10pt, 10pt), square(size: 5pt, fill: inner)))
+
+---
+// Error: 9-11 pattern tile size must be non-zero
+#pattern[]
+
+---
+// Error: 19-34 point array must contain exactly two entries
+#pattern(spacing: (1pt, 2pt, 3pt))[A]
+
+---
+// Error: 9-55 spacing must not make the tile size negative
+#pattern(size: (10pt, 10pt), spacing: (-10pt, 0pt))[A]
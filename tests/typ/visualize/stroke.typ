@@ -0,0 +1,48 @@
+// Test dash patterns, line caps, line joins and miter limits.
+
+---
+// Test stroke dictionaries with dash patterns and caps.
+#set line(length: 60pt)
+#stack(
+  spacing: 4pt,
+  line(stroke: (paint: red, thickness: 2pt, cap: "round")),
+  line(stroke: (thickness: 2pt, cap: "square", dash: "dotted")),
+  line(stroke: (paint: blue, dash: "dash-dotted")),
+  line(stroke: (dash: ("dot", 2pt, 4pt, 2pt))),
+  line(stroke: (dash: (array: (3pt, 1pt), phase: 1pt))),
+  line(stroke: (dash: "solid")),
+  line(stroke: (dash: none)),
+)
+
+---
+// Test joins and miter limits on shapes and tables.
+#polygon(
+  stroke: (thickness: 4pt, join: "miter", miter-limit: 2),
+  (0pt, 0pt), (20pt, 5pt), (0pt, 10pt),
+)
+#polygon(stroke: (thickness: 4pt, join: "bevel"), (0pt, 0pt), (20pt, 5pt), (0pt, 10pt))
+#rect(stroke: (thickness: 2pt, join: "round", dash: "densely-dashed"))
+#rect(stroke: (top: (dash: "loosely-dotted"), rest: 1pt))
+#ellipse(stroke: (paint: green, dash: "loosely-dash-dotted"))
+#table(columns: 2, stroke: (dash: "dotted"), [A], [B])
+
+---
+// Test the representation of strokes.
+// Ref: false
+#test(repr(2pt + red), "2pt + rgb(\"#ff4136\")")
+
+---
+// Error: 15-39 unexpected key "foo", valid keys are "paint", "thickness", "cap", "join", "dash", and "miter-limit"
+#line(stroke: (paint: red, foo: "bar"))
+
+---
+// Error: 15-30 expected "butt", "round", or "square"
+#line(stroke: (cap: "pointy"))
+
+---
+// Error: 15-29 expected "dot" or length, found integer
+#line(stroke: (dash: (1, 2)))
+
+---
+// Error: 15-47 unexpected key "offset", valid keys are "array" and "phase"
+#line(stroke: (dash: (offset: 1pt, array: ())))